@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use crate::{Error, Result};
+
+/// Known good answers for a day's input, written as `<part>: <answer>` lines
+///
+/// An answer spanning several lines, like a rendered screen, leaves the value empty and follows with
+/// one `|` prefixed line per answer line. Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers(Vec<(u8, String)>);
+
+impl Answers {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut answers: Vec<(u8, String)> = vec![];
+        let mut open = false;
+        for (i, line) in content.lines().enumerate() {
+            if let Some(rest) = line.strip_prefix('|') {
+                match answers.last_mut() {
+                    Some((_, answer)) if open => {
+                        if !answer.is_empty() {
+                            answer.push('\n');
+                        }
+                        answer.push_str(rest);
+                    },
+                    _ => return Err(Error::parse(i, line, "Continuation line without a multi-line answer"))
+                }
+                continue;
+            }
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                open = false;
+                continue;
+            }
+            let (part, answer) = trimmed.split_once(':')
+                .ok_or_else(|| Error::parse(i, line, "Expected '<part>: <answer>'"))?;
+            let part: u8 = crate::parse_field(part, i, line)?;
+            if answers.iter().any(|(p, _)| *p == part) {
+                return Err(Error::parse(i, line, format!("Part {} is recorded twice", part)));
+            }
+            open = answer.trim().is_empty();
+            answers.push((part, answer.trim().to_string()));
+        }
+        Ok(Answers(answers))
+    }
+
+    pub fn get(&self, part: u8) -> Option<&str> {
+        self.0.iter()
+            .find(|(p, _)| *p == part)
+            .map(|(_, answer)| answer.as_str())
+    }
+
+    /// Records `answer`, replacing any previous answer for the part
+    pub fn set(&mut self, part: u8, answer: impl ToString) {
+        let answer = answer.to_string();
+        match self.0.iter_mut().find(|(p, _)| *p == part) {
+            Some((_, previous)) => *previous = answer,
+            None => self.0.push((part, answer))
+        }
+    }
+}
+
+impl Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (part, answer) in &self.0 {
+            if answer.contains('\n') {
+                writeln!(f, "{}:", part)?;
+                for line in answer.lines() {
+                    writeln!(f, "|{}", line)?;
+                }
+            } else {
+                writeln!(f, "{}: {}", part, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_and_multi_line_answers() {
+        let content = "# Day 10\n1: 13140\n2:\n|##..\n|#..#\n";
+        let answers = Answers::parse(content).unwrap();
+        assert_eq!(answers.get(1), Some("13140"));
+        assert_eq!(answers.get(2), Some("##..\n#..#"));
+        assert_eq!(answers.get(3), None);
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers)
+    }
+
+    #[test]
+    fn reject_malformed_answers() {
+        assert!(matches!(Answers::parse("1: 5\n|#..#"), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(Answers::parse("one: 5"), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(Answers::parse("1: 5\n1: 6"), Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(Answers::parse("66306"), Err(Error::Parse { line: 1, .. })))
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input couldn't be read
+    Io(std::io::Error),
+    /// A line of the puzzle input couldn't be understood, `line` and `column` count from 1
+    Parse {
+        line: usize,
+        column: usize,
+        content: String,
+        reason: String
+    },
+    /// The input was understood but the puzzle can't be solved from it
+    InvalidState(String)
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Parse error for a whole line, `line_index` counts from 0 as `enumerate` does
+    pub fn parse(line_index: usize, content: &str, reason: impl Into<String>) -> Self {
+        Self::parse_at(line_index, 0, content, reason)
+    }
+
+    /// Parse error at a position in the line, both indexes count from 0
+    pub fn parse_at(line_index: usize, column_index: usize, content: &str, reason: impl Into<String>) -> Self {
+        Error::Parse {
+            line: line_index + 1,
+            column: column_index + 1,
+            content: content.to_string(),
+            reason: reason.into()
+        }
+    }
+
+    pub fn invalid_state(reason: impl Into<String>) -> Self {
+        Error::InvalidState(reason.into())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(er) => write!(f, "{}", er),
+            Error::Parse { line, column, content, reason } => {
+                write!(f, "{} at line {}, column {}: '{}'", reason, line, column, content)
+            },
+            Error::InvalidState(reason) => write!(f, "{}", reason)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(er) => Some(er),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(er: std::io::Error) -> Self {
+        Error::Io(er)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,102 @@
+use crate::{Error, Puzzle, Result};
+
+/// Small deterministic random source (SplitMix64), the same seed always generates the same input
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`, `bound` must not be 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Empty range");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// Uniform value in `low..=high`
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "Empty range {}..={}", low, high);
+        let span = (high - low) as u128 + 1;
+        low + ((self.next_u64() as u128 * span) >> 64) as i64
+    }
+
+    /// True `percent` times out of a hundred
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Synthetic puzzle input, along with the answers known without running the solvers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>
+}
+
+impl Generated {
+    pub fn new(input: String, part1: Option<String>, part2: Option<String>) -> Self {
+        Generated { input, part1, part2 }
+    }
+
+    /// Input for which both answers were computed independently
+    pub fn solved(input: String, part1: impl ToString, part2: impl ToString) -> Self {
+        Self::new(input, Some(part1.to_string()), Some(part2.to_string()))
+    }
+
+    /// Solves the input with `P`, failing on the first answer differing from the known one
+    pub fn check<P: Puzzle>(&self) -> Result<()> {
+        let input = P::parse(&self.input)?;
+        if let Some(expected) = &self.part1 {
+            let answer = P::part1(&input)?.to_string();
+            if answer != *expected {
+                return Err(Error::invalid_state(format!("Part 1 answered {}, expected {}", answer, expected)));
+            }
+        }
+        if let Some(expected) = &self.part2 {
+            let answer = P::part2(&input)?.to_string();
+            if answer != *expected {
+                return Err(Error::invalid_state(format!("Part 2 answered {}, expected {}", answer, expected)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_sequences() {
+        let mut rng = Rng::new(7);
+        let first: Vec<_> = (0..16).map(|_| rng.between(-3, 3)).collect();
+        assert_eq!(first, (0..16).scan(Rng::new(7), |rng, _| Some(rng.between(-3, 3))).collect::<Vec<_>>());
+        assert!(first.iter().all(|v| (-3..=3).contains(v)));
+        assert!((0..1000).all(|_| rng.below(5) < 5));
+        let mut items: Vec<_> = (0..10).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>())
+    }
+}
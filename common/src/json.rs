@@ -0,0 +1,161 @@
+use std::fmt::Display;
+
+/// JSON value for the machine readable reports, objects keep their keys in insertion order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// Integers are kept as their decimal digits, so any width fits
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+
+impl Json {
+    pub fn object() -> Self {
+        Json::Object(vec![])
+    }
+
+    /// Adds `key` to an object, replacing any previous value
+    pub fn with(mut self, key: &str, value: impl Into<Json>) -> Self {
+        if let Json::Object(entries) = &mut self {
+            let value = value.into();
+            match entries.iter_mut().find(|(k, _)| k == key) {
+                Some((_, previous)) => *previous = value,
+                None => entries.push((key.to_string(), value))
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None
+        }
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Compact JSON text, on a single line
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+macro_rules! json_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Json {
+                fn from(n: $t) -> Self {
+                    Json::Number(n.to_string())
+                }
+            }
+        )*
+    };
+}
+
+json_number!(i32, i64, i128, isize, u8, u32, u64, u128, usize);
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Json::Bool(b)
+    }
+}
+
+impl From<char> for Json {
+    fn from(c: char) -> Self {
+        Json::String(c.to_string())
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(items: Vec<T>) -> Self {
+        items.into_iter().collect()
+    }
+}
+
+impl<T: Into<Json>> FromIterator<T> for Json {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Json::Array(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_nested_values() {
+        let json = Json::object()
+            .with("day", 7)
+            .with("answer", 1_569_054_441_243_u128)
+            .with("top", vec!["a\"b", "c\nd"])
+            .with("missing", None::<u32>)
+            .with("day", 8);
+        assert_eq!(json.to_string(), r#"{"day":8,"answer":1569054441243,"top":["a\"b","c\nd"],"missing":null}"#);
+        assert_eq!(json.get("day"), Some(&Json::from(8)));
+        assert_eq!(Json::from(vec![Json::Null, true.into()]).to_string(), "[null,true]")
+    }
+}
@@ -0,0 +1,84 @@
+mod answers;
+mod error;
+mod generator;
+mod json;
+mod puzzle;
+pub mod trace;
+
+pub use answers::Answers;
+pub use error::{Error, Result};
+pub use generator::{Generated, Rng};
+pub use json::Json;
+pub use puzzle::Puzzle;
+
+use std::{io::BufRead, str::FromStr};
+
+pub fn read_input(input_path: &str) -> Result<String> {
+    Ok(std::fs::read_to_string(input_path)?)
+}
+
+/// Reads the whole puzzle input from a stream, e.g. stdin or a socket
+pub fn read_from(mut reader: impl BufRead) -> Result<String> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Parses `field`, a slice of `line`, reporting the field's column if it isn't a valid `T`
+pub fn parse_field<T: FromStr>(field: &str, line_index: usize, line: &str) -> Result<T> {
+    field.trim().parse()
+        .map_err(
+            |_| {
+                let column = (field.as_ptr() as usize)
+                    .checked_sub(line.as_ptr() as usize)
+                    .filter(|offset| *offset < line.len())
+                    .unwrap_or(0);
+                Error::parse_at(line_index, column, line, format!("Invalid value '{}'", field.trim()))
+            }
+        )
+}
+
+/// Whitespace separated tokens of the line, with the byte index each starts at
+pub fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        if !c.is_whitespace() {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            tokens.push((s, &line[s..i]));
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_field_reports_column() {
+        let line = "move 1 from x to 3";
+        let field = line.split_whitespace().nth(3).unwrap();
+        match parse_field::<usize>(field, 4, line) {
+            Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (5, 13)),
+            res => panic!("Expected parse error, got {:?}", res)
+        }
+    }
+
+    #[test]
+    fn read_from_stream() {
+        assert_eq!(read_from("1000\n2000\n".as_bytes()).unwrap(), "1000\n2000\n")
+    }
+
+    #[test]
+    fn parse_field_accepts_padded_values() {
+        assert_eq!(parse_field::<i64>(" -42 ", 0, " -42 ").unwrap(), -42)
+    }
+
+    #[test]
+    fn tokens_keep_their_index() {
+        assert_eq!(tokens(" A  Yy\tC "), [(1, "A"), (4, "Yy"), (7, "C")]);
+        assert!(tokens("   ").is_empty())
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fmt::Display, io::BufRead};
+
+use crate::{Json, Result};
+
+/// A day's puzzle split into its stages, so a parsed input can be reused by both parts
+pub trait Puzzle {
+    type Input;
+    type Answer1: Display + Into<Json>;
+    type Answer2: Display + Into<Json>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2>;
+
+    /// Shape of the parsed input for the reports, counts and sizes rather than the whole input
+    fn summary(_input: &Self::Input) -> Json {
+        Json::Null
+    }
+
+    /// Intermediate results behind the answers for the reports, e.g. a final layout or the order things happened in
+    fn artefacts(_input: &Self::Input) -> Result<Json> {
+        Ok(Json::Null)
+    }
+
+    /// Both answers along with the input summary and the artefacts, anything failing reports its error instead
+    fn report(input: &Self::Input) -> Json {
+        let part = |part: u8, answer: Result<Json>| match answer {
+            Ok(answer) => Json::object().with("part", part).with("answer", answer),
+            Err(er) => Json::object().with("part", part).with("error", er.to_string())
+        };
+        Json::object()
+            .with("summary", Self::summary(input))
+            .with(
+                "parts",
+                vec![part(1, Self::part1(input).map(Into::into)), part(2, Self::part2(input).map(Into::into))]
+            )
+            .with(
+                "artefacts",
+                Self::artefacts(input).unwrap_or_else(|er| Json::object().with("error", er.to_string()))
+            )
+    }
+
+    fn parse_file(input_path: &str) -> Result<Self::Input> {
+        Self::parse(&crate::read_input(input_path)?)
+    }
+
+    fn parse_reader(reader: impl BufRead) -> Result<Self::Input> {
+        Self::parse(&crate::read_from(reader)?)
+    }
+
+    fn solve_part1(input: &str) -> Result<Self::Answer1> {
+        Self::part1(&Self::parse(input)?)
+    }
+
+    fn solve_part2(input: &str) -> Result<Self::Answer2> {
+        Self::part2(&Self::parse(input)?)
+    }
+}
@@ -0,0 +1,149 @@
+use std::{cell::{Cell, RefCell}, fmt::Display, rc::Rc, str::FromStr};
+
+/// Detail of an event, observing a level lets every coarser level through too
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// A few events per step of a solver, e.g. once per minute or per packet pair
+    Debug,
+    /// Events from the innermost loops, e.g. every search state
+    Trace
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("Unknown level '{}'", s))
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace")
+        }
+    }
+}
+
+/// Something a solver went through, reported to the observer of the current thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    StateVisited { state: String, time_left: u32, score: u32 },
+    RockPlaced { rock: usize, x: isize, y: isize, height: usize },
+    /// The tower grows by `growth` every `length` rocks, from the `first` rock on
+    PatternFound { first: usize, length: usize, growth: u128 },
+    MonkeyThrow { round: usize, monkey: usize, worry: i64, to: usize },
+    /// Minutes are counted from the start of the current trip
+    BlizzardStep { minute: u64, reachable: usize },
+    /// Packet pairs are counted from 1
+    PacketsCompared { pair: usize, ordered: bool }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::StateVisited { state, time_left, score } => {
+                write!(f, "visited {}, {} minutes left, score {}", state, time_left, score)
+            },
+            Event::RockPlaced { rock, x, y, height } => {
+                write!(f, "rock {} placed at ({}, {}), tower {} high", rock, x, y, height)
+            },
+            Event::PatternFound { first, length, growth } => {
+                write!(f, "from rock {} the tower grows {} every {} rocks", first, growth, length)
+            },
+            Event::MonkeyThrow { round, monkey, worry, to } => {
+                write!(f, "round {}, monkey {} throws worry {} to monkey {}", round, monkey, worry, to)
+            },
+            Event::BlizzardStep { minute, reachable } => {
+                write!(f, "minute {}, {} squares reachable", minute, reachable)
+            },
+            Event::PacketsCompared { pair, ordered } => {
+                write!(f, "pair {} is {}", pair, if *ordered { "in order" } else { "out of order" })
+            }
+        }
+    }
+}
+
+type Observer = Box<dyn FnMut(Level, &Event)>;
+
+thread_local! {
+    static LEVEL: Cell<Option<Level>> = const { Cell::new(None) };
+    static OBSERVER: RefCell<Option<Observer>> = const { RefCell::new(None) };
+}
+
+pub fn enabled(level: Level) -> bool {
+    LEVEL.with(|observed| observed.get().is_some_and(|observed| level <= observed))
+}
+
+/// Hands the event to the observer if it takes `level`, the event is only built then
+///
+/// Without an observer this is a single thread local read, so solvers can emit from their hot loops.
+#[inline]
+pub fn emit(level: Level, event: impl FnOnce() -> Event) {
+    if enabled(level) {
+        let event = event();
+        OBSERVER.with(
+            |observer| if let Some(observer) = observer.borrow_mut().as_mut() {
+                observer(level, &event)
+            }
+        );
+    }
+}
+
+/// Puts the previous observer back when dropped, even if the observed code panicked
+struct Restore(Option<Level>, Option<Observer>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        LEVEL.with(|level| level.set(self.0));
+        OBSERVER.with(|observer| *observer.borrow_mut() = self.1.take());
+    }
+}
+
+/// Runs `f` with `observer` receiving the events up to `level` emitted on this thread
+pub fn observe<T>(level: Level, observer: impl FnMut(Level, &Event) + 'static, f: impl FnOnce() -> T) -> T {
+    let previous_level = LEVEL.with(|previous| previous.replace(Some(level)));
+    let previous = OBSERVER.with(|previous| previous.borrow_mut().replace(Box::new(observer)));
+    let _restore = Restore(previous_level, previous);
+    f()
+}
+
+/// Runs `f` and returns the events up to `level` it emitted, mostly for tests
+pub fn collect<T>(level: Level, f: impl FnOnce() -> T) -> (T, Vec<Event>) {
+    let events = Rc::new(RefCell::new(vec![]));
+    let sink = Rc::clone(&events);
+    let result = observe(level, move |_, event| sink.borrow_mut().push(event.clone()), f);
+    let events = events.take();
+    (result, events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(minute: u64) {
+        emit(Level::Debug, || Event::BlizzardStep { minute, reachable: 1 });
+        emit(Level::Trace, || Event::StateVisited { state: String::from("AA"), time_left: 30, score: 0 });
+    }
+
+    #[test]
+    fn filter_by_level() {
+        let (_, events) = collect(Level::Debug, || step(1));
+        assert_eq!(events, [Event::BlizzardStep { minute: 1, reachable: 1 }]);
+        let (_, events) = collect(Level::Trace, || step(2));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].to_string(), "visited AA, 30 minutes left, score 0")
+    }
+
+    #[test]
+    fn unobserved_events_are_never_built() {
+        emit(Level::Debug, || panic!("Built without an observer"));
+        collect(Level::Debug, || emit(Level::Trace, || panic!("Built above the observed level")));
+        assert!(!enabled(Level::Debug))
+    }
+}
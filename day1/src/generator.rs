@@ -0,0 +1,37 @@
+use common::{Generated, Rng};
+
+/// Inventories of `size` elves (at least 3), answers found by sorting the totals
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let elves: Vec<Vec<u32>> = (0..size.max(3))
+        .map(
+            |_| {
+                (0..rng.between(1, 12))
+                    .map(|_| rng.between(1_000, 65_000) as u32)
+                    .collect()
+            }
+        )
+        .collect();
+
+    let mut totals: Vec<u32> = elves.iter().map(|items| items.iter().sum()).collect();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    let input = elves.iter()
+        .map(
+            |items| items.iter().map(|cal| cal.to_string()).collect::<Vec<_>>().join("\n")
+        )
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Generated::solved(input, totals[0], totals[..3].iter().sum::<u32>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day1;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 50).check::<Day1>().unwrap();
+        }
+    }
+}
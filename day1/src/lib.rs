@@ -1,69 +1,275 @@
-pub fn find_elf_carrying_most(input_path: &str) -> Option<(usize, u32)> {
-    let mut elf_ind = 0;
-    let mut elf_ind_max = 0;
-    let mut elf_backpack = 0;
-
-    let mut elf_end_count = |cal_count: &mut u32| {
-        if *cal_count > elf_backpack {
-            elf_ind_max = elf_ind;
-            elf_backpack = *cal_count;
+pub mod generator;
+
+use common::{Error, Json, Puzzle, Result};
+
+/// Elf index, counted from 0 in input order, and the calories it carries
+pub type ElfLoad = (usize, u64);
+
+/// How lines that aren't a calorie count are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Only blank lines separate elves, anything else that isn't a count is an error
+    Strict,
+    /// Any line that isn't a count separates elves, the way the puzzle was first solved, so separators
+    /// in a row or ending the file count elves carrying nothing
+    Lenient
+}
+
+/// Items carried by each elf, in the order the elves appear
+///
+/// Elves are separated by blank lines, a run of several blank lines or one ending the file doesn't
+/// make an elf without items, except when parsed leniently.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CalorieLedger(Vec<Vec<u32>>);
+
+impl CalorieLedger {
+    pub fn new(elves: Vec<Vec<u32>>) -> Self {
+        CalorieLedger(elves.into_iter().filter(|items| !items.is_empty()).collect())
+    }
+
+    pub fn elves(&self) -> &[Vec<u32>] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Calories carried by `elf`
+    pub fn total(&self, elf: usize) -> Option<u64> {
+        self.0.get(elf).map(|items| items.iter().map(|cal| *cal as u64).sum())
+    }
+
+    /// Calories carried by every elf, in input order
+    pub fn totals(&self) -> Vec<u64> {
+        (0..self.len()).filter_map(|elf| self.total(elf)).collect()
+    }
+
+    pub fn grand_total(&self) -> u64 {
+        self.totals().iter().sum()
+    }
+
+    /// Every load, heaviest first, elves carrying the same coming in input order
+    fn ranked(&self) -> Vec<ElfLoad> {
+        let mut loads: Vec<ElfLoad> = self.totals().into_iter().enumerate().collect();
+        loads.sort_by(|(a, a_cal), (b, b_cal)| b_cal.cmp(a_cal).then(a.cmp(b)));
+        loads
+    }
+
+    /// The `n` heaviest loads, heaviest first
+    pub fn top(&self, n: usize) -> Result<Vec<ElfLoad>> {
+        if n > self.len() {
+            return Err(Error::invalid_state(format!("Expected at least {} elves, found {}", n, self.len())));
         }
-        elf_ind = elf_ind + 1;
-        *cal_count = 0;
-    };
-    
-    if let Ok(input) = std::fs::read_to_string(input_path) {
-        let mut cal_count = 0;
-        for food_item in input.split_terminator("\n") {
-            if let Ok(cal) = food_item.trim().parse::<u32>() {
-                cal_count = cal_count + cal;
-            } else {
-                elf_end_count(&mut cal_count);
-            }
+        let mut loads = self.ranked();
+        loads.truncate(n);
+        Ok(loads)
+    }
+
+    /// Rank of `elf` from 1 for the heaviest load, elves carrying the same share a rank
+    pub fn rank(&self, elf: usize) -> Option<usize> {
+        let calories = self.total(elf)?;
+        Some(1 + self.totals().iter().filter(|cal| **cal > calories).count())
+    }
+
+    /// Load below which `percent` of the loads are, by the nearest rank, `None` without elves
+    pub fn percentile(&self, percent: u8) -> Option<u64> {
+        if self.is_empty() || percent > 100 {
+            return None;
         }
-        elf_end_count(&mut cal_count);
-        Some((elf_ind_max, elf_backpack))
-    } else { None }
+        let mut totals = self.totals();
+        totals.sort_unstable();
+        let rank = (percent as usize * totals.len()).div_ceil(100).max(1);
+        Some(totals[rank - 1])
+    }
+
+    pub fn median(&self) -> Option<u64> {
+        self.percentile(50)
+    }
 
+    /// Elves counted per `width` calories, every bucket from the lightest load's to the heaviest's
+    ///
+    /// Buckets are given by the lowest load they hold, a multiple of `width`.
+    pub fn histogram(&self, width: u64) -> Result<Vec<(u64, usize)>> {
+        if width == 0 {
+            return Err(Error::invalid_state("Histogram buckets can't be empty"));
+        }
+        let totals = self.totals();
+        let (Some(lightest), Some(heaviest)) = (totals.iter().min(), totals.iter().max()) else {
+            return Ok(vec![]);
+        };
+        let (first, last) = (lightest / width, heaviest / width);
+        let mut buckets: Vec<(u64, usize)> = (first..=last).map(|bucket| (bucket * width, 0)).collect();
+        for total in totals {
+            buckets[(total / width - first) as usize].1 += 1;
+        }
+        Ok(buckets)
+    }
 }
 
-pub fn find_top_three_elfs_carrying_most(input_path: &str) -> Option<((usize, u32), (usize, u32), (usize, u32))> {
-    if let Ok(input) = std::fs::read_to_string(input_path) {
-        let cals = input.split_terminator("\n")
-            .into_iter()
-            .map(
-                |c| {
-                    c.trim().parse::<u32>()
-                }
-            );
-        let mut elf_ind: usize = 0;
-        let mut elf_backpack = 0;
-        let mut elfs = vec![];
-        for cal in cals {
-            match cal {
-                Ok(cal) => {
-                    elf_backpack = elf_backpack + cal
-                },
-                Err(_) => {
-                    let ind = elfs.binary_search_by_key(
-                        &elf_backpack,
-                        |(_, kcal)| { *kcal }
-                    );
-                    match ind {
-                        Ok(ind) => elfs.insert(ind + 1,(elf_ind, elf_backpack)),
-                        Err(ind) => elfs.insert(ind, (elf_ind, elf_backpack))
-                    }
-                    elf_ind = elf_ind + 1;
-                    elf_backpack = 0;
-                }
-            }
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    type Input = CalorieLedger;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_ledger(input, ParseMode::Strict)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(input.top(1)?[0].1)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(input.top(3)?.iter().map(|(_, calories)| calories).sum())
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("elves", input.len())
+            .with("items", input.elves().iter().map(Vec::len).sum::<usize>())
+            .with("calories", input.grand_total())
+    }
+
+    /// The three heaviest loads as elf index and calories, heaviest first, and the median load
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        Ok(
+            Json::object()
+                .with(
+                    "top_three",
+                    input.top(3)?
+                        .iter()
+                        .map(|(elf, calories)| Json::object().with("elf", *elf).with("calories", *calories))
+                        .collect::<Json>()
+                )
+                .with("median", input.median())
+        )
+    }
+}
+
+/// Lines that are neither a calorie count nor blank, each as the error strict parsing reports for it
+pub fn malformed_lines(input: &str) -> Vec<Error> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| common::parse_field::<u32>(line, i, line).err())
+        .collect()
+}
+
+pub fn parse_ledger(input: &str, mode: ParseMode) -> Result<CalorieLedger> {
+    if mode == ParseMode::Strict {
+        if let Some(er) = malformed_lines(input).into_iter().next() {
+            return Err(er);
         }
-        if let (_, [a, b, c]) = elfs.split_at(elfs.len() - 3) {
-            Some((*a, *b, *c))
+    }
+    let mut elves = vec![];
+    let mut items = vec![];
+    for food_item in input.lines() {
+        if let Ok(cal) = food_item.trim().parse::<u32>() {
+            items.push(cal);
         } else {
-            None
+            elves.push(std::mem::take(&mut items));
         }
-    } else {
-        None
     }
-}
\ No newline at end of file
+    elves.push(items);
+    match mode {
+        ParseMode::Strict => Ok(CalorieLedger::new(elves)),
+        ParseMode::Lenient => Ok(CalorieLedger(elves))
+    }
+}
+
+pub fn find_elf_carrying_most(input_path: &str) -> Result<ElfLoad> {
+    Ok(Day1::parse_file(input_path)?.top(1)?[0])
+}
+
+/// The three heaviest loads, lightest of them first
+pub fn find_top_three_elfs_carrying_most(input_path: &str) -> Result<(ElfLoad, ElfLoad, ElfLoad)> {
+    let top = Day1::parse_file(input_path)?.top(3)?;
+    Ok((top[2], top[1], top[0]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INP: &str =
+r#"1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000"#;
+
+    #[test]
+    fn test_input() {
+        assert_eq!(Day1::solve_part1(TEST_INP).unwrap(), 24000)
+    }
+
+    #[test]
+    fn test_input_part2() {
+        assert_eq!(Day1::solve_part2(TEST_INP).unwrap(), 45000)
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        let ledger = Day1::parse(TEST_INP).unwrap();
+        assert_eq!(Day1::parse(&format!("{}\n", TEST_INP)).unwrap(), ledger);
+        assert_eq!(Day1::parse(&format!("{}\n\n\n", TEST_INP)).unwrap(), ledger);
+        assert_eq!(Day1::parse(&TEST_INP.replace("\n\n", "\n\n\n")).unwrap(), ledger);
+        assert_eq!(ledger.len(), 5)
+    }
+
+    #[test]
+    fn test_queries() {
+        let ledger = Day1::parse(TEST_INP).unwrap();
+        assert_eq!(ledger.top(4).unwrap(), [(3, 24000), (2, 11000), (4, 10000), (0, 6000)]);
+        assert!(ledger.top(6).is_err());
+        assert_eq!((ledger.rank(3), ledger.rank(1), ledger.rank(5)), (Some(1), Some(5), None));
+        assert_eq!((ledger.grand_total(), ledger.median(), ledger.percentile(100)), (55000, Some(10000), Some(24000)));
+        assert_eq!(ledger.histogram(10000).unwrap(), [(0, 2), (10000, 2), (20000, 1)]);
+        assert!(ledger.histogram(0).is_err())
+    }
+
+    #[test]
+    fn test_malformed_lines() {
+        let input = TEST_INP.replace("2000", "2a00").replace("8000", "8000000000");
+        assert!(matches!(Day1::parse(&input), Err(Error::Parse { line: 2, .. })));
+        let lines: Vec<_> = malformed_lines(&input).iter()
+            .map(|er| match er {
+                Error::Parse { line, .. } => *line,
+                _ => 0
+            })
+            .collect();
+        assert_eq!(lines, [2, 11]);
+        // Leniently the bad lines split their elves, adding two
+        assert_eq!(parse_ledger(&input, ParseMode::Lenient).unwrap().len(), 7)
+    }
+
+    #[test]
+    fn test_lenient_empty_groups() {
+        let input = "1000\n\n\n2000\nx\n3000\n";
+        assert_eq!(parse_ledger(input, ParseMode::Lenient).unwrap().totals(), [1000, 0, 2000, 3000]);
+        assert_eq!(parse_ledger("1000\n\n", ParseMode::Lenient).unwrap().totals(), [1000, 0]);
+        assert_eq!(parse_ledger(&input.replace('x', ""), ParseMode::Strict).unwrap().totals(), [1000, 2000, 3000])
+    }
+
+    #[test]
+    fn test_wide_totals() {
+        let ledger = Day1::parse("4000000000\n4000000000\n\n1\n\n2").unwrap();
+        assert_eq!(Day1::part1(&ledger).unwrap(), 8_000_000_000)
+    }
+}
@@ -3,5 +3,8 @@ fn main() {
         "./day10/resources/input.txt",
         advent_of_code_2022_day10::default_probe
     );
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
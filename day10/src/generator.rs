@@ -0,0 +1,57 @@
+use common::{Generated, Rng};
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_CYCLES: usize = SCREEN_WIDTH * 6;
+
+/// Program of at least `size` instructions, long enough to draw the whole screen
+///
+/// The register is recorded for every cycle, then probed and drawn from that trace.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut program = vec![];
+    // Register value during each cycle, starting with cycle 1
+    let mut trace = vec![];
+    let mut x: i64 = 1;
+    while program.len() < size || trace.len() < SCREEN_CYCLES {
+        if rng.chance(30) {
+            program.push(String::from("noop"));
+            trace.push(x);
+        } else {
+            let add = rng.between(-x.min(10) - 1, (SCREEN_WIDTH as i64 - x).min(10));
+            program.push(format!("addx {}", add));
+            trace.extend([x, x]);
+            x += add;
+        }
+    }
+
+    let signal: i64 = trace.iter()
+        .enumerate()
+        .map(|(i, x)| (i + 1, x))
+        .filter(|(cycle, _)| (cycle + 20) % SCREEN_WIDTH == 0)
+        .map(|(cycle, x)| cycle as i64 * x)
+        .sum();
+    let screen = trace[..SCREEN_CYCLES].chunks(SCREEN_WIDTH)
+        .map(
+            |row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(column, x)| if (column as i64 - x).abs() <= 1 { '#' } else { '.' })
+                    .collect::<String>()
+            }
+        )
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(program.join("\n"), signal, screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day10;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), seed as usize * 20).check::<Day10>().unwrap();
+        }
+    }
+}
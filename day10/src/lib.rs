@@ -1,29 +1,67 @@
+pub mod generator;
+
+use common::{Error, Json, Puzzle, Result};
+
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    type Input = Vec<Instruction>;
+    type Answer1 = i32;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        compile_program(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(execute_prober(input, default_probe))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        render(input)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        let cycles: usize = input.iter()
+            .map(|inst| if let Instruction::AddX(_) = inst { 2 } else { 1 })
+            .sum();
+        Json::object()
+            .with("instructions", input.len())
+            .with("cycles", cycles)
+    }
+
+    /// CRT screen drawn in part 2, one string per row
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        Ok(Json::object().with("screen", render(input)?.lines().collect::<Json>()))
+    }
+}
+
 #[derive(Debug)]
-enum Instruction {
+pub enum Instruction {
     AddX(isize),
     NoOp
 }
 
 pub fn default_probe(clock_counter: usize) -> bool {
-    (clock_counter + 20) % 40 == 0
+    (clock_counter + 20).is_multiple_of(40)
 }
 
-fn compile_program(program_code: &str) -> Vec<Instruction> {
+fn compile_program(program_code: &str) -> Result<Vec<Instruction>> {
     program_code.lines()
+        .enumerate()
         .map(
-            |l| {
+            |(i, l)| {
                 match Vec::from_iter(l.split_whitespace()).as_slice() {
-                    ["addx", x] => Instruction::AddX(x.parse().unwrap()),
-                    ["noop"] => Instruction::NoOp,
-                    _ => panic!("Unknown instruction '{}'", l)
+                    ["addx", x] => Ok(Instruction::AddX(common::parse_field(x, i, l)?)),
+                    ["noop"] => Ok(Instruction::NoOp),
+                    _ => Err(Error::parse(i, l, "Unknown instruction"))
                 }
             }
         )
         .collect()
 }
 
-fn execute_prober(program_code: &str, probe_condition: fn(usize) -> bool) -> i32 {
-    let program = compile_program(program_code);
+pub fn execute_prober(program: &[Instruction], probe_condition: fn(usize) -> bool) -> i32 {
     program.iter()
         .fold(
             (1, 1, 0),
@@ -52,22 +90,14 @@ fn execute_prober(program_code: &str, probe_condition: fn(usize) -> bool) -> i32
     ).2 as i32
 }
 
-pub fn decode_cpu_clock(input_path: &str, probe_condition: fn(usize) -> bool) -> i32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => execute_prober(&content, probe_condition),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn decode_cpu_clock(input_path: &str, probe_condition: fn(usize) -> bool) -> Result<i32> {
+    Ok(execute_prober(&Day10::parse_file(input_path)?, probe_condition))
 }
 
-fn render(program_code: &str) -> String {
-    let program = compile_program(program_code);
+fn render(program: &[Instruction]) -> Result<String> {
     let mut program_counter = program.iter().peekable();
-    (0..(40 * 6)).into_iter()
-        .fold(
+    let screen = (0..(40 * 6))
+        .try_fold(
             (String::new(), &mut program_counter, 1_isize, false),
             |(screen, pc, reg_x, add_cycle), clock| {
                 let x_pos = clock % 40;
@@ -81,34 +111,29 @@ fn render(program_code: &str) -> String {
                 } else {
                     n_screen + "."
                 };
-                let cur_inst = pc.peek().unwrap();
+                let cur_inst = pc.peek()
+                    .ok_or_else(|| Error::invalid_state(format!("Program ended at cycle {} before the screen was drawn", clock + 1)))?;
                 match (cur_inst, add_cycle) {
                     (Instruction::AddX(x), true) => {
                         pc.next();
-                        (n_screen, pc, reg_x + x, false)
+                        Ok((n_screen, pc, reg_x + x, false))
                     },
                     (Instruction::AddX(_x), false) => {
-                        (n_screen, pc, reg_x, true)
+                        Ok((n_screen, pc, reg_x, true))
                     },
                     (Instruction::NoOp, false) => {
                         pc.next();
-                        (n_screen, pc, reg_x, false)
+                        Ok((n_screen, pc, reg_x, false))
                     },
-                    _ => panic!("CPU failure. '{:?} ({})' at {}", cur_inst, add_cycle, clock)
+                    _ => Err(Error::invalid_state(format!("CPU failure. '{:?} ({})' at {}", cur_inst, add_cycle, clock)))
                 }
             }
-        ).0
+        )?.0;
+    Ok(screen)
 }
 
-pub fn render_crt(input_path: &str) -> String {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => render(&content),
-        Err(er) => {
-            println!("{}", er);
-            String::new()
-        }
-    }
+pub fn render_crt(input_path: &str) -> Result<String> {
+    Day10::part2(&Day10::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -273,11 +298,16 @@ r#"##..##..##..##..##..##..##..##..##..##..
 
     #[test]
     fn test_input1() {
-        assert_eq!(execute_prober(TEST_INP1, default_probe), 13140)
+        assert_eq!(Day10::solve_part1(TEST_INP1).unwrap(), 13140)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(render(TEST_INP1), TEST_RENDER)
+        assert_eq!(Day10::solve_part2(TEST_INP1).unwrap(), TEST_RENDER)
+    }
+
+    #[test]
+    fn test_short_program() {
+        assert!(matches!(Day10::solve_part2("addx 15\nnoop"), Err(Error::InvalidState(_))))
     }
 }
\ No newline at end of file
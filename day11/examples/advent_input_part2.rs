@@ -4,5 +4,8 @@ fn main() {
         10_000,
         false
     );
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
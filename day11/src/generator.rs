@@ -0,0 +1,108 @@
+use common::{Generated, Rng};
+
+use crate::{RELIEF_ROUNDS, WORRY_ROUNDS};
+
+const DIVISORS: [u128; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+#[derive(Clone)]
+struct Monkey {
+    items: Vec<u128>,
+    operation: (char, Option<u128>),
+    divisor: u128,
+    targets: (usize, usize)
+}
+
+/// Busiest monkeys after `rounds`, `None` if a worry level outgrows the solver's integers
+///
+/// Worry levels are kept exact with relief, and only reduced modulo the least common multiple of the
+/// divisors without it.
+fn business(monkeys: &[Monkey], rounds: usize, relief: bool) -> Option<u128> {
+    let mut monkeys = monkeys.to_vec();
+    let modulus = monkeys.iter().fold(1, |lcm, m| lcm * m.divisor / gcd(lcm, m.divisor));
+    let mut inspected = vec![0; monkeys.len()];
+    for _ in 0..rounds {
+        for m in 0..monkeys.len() {
+            for item in std::mem::take(&mut monkeys[m].items) {
+                inspected[m] += 1;
+                let Monkey { operation: (op, operand), divisor, targets, .. } = &monkeys[m];
+                let operand = operand.unwrap_or(item);
+                let worry = match op {
+                    '+' => item.checked_add(operand),
+                    _ => item.checked_mul(operand)
+                }.filter(|worry| *worry <= i64::MAX as u128)?;
+                let worry = if relief { worry / 3 } else { worry % modulus };
+                let target = if worry % divisor == 0 { targets.0 } else { targets.1 };
+                monkeys[target].items.push(worry);
+            }
+        }
+    }
+    inspected.sort_unstable();
+    Some(inspected.iter().rev().take(2).product())
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Between 2 and 8 monkeys (`size`), each testing a different prime
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(2, DIVISORS.len());
+    let mut divisors = DIVISORS.to_vec();
+    rng.shuffle(&mut divisors);
+    let squaring = rng.chance(50).then(|| rng.below(count));
+
+    let monkeys: Vec<Monkey> = (0..count)
+        .map(
+            |id| {
+                let operation = if squaring == Some(id) {
+                    ('*', None)
+                } else if rng.chance(50) {
+                    ('+', Some(rng.between(1, 9) as u128))
+                } else {
+                    ('*', Some(rng.between(2, 19) as u128))
+                };
+                let mut others: Vec<usize> = (0..count).filter(|other| *other != id).collect();
+                rng.shuffle(&mut others);
+                Monkey {
+                    items: (0..rng.between(1, 6)).map(|_| rng.between(50, 99) as u128).collect(),
+                    operation,
+                    divisor: divisors[id],
+                    targets: (others[0], *others.last().unwrap())
+                }
+            }
+        )
+        .collect();
+
+    let input = monkeys.iter()
+        .enumerate()
+        .map(
+            |(id, m)| {
+                let items = m.items.iter().map(u128::to_string).collect::<Vec<_>>().join(", ");
+                let operand = m.operation.1.map_or(String::from("old"), |operand| operand.to_string());
+                format!(
+                    "Monkey {}:\n  Starting items: {}\n  Operation: new = old {} {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}",
+                    id, items, m.operation.0, operand, m.divisor, m.targets.0, m.targets.1
+                )
+            }
+        )
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Generated::new(
+        input,
+        business(&monkeys, RELIEF_ROUNDS, true).map(|b| b.to_string()),
+        business(&monkeys, WORRY_ROUNDS, false).map(|b| b.to_string())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day11;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..10 {
+            generate(&mut Rng::new(seed), 8).check::<Day11>().unwrap();
+        }
+    }
+}
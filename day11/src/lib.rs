@@ -1,6 +1,48 @@
+pub mod generator;
+
 use std::cell::RefCell;
 
-#[derive(Debug)]
+use common::{trace::{self, Event, Level}, Error, Json, Puzzle, Result};
+
+const RELIEF_ROUNDS: usize = 20;
+const WORRY_ROUNDS: usize = 10_000;
+
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Vec<Monkey>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_monkeys(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(monkey_bussiness(input, RELIEF_ROUNDS, true))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(monkey_bussiness(input, WORRY_ROUNDS, false))
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("monkeys", input.len())
+            .with("items", input.iter().map(|m| m.2.len()).sum::<usize>())
+    }
+
+    /// Items inspected by each monkey over each part's rounds
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        Ok(
+            Json::object()
+                .with("inspections_relief", inspections(input, RELIEF_ROUNDS, true))
+                .with("inspections_worry", inspections(input, WORRY_ROUNDS, false))
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
 enum Operation {
     AddI(i64),
     MultI(i64),
@@ -17,7 +59,7 @@ impl Operation {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Test(i64, usize, usize);
 
 impl Test {
@@ -30,89 +72,97 @@ impl Test {
     }
 }
 
-#[derive(Debug)]
-struct Monkey(usize, u64, Vec<i64>, Operation, Test);
+#[derive(Clone, Debug)]
+pub struct Monkey(usize, u64, Vec<i64>, Operation, Test);
 
-fn build_monkeys(monkey_desc: &str) -> Vec<RefCell<Monkey>> {
-    monkey_desc.lines()
-        .fold(
+fn build_monkeys(monkey_desc: &str) -> Result<Vec<Monkey>> {
+    let (monkeys, builder) = monkey_desc.lines()
+        .enumerate()
+        .try_fold(
             (vec![], (None, None, None, None, None)),
-            |(mut v, builder), l| {
+            |(mut v, builder), (line_index, l)| {
                 let l_spl = Vec::from_iter(l.split_whitespace());
                 match (l_spl.as_slice(), builder) {
                     ([], (None, None, None, None, None)) => {
-                        (v, (None, None, None, None, None))
+                        Ok((v, (None, None, None, None, None)))
                     },
                     (["Monkey", id], (None, None, None, None, None)) => {
-                        let p_id = id.trim_end_matches(":").parse::<usize>().unwrap();
-                        (v, (Some(p_id), None, None, None, None))
+                        let p_id = common::parse_field(id.trim_end_matches(":"), line_index, l)?;
+                        Ok((v, (Some(p_id), None, None, None, None)))
                     },
                     (["Starting", "items:", worries @ ..], (Some(m_id), None, None, None, None)) => {
-                        let worry_list: Vec<i64> = worries.iter().map(|w| w.trim_end_matches(",").parse().unwrap()).collect();
-                        (v, (Some(m_id), Some(worry_list), None, None, None))
+                        let worry_list: Vec<i64> = worries.iter().map(|w| common::parse_field(w.trim_end_matches(","), line_index, l)).collect::<Result<_>>()?;
+                        Ok((v, (Some(m_id), Some(worry_list), None, None, None)))
                     },
                     (["Operation:", "new", "=", "old", "+", i], (Some(m_id), Some(wr_v), None, None, None)) => {
-                        (v, (Some(m_id), Some(wr_v), Some(Operation::AddI(i.parse().unwrap())), None, None))
+                        Ok((v, (Some(m_id), Some(wr_v), Some(Operation::AddI(common::parse_field(i, line_index, l)?)), None, None)))
                     },
                     (["Operation:", "new", "=", "old", "*", "old"], (Some(m_id), Some(wr_v), None, None, None)) => {
-                        (v, (Some(m_id), Some(wr_v), Some(Operation::Pow2), None, None))
+                        Ok((v, (Some(m_id), Some(wr_v), Some(Operation::Pow2), None, None)))
                     },
                     (["Operation:", "new", "=", "old", "*", i], (Some(m_id), Some(wr_v), None, None, None)) => {
-                        (v, (Some(m_id), Some(wr_v), Some(Operation::MultI(i.parse().unwrap())), None, None))
+                        Ok((v, (Some(m_id), Some(wr_v), Some(Operation::MultI(common::parse_field(i, line_index, l)?)), None, None)))
                     },
                     (["Test:", "divisible", "by", i], (Some(m_id), Some(wr_v), Some(op), None, None)) => {
-                        (v, (Some(m_id), Some(wr_v), Some(op), Some(i.parse::<i64>().unwrap()), None))
+                        match common::parse_field(i, line_index, l)? {
+                            0 => Err(Error::parse(line_index, l, "Divisor can't be 0")),
+                            div => Ok((v, (Some(m_id), Some(wr_v), Some(op), Some(div), None)))
+                        }
                     },
                     (["If", "true:", "throw", "to", "monkey", i], (Some(m_id), Some(wr_v), Some(op), Some(div), None)) => {
-                        (v, (Some(m_id), Some(wr_v), Some(op), Some(div), Some(i.parse::<usize>().unwrap())))
+                        Ok((v, (Some(m_id), Some(wr_v), Some(op), Some(div), Some(common::parse_field(i, line_index, l)?))))
                     },
                     (["If", "false:", "throw", "to", "monkey", i], (Some(m_id), Some(wr_v), Some(op), Some(div), Some(t))) => {
-                        v.push(RefCell::new(Monkey(m_id, 0, wr_v, op, Test(div, t, i.parse::<usize>().unwrap()))));
-                        (v, (None, None, None, None, None))
+                        v.push(Monkey(m_id, 0, wr_v, op, Test(div, t, common::parse_field(i, line_index, l)?)));
+                        Ok((v, (None, None, None, None, None)))
                     },
-                    _ => panic!("Unknown building step '{}'", l)
+                    _ => Err(Error::parse(line_index, l, "Unknown building step"))
                 }
             }
-        ).0
+        )?;
+    if builder.0.is_some() {
+        return Err(Error::invalid_state(format!("Description of monkey {} is incomplete", monkeys.len())));
+    }
+    for Monkey(id, _, _, _, Test(_, t, f)) in &monkeys {
+        if let Some(receiver) = [*t, *f].into_iter().find(|r| *r >= monkeys.len()) {
+            return Err(Error::invalid_state(format!("Monkey {} throws to unknown monkey {}", id, receiver)));
+        }
+    }
+    Ok(monkeys)
 }
 
-fn monkey_bussiness(monkey_desc: &str, round_limit: usize, relief: bool) -> u64 {
-    let monkeys = build_monkeys(monkey_desc);
+fn inspections(monkeys: &[Monkey], round_limit: usize, relief: bool) -> Vec<u64> {
+    let monkeys: Vec<RefCell<Monkey>> = monkeys.iter().cloned().map(RefCell::new).collect();
     let cm: i64 = monkeys.iter().map(|m| m.borrow().4.0).product();
-    for _ in 0..round_limit {
+    for round in 1..=round_limit {
         for m_id in 0..(monkeys.len()) {
             let mut mon = monkeys.get(m_id).unwrap().borrow_mut();
             let drain: Vec<i64> = mon.2.drain(..).collect();
             for item in drain {
                 mon.1 += 1;
+                // Keeping worries modulo the divisors' product only works while they aren't divided
                 let new_w = if relief {
                     mon.3.exec(item) / 3
                 } else {
-                    mon.3.exec(item)
+                    mon.3.exec(item) % cm
                 };
 
                 let receiver = mon.4.throw(new_w);
-                monkeys.get(receiver).unwrap().borrow_mut().2.push(new_w % cm);
+                trace::emit(Level::Trace, || Event::MonkeyThrow { round, monkey: m_id, worry: new_w, to: receiver });
+                monkeys.get(receiver).unwrap().borrow_mut().2.push(new_w);
             }
         }
     }
-    std::collections::BinaryHeap::from_iter(
-        monkeys.iter()
-            .map(
-                |m| m.borrow().1
-            )
-        ).iter().take(2).product()
+    monkeys.iter().map(|m| m.borrow().1).collect()
 }
 
-pub fn chase_monkeys(input_path: &str, round_limit: usize, relief: bool) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => monkey_bussiness(&content, round_limit, relief),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn monkey_bussiness(monkeys: &[Monkey], round_limit: usize, relief: bool) -> u64 {
+    std::collections::BinaryHeap::from_iter(inspections(monkeys, round_limit, relief))
+        .into_sorted_vec().iter().rev().take(2).product()
+}
+
+pub fn chase_monkeys(input_path: &str, round_limit: usize, relief: bool) -> Result<u64> {
+    Ok(monkey_bussiness(&Day11::parse_file(input_path)?, round_limit, relief))
 }
 
 #[cfg(test)]
@@ -150,11 +200,43 @@ Monkey 3:
 
     #[test]
     fn test_input1() {
-        assert_eq!(monkey_bussiness(TEST_INP1, 20, true), 10605)
+        assert_eq!(Day11::solve_part1(TEST_INP1).unwrap(), 10605)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(monkey_bussiness(TEST_INP1, 10_000, false), 2713310158)
+        assert_eq!(Day11::solve_part2(TEST_INP1).unwrap(), 2713310158)
+    }
+
+    #[test]
+    fn test_unknown_receiver() {
+        let input = TEST_INP1.replace("If false: throw to monkey 1", "If false: throw to monkey 7");
+        assert!(matches!(Day11::parse(&input), Err(Error::InvalidState(_))))
+    }
+
+    #[test]
+    fn test_relief_worries_past_divisors() {
+        // Worries grow past the divisors' product of 30 while relief still divides them
+        let input = r#"Monkey 0:
+  Starting items: 4
+  Operation: new = old * 2
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 2
+
+Monkey 1:
+  Starting items: 29
+  Operation: new = old * 5
+  Test: divisible by 3
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 20
+  Operation: new = old + 7
+  Test: divisible by 5
+    If true: throw to monkey 0
+    If false: throw to monkey 1"#;
+        assert_eq!(Day11::solve_part1(input).unwrap(), 1536)
     }
 }
\ No newline at end of file
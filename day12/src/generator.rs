@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use common::{Generated, Rng};
+
+/// Steps from every square to `end`, walking the climbing rule backwards from it
+fn distances_to(heights: &[Vec<u8>], end: (usize, usize)) -> Vec<Vec<Option<usize>>> {
+    let mut distances = vec![vec![None; heights[0].len()]; heights.len()];
+    distances[end.1][end.0] = Some(0);
+    let mut queue = VecDeque::from([end]);
+    while let Some((x, y)) = queue.pop_front() {
+        let steps = distances[y][x].unwrap();
+        let around = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+        for (nx, ny) in around {
+            let reachable = heights.get(ny)
+                .and_then(|row| row.get(nx))
+                .is_some_and(|h| heights[y][x] <= h + 1);
+            if reachable && distances[ny][nx].is_none() {
+                distances[ny][nx] = Some(steps + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    distances
+}
+
+/// Slope of about `size` squares across rising from the top left corner, with random bumps
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let (width, height) = (size.max(20), (size * 2 / 3).max(13));
+    let slope = width + height - 2;
+    loop {
+        let mut heights: Vec<Vec<u8>> = (0..height)
+            .map(
+                |y| {
+                    (0..width)
+                        .map(
+                            |x| {
+                                let base = ((x + y) * 25 / slope) as i64;
+                                let bump = if rng.chance(15) { rng.between(-3, 3) } else { 0 };
+                                (base + bump).clamp(0, 25) as u8
+                            }
+                        )
+                        .collect()
+                }
+            )
+            .collect();
+        let start = (rng.below(width / 4), rng.below(height / 4));
+        let end = (width - 1 - rng.below(width / 4), height - 1 - rng.below(height / 4));
+        heights[start.1][start.0] = 0;
+        heights[end.1][end.0] = 25;
+
+        let distances = distances_to(&heights, end);
+        let from_start = distances[start.1][start.0];
+        let from_lowest = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|(x, y)| heights[*y][*x] == 0)
+            .filter_map(|(x, y)| distances[y][x])
+            .min();
+        let Some(from_start) = from_start else { continue };
+
+        let input = heights.iter()
+            .enumerate()
+            .map(
+                |(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(
+                            |(x, h)| match (x, y) {
+                                p if p == start => 'S',
+                                p if p == end => 'E',
+                                _ => (b'a' + h) as char
+                            }
+                        )
+                        .collect::<String>()
+                }
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Generated::solved(input, from_start, from_lowest.unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day12;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 40).check::<Day12>().unwrap();
+        }
+    }
+}
@@ -1,142 +1,129 @@
-fn build_height_map(height_map_code: &str) -> ((usize, usize), (usize, usize), Vec<Vec<u32>>) {
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    let heights = height_map_code.lines().enumerate()
-        .map(
-            |(i, l)| {
-                l.chars().enumerate()
-                    .map(
-                        |(j, c)| {
-                            if c == 'S' {
-                                start = (i, j);
-                                0
-                            } else if c == 'E' {
-                                end = (i, j);
-                                ('z' as u32) - ('a' as u32)
-                            } else {
-                                (c as u32) - ('a' as u32)
-                            }
-                        }
-                    )
-                    .collect()
-            }
+pub mod generator;
+
+use std::collections::VecDeque;
+
+use common::{Error, Json, Puzzle, Result};
+use grid::{Grid, Point};
+
+/// Start, end and the height of every square
+pub type HeightMap = (Point, Point, Grid<u32>);
+
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Input = HeightMap;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_height_map(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        trek(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        scenic_trek(input)
+    }
+
+    fn summary((start, end, height_map): &Self::Input) -> Json {
+        Json::object()
+            .with("width", height_map.width())
+            .with("height", height_map.height())
+            .with("start", *start)
+            .with("end", *end)
+    }
+
+    /// Steps from the start to each square explored before the end was reached, `null` for the rest
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        let (_, steps) = trek_steps(input);
+        Ok(
+            Json::object().with(
+                "steps",
+                steps.rows()
+                    .map(|row| row.iter().map(|s| (*s != u32::MAX).then_some(*s)).collect::<Json>())
+                    .collect::<Json>()
+            )
         )
-        .collect();
-    (start, end, heights)
+    }
 }
 
-#[allow(dead_code)]
-fn print_2d_matrix(matrix: &Vec<Vec<u32>>) {
-    matrix.iter()
-        .for_each(
-            |v| {
-                v.iter().for_each(|c| print!("{c:03} "));
-                println!("");
-            }
-        );
+fn build_height_map(height_map_code: &str) -> Result<HeightMap> {
+    let squares = Grid::parse(
+        height_map_code,
+        |c| matches!(c, 'S' | 'E' | 'a'..='z').then_some(c)
+    )?;
+    let find = |square| squares.iter().find(|(_, c)| **c == square).map(|(p, _)| p);
+    let heights = squares.map(
+        |c| match c {
+            'S' => 0,
+            'E' => ('z' as u32) - ('a' as u32),
+            _ => (*c as u32) - ('a' as u32)
+        }
+    );
+    match (find('S'), find('E')) {
+        (Some(start), Some(end)) => Ok((start, end, heights)),
+        (None, _) => Err(Error::invalid_state("No start 'S' in the height map")),
+        (_, None) => Err(Error::invalid_state("No end 'E' in the height map"))
+    }
 }
 
 fn advance_trek(
-    height_map: &Vec<Vec<u32>>,
-    steps: &mut Vec<Vec<u32>>,
-    queue: &mut std::collections::VecDeque<(usize, usize)>,
-    dups: &mut std::collections::BTreeSet<(usize, usize)>,
-    end: (usize, usize)
-) -> u32 {
-    loop {
-        if let Some((x, y)) = queue.pop_front() {
-            if end == (x, y) {
-                break steps[x][y];
-            }
-            dups.remove(&(x, y));
-            let mut adj = vec![];
-            let cur_height = height_map[x][y];
-            let cur_steps = steps[x][y];
-            if x > 0 {
-                adj.push((x - 1, y));
-            }
-            if x < height_map.len() - 1 {
-                adj.push((x + 1, y));
-            }
-            if y > 0 {
-                adj.push((x, y - 1));
-            }
-            if y < height_map[0].len() - 1 {
-                adj.push((x, y + 1));
+    height_map: &Grid<u32>,
+    steps: &mut Grid<u32>,
+    queue: &mut VecDeque<Point>,
+    end: Point
+) -> Option<u32> {
+    while let Some(square) = queue.pop_front() {
+        if end == square {
+            return Some(steps[square]);
+        }
+        let cur_height = height_map[square];
+        let cur_steps = steps[square];
+        for next in height_map.neighbours4(square) {
+            if steps[next] == u32::MAX && height_map[next] as i32 - cur_height as i32 <= 1 {
+                queue.push_back(next);
+                steps[next] = cur_steps + 1;
             }
-            adj.into_iter()
-                .for_each(
-                    |(x, y)| {
-                        if steps[x][y] > cur_steps + 1 && height_map[x][y] as i32 - cur_height as i32 <= 1 {
-                            if !dups.contains(&(x, y)) {
-                                queue.push_back((x, y));
-                                dups.insert((x, y));
-                                steps[x][y] = cur_steps + 1;
-                            }
-                        }
-                    }
-                );
-        } else {
-            break 0;
         }
     }
+    None
 }
 
-fn trek(height_map_code: &str) -> u32 {
-    let (start, end, height_map) = build_height_map(height_map_code);
-    let mut steps = vec![vec![999; height_map[0].len()]; height_map.len()];
-    steps[start.0][start.1] = 0;
-    let mut queue = std::collections::VecDeque::from_iter([start]);
-    let mut dups = std::collections::BTreeSet::from_iter([start]);
-    advance_trek(&height_map, &mut steps, &mut queue, &mut dups, end)
+fn unreachable_end() -> Error {
+    Error::invalid_state("End 'E' can't be reached")
 }
 
-pub fn climb_to_best_reception(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => trek(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+/// Steps to the end if it was reached, along with the steps to every square explored
+fn trek_steps((start, end, height_map): &HeightMap) -> (Option<u32>, Grid<u32>) {
+    let mut steps = height_map.map(|_| u32::MAX);
+    steps[*start] = 0;
+    let mut queue = VecDeque::from_iter([*start]);
+    let reached = advance_trek(height_map, &mut steps, &mut queue, *end);
+    (reached, steps)
 }
 
-fn scenic_trek(height_map_code: &str) -> u32 {
-    let (start, end, height_map) = build_height_map(height_map_code);
-    let mut steps = vec![vec![999; height_map[0].len()]; height_map.len()];
-    steps[start.0][start.1] = 0;
-    let mut queue = std::collections::VecDeque::new();
-    let mut dups = std::collections::BTreeSet::new();
-    
-    height_map.iter().enumerate()
-        .for_each(
-            |(i, v)| {
-                v.iter().enumerate()
-                    .for_each(
-                        |(j, height)| {
-                            if height == &0 {
-                                queue.push_back((i, j));
-                                dups.insert((i, j));
-                                steps[i][j] = 0;
-                            }
-                        }
-                    )
-            }
-        );
-    
-    advance_trek(&height_map, &mut steps, &mut queue, &mut dups, end)
+fn trek(height_map: &HeightMap) -> Result<u32> {
+    trek_steps(height_map).0.ok_or_else(unreachable_end)
 }
 
-pub fn find_scenic_trek(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => scenic_trek(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn climb_to_best_reception(input_path: &str) -> Result<u32> {
+    Day12::part1(&Day12::parse_file(input_path)?)
+}
+
+fn scenic_trek((_, end, height_map): &HeightMap) -> Result<u32> {
+    let mut steps = height_map.map(|height| if *height == 0 { 0 } else { u32::MAX });
+    let mut queue = steps.iter()
+        .filter(|(_, steps)| **steps == 0)
+        .map(|(p, _)| p)
+        .collect();
+    advance_trek(height_map, &mut steps, &mut queue, *end)
+        .ok_or_else(unreachable_end)
+}
+
+pub fn find_scenic_trek(input_path: &str) -> Result<u32> {
+    Day12::part2(&Day12::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -152,11 +139,24 @@ abdefghi"#;
 
     #[test]
     fn test_input1() {
-        assert_eq!(trek(TEST_INP1), 31)
+        assert_eq!(Day12::solve_part1(TEST_INP1).unwrap(), 31)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(scenic_trek(TEST_INP1), 29)
+        assert_eq!(Day12::solve_part2(TEST_INP1).unwrap(), 29)
+    }
+
+    #[test]
+    fn test_step_matrix() {
+        let artefacts = Day12::artefacts(&Day12::parse(TEST_INP1).unwrap()).unwrap();
+        let Some(Json::Array(rows)) = artefacts.get("steps") else { panic!("No step matrix") };
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[2], Json::from(vec![Some(2), Some(3), Some(4), Some(21), Some(30), Some(31), Some(26), Some(13)]))
+    }
+
+    #[test]
+    fn test_unreachable_end() {
+        assert!(matches!(Day12::solve_part1("Sbcz\nabcE"), Err(Error::InvalidState(_))))
     }
 }
\ No newline at end of file
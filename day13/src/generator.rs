@@ -0,0 +1,109 @@
+use std::cmp::Ordering;
+
+use common::{Generated, Rng};
+
+#[derive(Clone)]
+enum Value {
+    Integer(u32),
+    List(Vec<Value>)
+}
+
+impl Value {
+    fn render(&self) -> String {
+        match self {
+            Value::Integer(v) => v.to_string(),
+            Value::List(l) => format!("[{}]", l.iter().map(Value::render).collect::<Vec<_>>().join(","))
+        }
+    }
+
+    /// Packet order, comparing lists lexicographically and wrapping a lone integer compared to a list
+    fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Integer(l), Value::Integer(r)) => l.cmp(r),
+            (Value::List(l), Value::List(r)) => {
+                l.iter().zip(r)
+                    .map(|(l, r)| l.compare(r))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| l.len().cmp(&r.len()))
+            },
+            (Value::Integer(_), Value::List(_)) => Value::List(vec![self.clone()]).compare(other),
+            (Value::List(_), Value::Integer(_)) => self.compare(&Value::List(vec![other.clone()]))
+        }
+    }
+}
+
+fn random_value(rng: &mut Rng, depth: usize) -> Value {
+    if depth > 0 && rng.chance(40) {
+        Value::List((0..rng.between(0, 4)).map(|_| random_value(rng, depth - 1)).collect())
+    } else {
+        Value::Integer(rng.between(0, 10) as u32)
+    }
+}
+
+/// Copy of `value` differing somewhere, so the comparison has to go deep
+fn tweak(rng: &mut Rng, value: &Value) -> Value {
+    match value {
+        Value::List(l) if !l.is_empty() && rng.chance(80) => {
+            let mut l = l.clone();
+            let i = rng.below(l.len());
+            l[i] = tweak(rng, &l[i]);
+            if rng.chance(20) {
+                l.truncate(i + 1);
+            }
+            Value::List(l)
+        },
+        _ => random_value(rng, 2)
+    }
+}
+
+/// `size` packet pairs, the right packet often a close copy of the left one
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let dividers = [Value::List(vec![Value::List(vec![Value::Integer(2)])]), Value::List(vec![Value::List(vec![Value::Integer(6)])])];
+    let packet = |rng: &mut Rng| loop {
+        let value = Value::List((0..rng.between(0, 5)).map(|_| random_value(rng, 3)).collect());
+        // Packets tied with a divider would make its position ambiguous
+        if dividers.iter().all(|divider| value.compare(divider).is_ne()) {
+            return value;
+        }
+    };
+    let pairs: Vec<(Value, Value)> = (0..size.max(1))
+        .map(
+            |_| {
+                let left = packet(rng);
+                let right = if rng.chance(60) { tweak(rng, &left) } else { packet(rng) };
+                let right = match right {
+                    Value::List(_) if dividers.iter().all(|divider| right.compare(divider).is_ne()) => right,
+                    _ => packet(rng)
+                };
+                (left, right)
+            }
+        )
+        .collect();
+
+    let input = pairs.iter()
+        .map(|(left, right)| format!("{}\n{}", left.render(), right.render()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let ordered: usize = pairs.iter()
+        .enumerate()
+        .filter(|(_, (left, right))| left.compare(right).is_lt())
+        .map(|(i, _)| i + 1)
+        .sum();
+    let packets: Vec<&Value> = pairs.iter().flat_map(|(left, right)| [left, right]).collect();
+    let before = |divider: &Value| packets.iter().filter(|p| p.compare(divider).is_lt()).count();
+    let decoder_key = (before(&dividers[0]) + 1) * (before(&dividers[1]) + 2);
+    Generated::solved(input, ordered, decoder_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day13;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 50).check::<Day13>().unwrap();
+        }
+    }
+}
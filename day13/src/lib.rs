@@ -1,5 +1,48 @@
+pub mod generator;
+
+use common::{trace::{self, Event, Level}, Error, Json, Puzzle, Result};
+
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Input = Vec<(Packet, Packet)>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_packets(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(verify_packets(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(order_and_select(input))
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("pairs", input.len())
+    }
+
+    /// Indices of the pairs already in the right order, counted from 1
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        Ok(
+            Json::object().with(
+                "ordered_pairs",
+                input.iter()
+                    .cloned()
+                    .enumerate()
+                    .filter(|(_, pair)| verify_pair_order(pair.clone()) == Some(true))
+                    .map(|(i, _)| i + 1)
+                    .collect::<Json>()
+            )
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
-enum Packet {
+pub enum Packet {
     PacketValue(u32),
     PacketList(Vec<Packet>)
 }
@@ -9,21 +52,8 @@ impl Packet {
         match self {
             Packet::PacketList(l) => {
                 match l.as_slice() {
-                    [f] => {
-                        match f {
-                            Packet::PacketList(l) => {
-                                match l.as_slice() {
-                                    [f] => {
-                                        match f {
-                                            Packet::PacketValue(2 | 6) => true,
-                                            _ => false
-                                        }
-                                    },
-                                    _ => false
-                                }
-                            },
-                            _ => false
-                        }
+                    [Packet::PacketList(l)] => {
+                        matches!(l.as_slice(), [Packet::PacketValue(2 | 6)])
                     },
                     _ => false
                 }
@@ -45,17 +75,20 @@ impl std::fmt::Display for Packet {
     }
 }
 
-fn compile_packet(packet_data: &str) -> Packet {
+fn compile_packet(line_index: usize, packet_data: &str) -> Result<Packet> {
     let mut sub_packets = vec![];
     sub_packets.push(Packet::PacketList(vec![]));
     
     let mut val: Option<Packet> = None;
-    for c in packet_data.chars() {
+    for (j, c) in packet_data.chars().enumerate() {
         match c {
             '[' => {
                 sub_packets.push(Packet::PacketList(vec![]))
             },
             ']' => {
+                if sub_packets.len() == 1 {
+                    return Err(Error::parse_at(line_index, j, packet_data, "Unbalanced ']'"));
+                }
                 let mut p = sub_packets.pop().unwrap();
                 if let Packet::PacketList(l) = &mut p {
                     if let Some(vp) = val {
@@ -74,46 +107,53 @@ fn compile_packet(packet_data: &str) -> Packet {
                 }
             },
             d => {
-                let nd = d.to_string().parse::<u32>().unwrap();
+                let nd = d.to_digit(10)
+                    .ok_or_else(|| Error::parse_at(line_index, j, packet_data, format!("Unexpected character '{}'", d)))?;
                 val = match val {
                     None => Some(Packet::PacketValue(nd)),
                     Some(Packet::PacketValue(v)) => Some(Packet::PacketValue(v * 10 + nd)),
-                    _ => panic!("Malformed packet '{}'", packet_data)
+                    _ => return Err(Error::parse_at(line_index, j, packet_data, "Malformed packet"))
                 }
             }
         }
     }
     
-    val.unwrap()
+    match (sub_packets.len(), val) {
+        (1, Some(val)) => Ok(val),
+        (1, None) => Err(Error::parse(line_index, packet_data, "Empty packet")),
+        _ => Err(Error::parse(line_index, packet_data, "Unclosed '['"))
+    }
 }
 
 #[allow(dead_code)]
 fn flatten_packet(packet: Packet) -> Vec<u32> {
     match packet {
-        Packet::PacketList(l) => l.into_iter().flat_map(|p| flatten_packet(p)).collect(),
+        Packet::PacketList(l) => l.into_iter().flat_map(flatten_packet).collect(),
         Packet::PacketValue(v) => vec![v]
     }
 }
 
-fn build_packets(packet_content: &str) -> Vec<(Packet, Packet)> {
+fn build_packets(packet_content: &str) -> Result<Vec<(Packet, Packet)>> {
     packet_content.lines().collect::<Vec<&str>>().chunks(3)
+        .enumerate()
         .map(
-            |chunk| {
+            |(i, chunk)| {
+                let line_index = i * 3;
                 match chunk {
                     [p1, p2, ""] => {
-                        (compile_packet(p1), compile_packet(p2))
+                        Ok((compile_packet(line_index, p1)?, compile_packet(line_index + 1, p2)?))
                     }, 
                     [p1, p2] => {
-                        (compile_packet(p1), compile_packet(p2))
+                        Ok((compile_packet(line_index, p1)?, compile_packet(line_index + 1, p2)?))
                     },
-                    _ => panic!("Broken packet pair '{:?}'", chunk)
+                    [_, _, l] => Err(Error::parse(line_index + 2, l, "Expected a blank line after a packet pair")),
+                    _ => Err(Error::parse(line_index, chunk[0], "Packet without a pair"))
                 }
             }
         ).collect()
 }
 
 fn verify_pair_order(pair: (Packet, Packet)) -> Option<bool> {
-    // println!("Compare {} vs {}", pair.0, pair.1);
     match pair {
         (Packet::PacketList(l), Packet::PacketList(r)) => {
             let left_shorter = match l.len().cmp(&r.len()) {
@@ -122,13 +162,10 @@ fn verify_pair_order(pair: (Packet, Packet)) -> Option<bool> {
                 std::cmp::Ordering::Greater => Some(false)
             };
             let mut inner = None;
-            for ip in l.into_iter().zip(r.into_iter()) {
-                match verify_pair_order(ip) {
-                    Some(b) => {
-                        inner = Some(b);
-                        break; 
-                    },
-                    None => ()
+            for ip in l.into_iter().zip(r) {
+                if let Some(b) = verify_pair_order(ip) {
+                    inner = Some(b);
+                    break; 
                 } 
             }
             match inner {
@@ -152,40 +189,27 @@ fn verify_pair_order(pair: (Packet, Packet)) -> Option<bool> {
     }
 }
 
-fn verify_packets(packet_content: &str) -> u32 {
-    let packets = build_packets(packet_content);
-    packets.into_iter()
+fn verify_packets(packets: &[(Packet, Packet)]) -> u32 {
+    packets.iter()
+        .cloned()
         .enumerate()
         .filter_map(
             |(i, pair) | {
-                // println!("Pair {}", i + 1);
-                let a = if let Some(true) = verify_pair_order(pair) {
-                    Some(i as u32 + 1)
-                } else {
-                    None
-                };
-                // println!("Order '{:?}'", a);
-                // println!();
-                a
+                let ordered = verify_pair_order(pair) == Some(true);
+                trace::emit(Level::Debug, || Event::PacketsCompared { pair: i + 1, ordered });
+                ordered.then_some(i as u32 + 1)
             }
         )
         .sum()
-        
 }
 
-pub fn verify_packets_order(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => verify_packets(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn verify_packets_order(input_path: &str) -> Result<u32> {
+    Day13::part1(&Day13::parse_file(input_path)?)
 }
 
-fn order_and_select(packet_content: &str) -> u32 {
-    let mut packets: Vec<_> = build_packets(packet_content).into_iter()
+fn order_and_select(packets: &[(Packet, Packet)]) -> u32 {
+    let mut packets: Vec<_> = packets.iter()
+        .cloned()
         .flat_map(|(p1, p2)| vec![p1, p2])
         .collect();
     packets.push(Packet::PacketList(vec![Packet::PacketList(vec![Packet::PacketValue(2)])]));
@@ -214,15 +238,8 @@ fn order_and_select(packet_content: &str) -> u32 {
         .product()
 }
 
-pub fn order_packets_and_select_distress(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => order_and_select(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn order_packets_and_select_distress(input_path: &str) -> Result<u32> {
+    Day13::part2(&Day13::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -256,11 +273,28 @@ r#"[1,1,3,1,1]
 
     #[test]
     fn test_input1() {
-        assert_eq!(verify_packets(TEST_INP1), 13)
+        assert_eq!(Day13::solve_part1(TEST_INP1).unwrap(), 13)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(order_and_select(TEST_INP1), 140)
+        assert_eq!(Day13::solve_part2(TEST_INP1).unwrap(), 140)
+    }
+
+    #[test]
+    fn test_traced_pairs() {
+        let (sum, events) = trace::collect(Level::Debug, || Day13::solve_part1(TEST_INP1).unwrap());
+        let ordered: Vec<_> = events.iter()
+            .filter_map(|event| match event {
+                Event::PacketsCompared { pair, ordered: true } => Some(*pair),
+                _ => None
+            })
+            .collect();
+        assert_eq!((sum, events.len(), ordered), (13, 8, vec![1, 2, 4, 6]))
+    }
+
+    #[test]
+    fn test_unclosed_packet() {
+        assert!(matches!(Day13::parse("[1,[2]\n[1,2]"), Err(Error::Parse { line: 1, .. })))
     }
 }
\ No newline at end of file
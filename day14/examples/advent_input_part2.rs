@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day14::find_sand_fill_amount("./day14/resources/input.txt", true);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,100 @@
+use common::{Generated, Rng};
+
+const SOURCE: (usize, usize) = (500, 0);
+
+/// Rock walls drawn as paths of alternating horizontal and vertical segments, `size` of them
+///
+/// The first answer drops grains one by one on a plain array, the second counts the squares reachable from
+/// the source through the three squares above them, as the sand pile fills them all.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.max(1);
+    let spread = (count * 2).max(10) as i64;
+    let paths: Vec<Vec<(usize, usize)>> = (0..count)
+        .map(
+            |_| {
+                let mut point = (
+                    rng.between(SOURCE.0 as i64 - spread, SOURCE.0 as i64 + spread),
+                    rng.between(2, spread)
+                );
+                let mut path = vec![point];
+                let horizontal = rng.chance(50);
+                for segment in 0..rng.between(1, 4) {
+                    let length = rng.between(-6, 6);
+                    if (segment % 2 == 0) == horizontal {
+                        point.0 += length;
+                    } else {
+                        point.1 = (point.1 + length).max(1);
+                    }
+                    path.push(point);
+                }
+                path.into_iter().map(|(x, y)| (x as usize, y as usize)).collect()
+            }
+        )
+        .collect();
+
+    let lowest = paths.iter().flatten().map(|(_, y)| *y).max().unwrap();
+    let floor = lowest + 2;
+    let rightmost = paths.iter().flatten().map(|(x, _)| *x).max().unwrap();
+    let width = rightmost.max(SOURCE.0 + floor) + 2;
+    let mut cave = vec![vec![false; width]; floor];
+    for path in paths.iter() {
+        for pair in path.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            for row in cave.iter_mut().take(y1.max(y2) + 1).skip(y1.min(y2)) {
+                row[x1.min(x2)..=x1.max(x2)].iter_mut().for_each(|c| *c = true);
+            }
+        }
+    }
+
+    let mut resting = 0;
+    let mut filled = cave.clone();
+    'pouring: loop {
+        let (mut x, mut y) = SOURCE;
+        loop {
+            if y == lowest {
+                break 'pouring;
+            }
+            match [x, x - 1, x + 1].into_iter().find(|nx| !filled[y + 1][*nx]) {
+                Some(nx) => (x, y) = (nx, y + 1),
+                None => break
+            }
+        }
+        filled[y][x] = true;
+        resting += 1;
+        // Walls can hold the sand all the way up to the source
+        if (x, y) == SOURCE {
+            break;
+        }
+    }
+
+    let mut reached = vec![vec![false; width]; floor];
+    reached[SOURCE.1][SOURCE.0] = true;
+    let mut piled = 1;
+    for y in 1..floor {
+        for x in 1..width - 1 {
+            if !cave[y][x] && (reached[y - 1][x - 1] || reached[y - 1][x] || reached[y - 1][x + 1]) {
+                reached[y][x] = true;
+                piled += 1;
+            }
+        }
+    }
+
+    let input = paths.iter()
+        .map(|path| path.iter().map(|(x, y)| format!("{},{}", x, y)).collect::<Vec<_>>().join(" -> "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(input, resting, piled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day14;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 20).check::<Day14>().unwrap();
+        }
+    }
+}
@@ -1,171 +1,134 @@
-use std::{collections::{HashMap}};
+pub mod generator;
 
-#[derive(Debug)]
-enum Fill {
+use common::{Error, Json, Puzzle, Result};
+use grid::{Point, SparseGrid};
+
+const SAND_SOURCE: Point = Point::new(500, 0);
+
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    type Input = SparseGrid<Fill>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_cave(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        sand_fill(input, false)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        sand_fill(input, true)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("rocks", input.len())
+            .with("abyss", abyss_height(input).map(|y| y as i64))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Fill {
     Rock,
     Sand
 }
 
-fn build_cave(cave: &str) -> HashMap<(usize, usize), Fill> {
-    cave.lines()
-        .flat_map(
-            |l| {
-                let mut coords = l.split_terminator(" -> ")
-                    .map(
-                        |coord| {
-                            let (x, y) = coord.split_once(",").unwrap();
-                            (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap())
-                        }
-                    );
-                let first = coords.next().unwrap();
-                coords.fold(
-                        (vec![], first),
-                        |(mut v, prev), cur| {
-                            match (prev.0.cmp(&cur.0), prev.1.cmp(&cur.1)) {
-                                (std::cmp::Ordering::Equal, std::cmp::Ordering::Less) => {
-                                    (prev.1..=cur.1).for_each(
-                                        |i| v.push((prev.0, i))
-                                    )
-                                },
-                                (std::cmp::Ordering::Equal, std::cmp::Ordering::Greater) => {
-                                    (cur.1..=prev.1).for_each(
-                                        |i| v.push((prev.0, i))
-                                    )
-                                },
-                                (std::cmp::Ordering::Equal, std::cmp::Ordering::Equal) => {
-                                    v.push((prev.0, prev.1))
-                                },
-                                (std::cmp::Ordering::Less, std::cmp::Ordering::Equal) => {
-                                    (prev.0..=cur.0).for_each(
-                                        |i| v.push((i, prev.1))
-                                    )
-                                },
-                                (std::cmp::Ordering::Greater, std::cmp::Ordering::Equal) => {
-                                    (cur.0..=prev.0).for_each(
-                                        |i| v.push((i, prev.1))
-                                    )
-                                },
-                                _ => panic!("Diagonal rock wall '{:?}' -> '{:?}'", prev, cur)
-                            }
-                            (v, cur)
-                        }
-                    )
-                    .0
-            }
-        )
-        .fold(
-            HashMap::new(),
-            |mut hm, cur| {
-                hm.insert(cur, Fill::Rock);
-                hm
+fn build_cave(cave: &str) -> Result<SparseGrid<Fill>> {
+    let mut rocks = SparseGrid::new();
+    for (i, l) in cave.lines().enumerate() {
+        let coords = l.split_terminator(" -> ")
+            .map(
+                |coord| {
+                    let (x, y) = coord.split_once(",")
+                        .ok_or_else(|| Error::parse(i, l, format!("Malformed coordinate '{}'", coord)))?;
+                    Ok(Point::new(common::parse_field(x, i, l)?, common::parse_field(y, i, l)?))
+                }
+            )
+            .collect::<Result<Vec<_>>>()?;
+        let (first, coords) = coords.split_first()
+            .ok_or_else(|| Error::parse(i, l, "Empty rock wall"))?;
+        rocks.insert(*first, Fill::Rock);
+        coords.iter().try_fold(
+            *first,
+            |mut prev, cur| {
+                if prev.x != cur.x && prev.y != cur.y {
+                    return Err(Error::parse(i, l, format!("Diagonal rock wall '{:?}' -> '{:?}'", prev, cur)));
+                }
+                let step = Point::new((cur.x - prev.x).signum(), (cur.y - prev.y).signum());
+                while prev != *cur {
+                    prev += step;
+                    rocks.insert(prev, Fill::Rock);
+                }
+                Ok(prev)
             }
-        )
+        )?;
+    }
+    Ok(rocks)
 }
 
-fn abyss_height(cave: &HashMap<(usize, usize), Fill>) -> usize {
-    cave.iter().max_by(|(a, _), (b, _)| a.1.cmp(&b.1)).unwrap().0.1
+fn abyss_height(cave: &SparseGrid<Fill>) -> Option<isize> {
+    cave.bounds().map(|(_, max)| max.y)
 }
 
-#[allow(dead_code)]
-fn print_cave(cave: &HashMap<(usize, usize), Fill>, include_floor: bool) {
-    let min_x = cave.iter()
-        .min_by(
-            |(a, _), (b, _)| a.0.cmp(&b.0)
-        ).unwrap().0.0;
-    let max_x = cave.iter()
-        .max_by(
-            |(a, _), (b, _)| a.0.cmp(&b.0)
-        ).unwrap().0.0;
-    let abyss = cave.iter()
-        .max_by(
-            |(a, _), (b, _)| a.1.cmp(&b.1)
-        ).unwrap().0.1  + if include_floor {
-            2
-        } else {
-            0
-        };
-
-    for y in 0..=abyss {
-        for x in min_x..=max_x {
-            if include_floor && y == abyss - 1 {
-                print!("#")
-            } else {
-                match cave.get(&(x, y)) {
-                    Some(Fill::Rock) => print!("#"),
-                    Some(Fill::Sand) => print!("o"),
-                    None => {
-                        if (x, y) == (500, 0) {
-                            print!("+")
-                        } else {
-                            print!(".")
-                        }
-                    }
-                }
-            }
+pub fn render_cave(cave: &SparseGrid<Fill>) -> String {
+    cave.render(
+        '.',
+        |f| match f {
+            Fill::Rock => '#',
+            Fill::Sand => 'o'
         }
-        println!()
-    }
+    )
 }
 
 fn check_sand_movement(
-    cave: &HashMap<(usize, usize), Fill>,
-    sand: &(usize, usize),
-    abyss: usize,
+    cave: &SparseGrid<Fill>,
+    sand: Point,
+    abyss: isize,
     include_floor: bool
-) -> Option<(usize, usize)> {
-    if include_floor && sand.1 == abyss - 1 {
+) -> Option<Point> {
+    if include_floor && sand.y == abyss - 1 {
         None
-    } else if !cave.contains_key(&(sand.0, sand.1 + 1)) {
-        Some((sand.0, sand.1 + 1))
-    } else if !cave.contains_key(&(sand.0 - 1, sand.1 + 1)) {
-        Some((sand.0 - 1, sand.1 + 1))
-    } else if !cave.contains_key(&(sand.0 + 1, sand.1 + 1)) {
-        Some((sand.0 + 1, sand.1 + 1))
     } else {
-        None
+        [Point::SOUTH, Point::SOUTH_WEST, Point::SOUTH_EAST].into_iter()
+            .map(|step| sand + step)
+            .find(|p| !cave.contains(*p))
     }
 }
 
-fn sand_fill(cave: &str, include_floor: bool) -> u32 {
-    let mut cave = build_cave(cave);
-    let abyss = abyss_height(&cave) + if include_floor {
+pub fn sand_fill(cave: &SparseGrid<Fill>, include_floor: bool) -> Result<u32> {
+    let mut cave = cave.clone();
+    let abyss = abyss_height(&cave).ok_or_else(|| Error::invalid_state("No rock walls in the cave"))? + if include_floor {
         2
     } else {
         0
     };
 
-    let mut sand = (500, 0);
-    loop {
-        if sand.1 > abyss {
-            break;
-        } else {
-            match check_sand_movement(&cave, &sand, abyss, include_floor) {
-                Some(c) => sand = c,
-                None => {
-                    cave.insert(sand, Fill::Sand);
-                    if sand == (500, 0) {
-                        break;
-                    }
-                    sand = (500, 0);
+    let mut sand = SAND_SOURCE;
+    while sand.y <= abyss {
+        match check_sand_movement(&cave, sand, abyss, include_floor) {
+            Some(p) => sand = p,
+            None => {
+                cave.insert(sand, Fill::Sand);
+                if sand == SAND_SOURCE {
+                    break;
                 }
+                sand = SAND_SOURCE;
             }
         }
     }
     
-    cave.iter()
+    let sand = cave.iter()
         .filter(|(_, f)| matches!(f, Fill::Sand))
-        .count() as u32
+        .count() as u32;
+    Ok(sand)
 }
 
-pub fn find_sand_fill_amount(input_path: &str, include_floor: bool) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => sand_fill(&content, include_floor),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn find_sand_fill_amount(input_path: &str, include_floor: bool) -> Result<u32> {
+    sand_fill(&Day14::parse_file(input_path)?, include_floor)
 }
 
 #[cfg(test)]
@@ -178,11 +141,22 @@ r#"498,4 -> 498,6 -> 496,6
 
     #[test]
     fn test_input1() {
-        assert_eq!(sand_fill(TEST_INP1, false), 24)
+        assert_eq!(Day14::solve_part1(TEST_INP1).unwrap(), 24)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(sand_fill(TEST_INP1, true), 93)
+        assert_eq!(Day14::solve_part2(TEST_INP1).unwrap(), 93)
+    }
+
+    #[test]
+    fn test_render_cave() {
+        let cave = Day14::parse(TEST_INP1).unwrap();
+        assert_eq!(render_cave(&cave), "....#...##\n....#...#.\n..###...#.\n........#.\n........#.\n#########.")
+    }
+
+    #[test]
+    fn test_diagonal_wall() {
+        assert!(matches!(Day14::parse("498,4 -> 498,6\n503,4 -> 502,5"), Err(Error::Parse { line: 2, .. })))
     }
 }
\ No newline at end of file
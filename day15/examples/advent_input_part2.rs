@@ -1,4 +1,10 @@
 fn main() {
-    let res = advent_of_code_2022_day15::find_beacon_frequency("./day15/resources/input.txt");
-    println!("{:?}", res)
+    let res = advent_of_code_2022_day15::find_beacon_frequency(
+        "./day15/resources/input.txt",
+        4_000_000
+    );
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+
+use common::{Generated, Rng};
+
+use crate::{FREQUENCY_MULTIPLIER, PROBE_DEPTH, SEARCH_BOUND};
+
+/// Sensor and beacon in the rotated coordinates `u = x + y` and `v = x - y`, where sensor ranges are squares
+type Response = ((i64, i64), (i64, i64));
+
+fn to_xy((u, v): (i64, i64)) -> (i64, i64) {
+    ((u + v) / 2, (u - v) / 2)
+}
+
+/// Sensors covering the whole search area but for one distress beacon, plus `decoys` sensors away from it
+///
+/// In rotated coordinates the sensor ranges are squares: four squares meet corner to corner around the gap,
+/// with four larger ones over the lines crossing it. Each beacon sits on the outer edge of the covered area,
+/// so it is no closer to any other sensor than that sensor's own beacon. The first answer adds up the ranges
+/// crossing the probed row, the second is the gap left on purpose.
+pub fn sensor_field(rng: &mut Rng, decoys: usize, depth: i64, bound: i64) -> Generated {
+    let gap = (rng.between(0, bound), rng.between(0, bound));
+    let (u0, v0) = (gap.0 + gap.1, gap.0 - gap.1);
+    // Squares must reach every corner of the search area, odd sizes keep the centers on the grid
+    let r = (bound | 1) + 2 * rng.between(0, 3);
+    let big = r + 2;
+
+    let mut responses: Vec<Response> = vec![];
+    for (su, sv) in [(-1, -1), (-1, 1), (1, -1), (1, 1)] {
+        responses.push(((u0 + su * (r + 1), v0 + sv * (r + 1)), (u0 + su * (2 * r + 1), v0 + sv * (2 * r + 1))));
+    }
+    for s in [-1, 1] {
+        responses.push(((u0 + s * (big + 1), v0), (u0 + s * (2 * big + 1), v0 + 1)));
+        responses.push(((u0, v0 + s * (big + 1)), (u0 + 1, v0 + s * (2 * big + 1))));
+    }
+    // Decoys line up past the covered area, far enough not to touch it or each other
+    let mut cursors = [u0 + 2 * big + 2, u0 - 2 * big - 2];
+    for _ in 0..decoys {
+        let side = rng.below(2);
+        let radius = rng.between(1, (bound / 4).max(1));
+        let u = if side == 0 { cursors[0] + radius } else { cursors[1] - radius };
+        cursors[side] = if side == 0 { u + radius + 1 } else { u - radius - 1 };
+        let v = rng.between(v0 - 2 * big, v0 + 2 * big);
+        let v = v + (u - v).rem_euclid(2);
+        responses.push(((u, v), (u + radius, v + radius)));
+    }
+
+    let mut responses: Vec<_> = responses.into_iter().map(|(s, b)| (to_xy(s), to_xy(b))).collect();
+    rng.shuffle(&mut responses);
+
+    let mut ranges: Vec<(i64, i64)> = responses.iter()
+        .filter_map(
+            |(s, b)| {
+                let reach = s.0.abs_diff(b.0) as i64 + s.1.abs_diff(b.1) as i64 - s.1.abs_diff(depth) as i64;
+                (reach >= 0).then_some((s.0 - reach, s.0 + reach))
+            }
+        )
+        .collect();
+    ranges.sort_unstable();
+    let (covered, _) = ranges.iter()
+        .fold(
+            (0, i64::MIN),
+            |(covered, end), (from, to)| {
+                let from = (*from).max(end + 1);
+                if from > *to { (covered, end) } else { (covered + to - from + 1, *to) }
+            }
+        );
+    let beacons: HashSet<_> = responses.iter().map(|(_, b)| *b).filter(|b| b.1 == depth).collect();
+
+    let input = responses.iter()
+        .map(|(s, b)| format!("Sensor at x={}, y={}: closest beacon is at x={}, y={}", s.0, s.1, b.0, b.1))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let frequency = gap.0 as u128 * FREQUENCY_MULTIPLIER + gap.1 as u128;
+    Generated::solved(input, covered - beacons.len() as i64, frequency)
+}
+
+/// Sensor field at the puzzle's scale, `size` decoys
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    sensor_field(rng, size, PROBE_DEPTH as i64, SEARCH_BOUND as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Puzzle;
+
+    use super::*;
+    use crate::{probe, tune_frequency, Day15};
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            let generated = sensor_field(&mut Rng::new(seed), 6, 10, 20);
+            let sensors = Day15::parse(&generated.input).unwrap();
+            assert_eq!(Some(probe(&sensors, 10).unwrap().to_string()), generated.part1);
+            assert_eq!(Some(tune_frequency(&sensors, 20).unwrap().to_string()), generated.part2);
+        }
+    }
+}
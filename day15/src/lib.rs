@@ -1,55 +1,89 @@
+pub mod generator;
+
 use std::collections::{BTreeMap, HashSet};
 
-const BOUND: isize = 4_000_000;
+use common::{Error, Json, Puzzle, Result};
+
+const FREQUENCY_MULTIPLIER: u128 = 4_000_000;
+const PROBE_DEPTH: isize = 2_000_000;
+const SEARCH_BOUND: isize = 4_000_000;
+
+/// Closest beacon reported by each sensor
+pub type SensorResponses = BTreeMap<(isize, isize), (isize, isize)>;
+
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    type Input = SensorResponses;
+    type Answer1 = u32;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_sensor_response(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        probe(input, PROBE_DEPTH)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        tune_frequency(input, SEARCH_BOUND)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("sensors", input.len())
+            .with("beacons", input.values().collect::<HashSet<_>>().len())
+    }
+}
 
 fn manhatan_distance(a: (isize, isize), b: (isize, isize)) -> usize {
     a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
 }
 
-fn build_sensor_response(cave: &str) -> BTreeMap<(isize, isize), (isize, isize)> {
-    cave.lines()
+fn build_sensor_response(cave: &str) -> Result<SensorResponses> {
+    let responses = cave.lines()
+        .enumerate()
         .map(
-            |l| {
+            |(i, l)| {
                 let bounds: &[_] = &[' ', ':', ',', '='];
                 let spl = l.split_terminator(bounds);
                 let v = Vec::from_iter(spl);
                 match v.as_slice() {
                     ["Sensor", "at", "x", sx, "", "y", sy, "", "closest", "beacon", "is", "at", "x", bx, "", "y", by] => {
-                        (
-                            (sx.parse().unwrap(), sy.parse().unwrap()),
-                            (bx.parse().unwrap(), by.parse().unwrap())
+                        Ok(
+                            (
+                                (common::parse_field(sx, i, l)?, common::parse_field(sy, i, l)?),
+                                (common::parse_field(bx, i, l)?, common::parse_field(by, i, l)?)
+                            )
                         )
                     },
-                    _ => panic!("Malformed sensor response '{}'", l)
+                    _ => Err(Error::parse(i, l, "Malformed sensor response"))
                 }
             }
         )
+        .collect::<Result<Vec<_>>>()?;
+    let sensors = responses.into_iter()
         .fold(
             BTreeMap::new(),
             |mut hm, (s, b)| {
                 hm.insert(s, b);
                 hm
             }
-        )
+        );
+    Ok(sensors)
 }
 
-fn in_range(sensors: &BTreeMap<(isize, isize), (isize, isize)>, pos: (isize, isize)) -> bool {
+fn in_range(sensors: &SensorResponses, pos: (isize, isize)) -> bool {
     sensors.iter()
-        .map(
+        .any(
             |(s, b)| {
-                if pos == *s || pos == *b {
-                    false
-                } else if manhatan_distance(*s, *b) >= manhatan_distance(*s, pos) {
-                    true
-                } else {
-                    false
-                }
+                pos != *s && pos != *b && manhatan_distance(*s, *b) >= manhatan_distance(*s, pos)
             }
         )
-        .any(|b| b)       
 }
 
-fn area_bounds(sensors: &BTreeMap<(isize, isize), (isize, isize)>) -> ((isize, isize), (isize, isize)) {
+fn area_bounds(sensors: &SensorResponses) -> ((isize, isize), (isize, isize)) {
     let objects = sensors.iter()
         .fold(
             HashSet::new(),
@@ -81,127 +115,54 @@ fn area_bounds(sensors: &BTreeMap<(isize, isize), (isize, isize)>) -> ((isize, i
     ((min_x, max_x), (min_y, max_y))
 }
 
-#[allow(dead_code)]
-fn print_ranges(sensors: &BTreeMap<(isize, isize), (isize, isize)>, depth: isize) {
-    let ((min_x, max_x), (min_y, max_y)) = area_bounds(sensors);
-    
-    let beacons = sensors.iter()
-        .fold(
-            HashSet::new(),
-            |mut hs, (_, b)| {
-                hs.insert(*b);
-                hs
-            }
-        );
-
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            if sensors.contains_key(&(x, y)) {
-                print!("S")
-            } else if beacons.contains(&(x, y)) {
-                print!("B")
-            } else if in_range(sensors, (x, y)) {
-                print!("#")
-            } else if y == depth {
-                print!("~")
-            } else {
-                print!(".")
-            }
-        }
-        println!()
+pub fn probe(sensors: &SensorResponses, depth: isize) -> Result<u32> {
+    if sensors.is_empty() {
+        return Err(Error::invalid_state("No sensor responses"));
     }
-}
-
-fn probe(cave: &str, depth: isize) -> u32 {
-    let sensors = build_sensor_response(cave);
-    let ((min_x, max_x), (_, _)) = area_bounds(&sensors);
-    ((min_x)..=max_x).into_iter()
-        .filter_map(
-            |x| {
-                if in_range(&sensors, (x, depth)) {
-                    Some(x)
-                } else {
-                    None
-                }
-            }
+    let ((min_x, max_x), (_, _)) = area_bounds(sensors);
+    let covered = ((min_x)..=max_x)
+        .filter(
+            |x| in_range(sensors, (*x, depth))
         )
-        .count() as u32
+        .count() as u32;
+    Ok(covered)
 }
 
-pub fn probe_depth(input_path: &str, depth: isize) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => probe(&content, depth),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn probe_depth(input_path: &str, depth: isize) -> Result<u32> {
+    probe(&Day15::parse_file(input_path)?, depth)
 }
 
-fn tune_frequency(cave: &str) -> u128 {
-    let sensors = build_sensor_response(cave);
-    (0..=BOUND).into_iter()
-        .filter_map(
-            |y| {
-                let range = sensors.iter()
-                    .fold(
-                        std::ops::RangeInclusive::new(0, 0),
-                        |r, (s, b)| {
-                            let bounds = r.into_inner();
-                            let radius = manhatan_distance(*s, *b);
-                            if radius < manhatan_distance(*s, (s.0, y)) {
-                                std::ops::RangeInclusive::new(bounds.0, bounds.1)
-                            } else {
-                                let vert_dist = s.1 - y;
-                                let hor_dist = radius as isize - vert_dist.abs();
-                                let range = (s.0 - hor_dist, s.0 + hor_dist);
-                                // o---o
-                                //        o---o
-                                if (bounds.1 + 1) < range.0 {
-                                    std::ops::RangeInclusive::new(bounds.0, bounds.1)
-                                // o---o
-                                //     o---o
-                                } else if bounds.0 < range.0 && (bounds.1 + 1) >= range.0 {
-                                    std::ops::RangeInclusive::new(bounds.0, bounds.1.max(range.1))
-                                //  o---o
-                                // o-------o
-                                } else if bounds.0 >= range.0 {
-                                    std::ops::RangeInclusive::new(bounds.0.min(range.0), bounds.1.max(range.1))
-                                } else {
-                                    std::ops::RangeInclusive::new(bounds.0, bounds.1)
-                                }
-                            }
-                        }
-                    )
-                    .into_inner();
-                match (range.0.cmp(&0), range.1.cmp(&BOUND)) {
-                    (std::cmp::Ordering::Less | std::cmp::Ordering::Equal, std::cmp::Ordering::Equal | std::cmp::Ordering::Greater) => {
-                        None
-                    },
-                    (std::cmp::Ordering::Greater, std::cmp::Ordering::Equal | std::cmp::Ordering::Greater) => {
-                        Some((range.0 - 1) as u128 * 4_000_000 + y as u128)
-                    },
-                    (std::cmp::Ordering::Less | std::cmp::Ordering::Equal, std::cmp::Ordering::Less) => {
-                        Some((range.1 + 1) as u128 * 4_000_000 + y as u128)
-                    },
-                    _ => panic!("Depth not fully covered ({:?})", range)
-                }
+pub fn tune_frequency(sensors: &SensorResponses, bound: isize) -> Result<u128> {
+    let mut ranges = Vec::with_capacity(sensors.len());
+    for y in 0..=bound {
+        ranges.clear();
+        ranges.extend(
+            sensors.iter()
+                .filter_map(
+                    |(s, b)| {
+                        let reach = manhatan_distance(*s, *b) as isize - s.1.abs_diff(y) as isize;
+                        (reach >= 0).then_some((s.0 - reach, s.0 + reach))
+                    }
+                )
+        );
+        ranges.sort_unstable();
+        // Sweep the row left to right, the first position no range reaches is the beacon
+        let mut x = 0;
+        for (from, to) in ranges.iter() {
+            if *from > x {
+                break;
             }
-        )
-        .max()
-        .unwrap()
-}
-
-pub fn find_beacon_frequency(input_path: &str) -> u128 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => tune_frequency(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
+            x = x.max(to + 1);
+        }
+        if x <= bound {
+            return Ok(x as u128 * FREQUENCY_MULTIPLIER + y as u128);
         }
     }
+    Err(Error::invalid_state("Every position within bounds is covered"))
+}
+
+pub fn find_beacon_frequency(input_path: &str, bound: isize) -> Result<u128> {
+    tune_frequency(&Day15::parse_file(input_path)?, bound)
 }
 
 #[cfg(test)]
@@ -226,11 +187,26 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3"#;
 
     #[test]
     fn test_input1() {
-        assert_eq!(probe(TEST_INP1, 10), 26)
+        assert_eq!(probe(&Day15::parse(TEST_INP1).unwrap(), 10).unwrap(), 26)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(tune_frequency(TEST_INP1), 56000011)
+        assert_eq!(tune_frequency(&Day15::parse(TEST_INP1).unwrap(), 20).unwrap(), 56000011)
+    }
+
+    #[test]
+    fn test_beacon_in_first_column() {
+        let cave = r#"Sensor at x=2, y=0: closest beacon is at x=-2, y=-2
+Sensor at x=3, y=1: closest beacon is at x=3, y=3
+Sensor at x=5, y=3: closest beacon is at x=7, y=5
+Sensor at x=5, y=6: closest beacon is at x=8, y=4"#;
+        assert_eq!(tune_frequency(&Day15::parse(cave).unwrap(), 6).unwrap(), 5)
+    }
+
+    #[test]
+    fn test_malformed_response() {
+        let cave = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9, y=16 closest beacon is at x=10, y=16";
+        assert!(matches!(Day15::parse(cave), Err(Error::Parse { line: 2, .. })))
     }
 }
\ No newline at end of file
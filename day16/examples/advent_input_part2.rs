@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day16::release_pressure_with_help("./day16/resources/input.txt");
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,72 @@
+use common::{Generated, Rng};
+
+use crate::{best_disjoint_pair, best_per_set};
+
+const ALONE: u32 = 30;
+const WITH_HELP: u32 = 26;
+
+fn name(index: usize) -> String {
+    format!("{}{}", (b'A' + (index / 26) as u8) as char, (b'A' + (index % 26) as u8) as char)
+}
+
+/// Connected cave of `size` valves (between 2 and 40), up to a third of them with a flow
+///
+/// Answers come from trying every order of opening the valves, pairing the best disjoint sets for part 2.
+/// Both share the solver's walk, so the day's tests check part 2 against a joint search on small caves.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(2, 40);
+    let mut tunnels = vec![vec![false; count]; count];
+    // A random tree keeps every valve reachable, the extra tunnels add loops to it
+    let mut links: Vec<(usize, usize)> = (1..count).map(|valve| (valve, rng.below(valve))).collect();
+    links.extend((0..count / 3).map(|_| (rng.below(count), rng.below(count))).filter(|(a, b)| a != b));
+    for (a, b) in links {
+        tunnels[a][b] = true;
+        tunnels[b][a] = true;
+    }
+    let mut flows = vec![0; count];
+    let mut flowing: Vec<usize> = (1..count).collect();
+    rng.shuffle(&mut flowing);
+    for valve in flowing.into_iter().take(rng.between(1, (count as i64 / 3).clamp(1, 10)) as usize) {
+        flows[valve] = rng.between(1, 25) as u32;
+    }
+
+    // Floyd-Warshall over the tunnels
+    let mut distances: Vec<Vec<u32>> = (0..count)
+        .map(|a| (0..count).map(|b| if a == b { 0 } else if tunnels[a][b] { 1 } else { u32::MAX / 4 }).collect())
+        .collect();
+    for k in 0..count {
+        for a in 0..count {
+            for b in 0..count {
+                distances[a][b] = distances[a][b].min(distances[a][k] + distances[k][b]);
+            }
+        }
+    }
+
+    let alone = best_per_set(&distances, &flows, 0, ALONE).into_values().max().unwrap();
+    let with_help = best_disjoint_pair(&best_per_set(&distances, &flows, 0, WITH_HELP));
+
+    let mut lines: Vec<String> = (0..count)
+        .map(
+            |valve| {
+                let leads: Vec<String> = (0..count).filter(|other| tunnels[valve][*other]).map(name).collect();
+                let (tunnel, lead, to) = if leads.len() == 1 { ("tunnel", "leads", "valve") } else { ("tunnels", "lead", "valves") };
+                format!("Valve {} has flow rate={}; {} {} to {} {}", name(valve), flows[valve], tunnel, lead, to, leads.join(", "))
+            }
+        )
+        .collect();
+    rng.shuffle(&mut lines);
+    Generated::solved(lines.join("\n"), alone, with_help)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day16;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..10 {
+            generate(&mut Rng::new(seed), 18).check::<Day16>().unwrap();
+        }
+    }
+}
@@ -1,16 +1,55 @@
+pub mod generator;
+
 use std::collections::{HashMap, VecDeque, HashSet};
 
-const MAX_TRAVEL: u32 = std::u16::MAX as u32;
+use common::{trace::{self, Event, Level}, Error, Json, Puzzle, Result};
 
-fn build_valve_layout(cave: &str) -> (HashMap<String, u32>, HashMap<String, Vec<String>>) {
-    cave.replace(';', "")
-        .replace(',', "")
-        .replace('=', " ")
-        .lines()
-        .fold(
+const MAX_TRAVEL: u32 = u16::MAX as u32;
+const START: &str = "AA";
+
+pub type ValveLayout = (HashMap<String, u32>, HashMap<String, Vec<String>>);
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    /// Flow rate of each valve and the tunnels leaving it
+    type Input = ValveLayout;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_valve_layout(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        depressurize(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        depressurize_with_help(input)
+    }
+
+    fn summary((valves, _): &Self::Input) -> Json {
+        Json::object()
+            .with("valves", valves.len())
+            .with("flowing", valves.values().filter(|rate| **rate > 0).count())
+    }
+
+    /// Valves in the order they're opened on the best lone route, starting from `AA`
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        let (_, opened) = best_route(input)?;
+        Ok(Json::object().with("opened", opened))
+    }
+}
+
+fn build_valve_layout(cave: &str) -> Result<ValveLayout> {
+    let (valves, tunnels): ValveLayout = cave.lines()
+        .enumerate()
+        .try_fold(
             (HashMap::new(), HashMap::new()),
-            |(mut valves, mut tunnels), l| {
-                let spl = l.split_terminator(' ');
+            |(mut valves, mut tunnels), (i, l)| {
+                let spl = l.split_terminator([' ', ';', ',', '='])
+                    .filter(|t| !t.is_empty());
                 let v = Vec::from_iter(spl);
                 match v.as_slice() {
                     [
@@ -26,14 +65,23 @@ fn build_valve_layout(cave: &str) -> (HashMap<String, u32>, HashMap<String, Vec<
                         "valve" | "valves",
                         a @ ..
                     ] => {
-                        valves.insert(String::from(*val), fr.parse().unwrap());
+                        valves.insert(String::from(*val), common::parse_field(fr, i, l)?);
                         tunnels.insert(String::from(*val), a.iter().map(|t| String::from(*t)).collect());
                     },
-                    _ => panic!("Malformed valve report '{l}'")
+                    _ => return Err(Error::parse(i, l, "Malformed valve report"))
                 }
-                (valves, tunnels)
+                Ok((valves, tunnels))
             }
-        )
+        )?;
+    if !valves.contains_key(START) {
+        return Err(Error::invalid_state(format!("No starting valve {}", START)));
+    }
+    for (valve, leads_to) in &tunnels {
+        if let Some(unknown) = leads_to.iter().find(|t| !valves.contains_key(*t)) {
+            return Err(Error::invalid_state(format!("Valve {} leads to unknown valve {}", valve, unknown)));
+        }
+    }
+    Ok((valves, tunnels))
 }
 
 fn build_travel_matrix(tunnels: &HashMap<String, Vec<String>>) -> HashMap<(String, String), u32> {
@@ -61,17 +109,15 @@ fn build_travel_matrix(tunnels: &HashMap<String, Vec<String>>) -> HashMap<(Strin
 }
 
 fn max_expected_depressure(
-    position: &String,
+    position: &str,
     next: String,
     time_left: u32,
     valves: &HashMap<String, u32>,
     travel: &HashMap<(String, String), u32>
 ) -> (u32, u32) {
-    let expected_travel = *travel.get(&(position.clone(), next.clone())).unwrap();
+    let expected_travel = *travel.get(&(position.to_string(), next.clone())).unwrap_or(&MAX_TRAVEL);
     let flow = valves.get(&next).unwrap();
-    if *flow == 0 {
-        ((expected_travel + 1), 0)
-    } else if expected_travel >= time_left {
+    if *flow == 0 || expected_travel >= time_left {
         ((expected_travel + 1), 0)
     } else {
         ((expected_travel + 1), (time_left - (expected_travel + 1)) * flow)
@@ -86,16 +132,16 @@ fn fan_out(
     travel_time: &HashMap<(String, String), u32>,
     opened: &HashSet<String>
 ) -> Vec<(String, u32, u32)> {
-    valves.iter()
+    valves.keys()
         .filter_map(
-            |(dest, _)| {
+            |dest| {
                 if !opened.contains(dest) && dest != cur && dest != cur_partner {
                     let (time_taken, expected) = max_expected_depressure(
-                        &cur,
+                        cur,
                         dest.clone(),
                         time_left,
-                        &valves,
-                        &travel_time
+                        valves,
+                        travel_time
                     );
                     if time_left >= time_taken && expected > 0 {
                         Some((dest.clone(), time_taken, expected))
@@ -110,25 +156,32 @@ fn fan_out(
         .collect()
 }
 
-fn depressurize(cave: &str) -> u32 {
-    let (valves, tunnels) = build_valve_layout(cave);
-    let travel_time = build_travel_matrix(&tunnels);
+/// Pressure released alone and the valves opened for it, in order
+fn best_route((valves, tunnels): &ValveLayout) -> Result<(u32, Vec<String>)> {
+    let travel_time = build_travel_matrix(tunnels);
     let mut queue = VecDeque::new();
-    queue.push_back((String::from("AA"), 30, 0, vec![String::from("AA")]));
+    queue.push_back((String::from(START), 30, 0, vec![String::from(START)]));
     let mut ended = vec![];
     while !queue.is_empty() {
         let (cur, time_left, released, opened) = queue.pop_front().unwrap();
-        println!("Currently at {} with {} minutes left, expected output of {}, opened {:?}", cur, time_left, released, opened);
+        trace::emit(
+            Level::Trace,
+            || Event::StateVisited {
+                state: format!("{}, opened {}", cur, opened.join(" ")),
+                time_left,
+                score: released
+            }
+        );
         let fanned = fan_out(
             &cur,
-            &String::from("AA"),
+            &String::from(START),
             time_left,
-            &valves,
+            valves,
             &travel_time,
-            &HashSet::from_iter(opened.iter().map(|s| s.clone()))
+            &HashSet::from_iter(opened.iter().cloned())
         );
         if fanned.is_empty() {
-            ended.push(released)
+            ended.push((released, opened))
         } else {
             fanned.iter()
                 .for_each(
@@ -147,62 +200,153 @@ fn depressurize(cave: &str) -> u32 {
                 )
         }
     }
-    ended.into_iter()
-        .max()
-        .unwrap()
+    Ok(
+        ended.into_iter()
+            .max_by_key(|(released, _)| *released)
+            .unwrap_or((0, vec![String::from(START)]))
+    )
+}
+
+fn depressurize(layout: &ValveLayout) -> Result<u32> {
+    best_route(layout).map(|(released, _)| released)
+}
+
+pub fn release_pressure(input_path: &str) -> Result<u32> {
+    Day16::part1(&Day16::parse_file(input_path)?)
+}
+
+/// Most pressure a lone walker from `start` releases in `minutes` for each set of valves it opens, the sets
+/// as bit masks over the indices of `flows`
+pub(crate) fn best_per_set(distances: &[Vec<u32>], flows: &[u32], start: usize, minutes: u32) -> HashMap<u64, u32> {
+    fn visit(
+        distances: &[Vec<u32>],
+        flows: &[u32],
+        at: usize,
+        left: u32,
+        opened: u64,
+        released: u32,
+        best: &mut HashMap<u64, u32>
+    ) {
+        trace::emit(
+            Level::Trace,
+            || Event::StateVisited { state: format!("valve {}, opened {:b}", at, opened), time_left: left, score: released }
+        );
+        let entry = best.entry(opened).or_insert(0);
+        *entry = (*entry).max(released);
+        for (valve, flow) in flows.iter().enumerate() {
+            let cost = distances[at][valve] + 1;
+            if *flow > 0 && opened & (1 << valve) == 0 && cost < left {
+                let left = left - cost;
+                visit(distances, flows, valve, left, opened | (1 << valve), released + flow * left, best);
+            }
+        }
+    }
+
+    let mut best = HashMap::new();
+    visit(distances, flows, start, minutes, 0, 0, &mut best);
+    best
 }
 
-pub fn release_pressure(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => depressurize(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
+/// Most released by two walkers opening disjoint sets of valves, given the best lone walk for each set
+pub(crate) fn best_disjoint_pair(walks: &HashMap<u64, u32>) -> u32 {
+    let mut walks: Vec<(u64, u32)> = walks.iter().map(|(set, released)| (*set, *released)).collect();
+    walks.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let mut most = 0;
+    for (i, (yours, released)) in walks.iter().enumerate() {
+        // Walks are sorted, so no later pair beats twice the current one
+        if released * 2 <= most {
+            break;
+        }
+        if let Some((_, elephants)) = walks[i..].iter().find(|(elephants, _)| yours & elephants == 0) {
+            most = most.max(released + elephants);
         }
     }
+    most
 }
 
-fn depressurize_with_help(cave: &str) -> u32 {
-    let (valves, tunnels) = build_valve_layout(cave);
-    let travel_time = build_travel_matrix(&tunnels);
-    let mut queue = VecDeque::new();
-    queue.push_back(
-        (
-            (String::from("AA"), 0, String::from("AA")),
-            (String::from("AA"), 0, String::from("AA")),
-            26,
-            0,
-            vec![String::from("AA")]
-        )
-    );
-    let mut ended = vec![];
-    while !queue.is_empty() {
-        let (
-            cur,
-            eleph,
-            time_left,
-            released,
-            opened
-        ) = queue.pop_front().unwrap();
-        // println!("===");
-        // println!("You are at {}, moving towards {} ariving in {} minutes", cur.0, cur.2, cur.1);
-        // println!("Elephant at {}, moving towards {} ariving in {} minutes", eleph.0, eleph.2, eleph.1);
-        // println!("{} minutes left, expected output of {}, opened {:?}",time_left, released, opened);
-        if time_left == 0 {
-            ended.push(released)
-        } else if cur.1 == 0 {
-            let fanned = fan_out(
-                &cur.2,
-                &eleph.2,
+/// You and the elephant open disjoint sets of valves, so the best pair of lone walks with nothing in
+/// common gives the most released together
+fn depressurize_with_help((valves, tunnels): &ValveLayout) -> Result<u32> {
+    let travel_time = build_travel_matrix(tunnels);
+    // As in part 1 the walks start at AA and never open it
+    let mut flowing: Vec<&String> = valves.iter()
+        .filter(|(valve, rate)| **rate > 0 && *valve != START)
+        .map(|(valve, _)| valve)
+        .collect();
+    flowing.sort();
+    if flowing.len() >= u64::BITS as usize {
+        return Err(Error::invalid_state(format!("{} flowing valves are too many to pair walks", flowing.len())));
+    }
+    let start = String::from(START);
+    let spots: Vec<&String> = std::iter::once(&start).chain(flowing).collect();
+    let distances: Vec<Vec<u32>> = spots.iter()
+        .map(|a| spots.iter().map(|b| *travel_time.get(&((*a).clone(), (*b).clone())).unwrap_or(&MAX_TRAVEL)).collect())
+        .collect();
+    let flows: Vec<u32> = spots.iter()
+        .enumerate()
+        .map(|(i, valve)| if i == 0 { 0 } else { valves[*valve] })
+        .collect();
+    Ok(best_disjoint_pair(&best_per_set(&distances, &flows, 0, 26)))
+}
+
+pub fn release_pressure_with_help(input_path: &str) -> Result<u32> {
+    Day16::part2(&Day16::parse_file(input_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::Rng;
+
+    const TEST_INP1: &str = 
+r#"Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II"#;
+
+    /// Part 2 moving you and the elephant together, kept to check the paired walks against
+    fn joint_search((valves, tunnels): &ValveLayout) -> u32 {
+        let travel_time = build_travel_matrix(tunnels);
+        let mut queue = VecDeque::new();
+        queue.push_back(
+            (
+                (String::from(START), 0, String::from(START)),
+                (String::from(START), 0, String::from(START)),
+                26,
+                0,
+                vec![String::from(START)]
+            )
+        );
+        let mut ended = vec![];
+        while !queue.is_empty() {
+            let (
+                cur,
+                eleph,
                 time_left,
-                &valves,
-                &travel_time,
-                &HashSet::from_iter(opened.iter().map(|s| s.clone()))
-            );
-            if fanned.is_empty() {
+                released,
+                opened
+            ) = queue.pop_front().unwrap();
+            if time_left == 0 {
                 ended.push(released)
-            } else {
+            } else if cur.1 == 0 {
+                let fanned = fan_out(
+                    &cur.2,
+                    &eleph.2,
+                    time_left,
+                    valves,
+                    &travel_time,
+                    &HashSet::from_iter(opened.iter().cloned())
+                );
+                // Leaving the remaining valves to the elephant can beat racing it to them
+                let mut idle = opened.clone();
+                idle.push(cur.2.clone());
+                queue.push_back(((cur.2.clone(), time_left, cur.2.clone()), eleph.clone(), time_left, released, idle));
                 fanned.iter()
                     .for_each(
                         |(dest, time_taken, expected)| {
@@ -219,19 +363,18 @@ fn depressurize_with_help(cave: &str) -> u32 {
                             )
                         }
                     )
-            }
-        } else if eleph.1 == 0 {
-            let fanned = fan_out(
-                &eleph.2,
-                &cur.2,
-                time_left,
-                &valves,
-                &travel_time,
-                &HashSet::from_iter(opened.iter().map(|s| s.clone()))
-            );
-            if fanned.is_empty() {
-                ended.push(released)
-            } else {
+            } else if eleph.1 == 0 {
+                let fanned = fan_out(
+                    &eleph.2,
+                    &cur.2,
+                    time_left,
+                    valves,
+                    &travel_time,
+                    &HashSet::from_iter(opened.iter().cloned())
+                );
+                let mut idle = opened.clone();
+                idle.push(eleph.2.clone());
+                queue.push_back((cur.clone(), (eleph.2.clone(), time_left, eleph.2.clone()), time_left, released, idle));
                 fanned.iter()
                     .for_each(
                         |(dest, time_taken, expected)| {
@@ -248,59 +391,75 @@ fn depressurize_with_help(cave: &str) -> u32 {
                             )
                         }
                     )
-            }
-        } else {
-            let timestep = cur.1.min(eleph.1);
-            queue.push_back(
-                (
-                    (cur.0.clone(), cur.1 - timestep, cur.2.clone()),
-                    (eleph.0.clone(), eleph.1 - timestep, eleph.2.clone()),
-                    time_left - timestep,
-                    released,
-                    opened
+            } else {
+                let timestep = cur.1.min(eleph.1);
+                queue.push_back(
+                    (
+                        (cur.0.clone(), cur.1 - timestep, cur.2.clone()),
+                        (eleph.0.clone(), eleph.1 - timestep, eleph.2.clone()),
+                        time_left - timestep,
+                        released,
+                        opened
+                    )
                 )
-            )
+            }
         }
+        ended.into_iter().max().unwrap_or(0)
     }
-    ended.into_iter()
-        .max()
-        .unwrap()
-}
 
-pub fn release_pressure_with_help(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => depressurize_with_help(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
+    #[test]
+    fn test_input1() {
+        assert_eq!(Day16::solve_part1(TEST_INP1).unwrap(), 1651)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_input1_part2() {
+        assert_eq!(Day16::solve_part2(TEST_INP1).unwrap(), 1707)
+    }
 
-    const TEST_INP1: &str = 
-r#"Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
-Valve BB has flow rate=13; tunnels lead to valves CC, AA
-Valve CC has flow rate=2; tunnels lead to valves DD, BB
-Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
-Valve EE has flow rate=3; tunnels lead to valves FF, DD
-Valve FF has flow rate=0; tunnels lead to valves EE, GG
-Valve GG has flow rate=0; tunnels lead to valves FF, HH
-Valve HH has flow rate=22; tunnel leads to valve GG
-Valve II has flow rate=0; tunnels lead to valves AA, JJ
-Valve JJ has flow rate=21; tunnel leads to valve II"#;
+    #[test]
+    fn test_opened_order() {
+        let artefacts = Day16::artefacts(&Day16::parse(TEST_INP1).unwrap()).unwrap();
+        assert_eq!(
+            artefacts.get("opened"),
+            Some(&Json::from(vec!["AA", "DD", "BB", "JJ", "HH", "EE", "CC"]))
+        )
+    }
 
     #[test]
-    fn test_input1() {
-        assert_eq!(depressurize(TEST_INP1), 1651)
+    fn test_unknown_tunnel() {
+        let cave = TEST_INP1.replace("valves EE, GG", "valves EE, ZZ");
+        assert!(matches!(Day16::parse(&cave), Err(Error::InvalidState(_))))
     }
 
     #[test]
-    fn test_input1_part2() {
-        assert_eq!(depressurize_with_help(TEST_INP1), 1707)
+    fn test_part2_leaving_valves_to_the_elephant() {
+        // Whoever opens DD runs out of valves in reach, the other still opens CC then EE
+        let cave = r#"Valve AA has flow rate=0; tunnels lead to valves BA, DA
+Valve BA has flow rate=0; tunnels lead to valves AA, BB
+Valve BB has flow rate=0; tunnels lead to valves BA, BC
+Valve BC has flow rate=0; tunnels lead to valves BB, BD
+Valve BD has flow rate=0; tunnels lead to valves BC, BE
+Valve BE has flow rate=0; tunnels lead to valves BD, BF
+Valve BF has flow rate=0; tunnels lead to valves BE, BG
+Valve BG has flow rate=0; tunnels lead to valves BF, CC
+Valve CC has flow rate=20; tunnels lead to valves BG, CA
+Valve CA has flow rate=0; tunnels lead to valves CC, CB
+Valve CB has flow rate=0; tunnels lead to valves CA, CD
+Valve CD has flow rate=0; tunnels lead to valves CB, EE
+Valve EE has flow rate=12; tunnel leads to valve CD
+Valve DA has flow rate=0; tunnels lead to valves AA, DB
+Valve DB has flow rate=0; tunnels lead to valves DA, DC
+Valve DC has flow rate=0; tunnels lead to valves DB, DD
+Valve DD has flow rate=3; tunnel leads to valve DC"#;
+        assert_eq!(Day16::solve_part2(cave).unwrap(), 17 * 20 + 12 * 12 + 21 * 3)
+    }
+
+    #[test]
+    fn test_part2_against_joint_search() {
+        for seed in 0..20 {
+            let layout = Day16::parse(&generator::generate(&mut Rng::new(seed), 15).input).unwrap();
+            assert_eq!(Day16::part2(&layout).unwrap(), joint_search(&layout), "seed {}", seed)
+        }
     }
 }
\ No newline at end of file
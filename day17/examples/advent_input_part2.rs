@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day17::falling_rocks_tower("./day17/resources/input.txt", 1_000_000_000_000);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+
+use common::{Generated, Rng};
+
+/// Rows of every rock from the bottom up, bit `x` set for a piece in column `x`
+const ROCKS: [&[u8]; 5] = [
+    &[0b1111],
+    &[0b010, 0b111, 0b010],
+    &[0b111, 0b100, 0b100],
+    &[0b1, 0b1, 0b1, 0b1],
+    &[0b11, 0b11]
+];
+/// Rows of the tower top compared when looking for a repeating state
+const PROFILE: usize = 64;
+
+fn fits(rows: &[u8], rock: &[u8], x: usize, y: usize) -> bool {
+    rock.iter()
+        .enumerate()
+        .all(
+            |(i, r)| {
+                let bits = (*r as u32) << x;
+                bits < 1 << 7 && rows.get(y + i).is_none_or(|row| *row as u32 & bits == 0)
+            }
+        )
+}
+
+/// Height of the tower after `count` rocks, skipping ahead once the rock, jet and top rows repeat
+fn tower_height(jets: &[bool], count: usize) -> u64 {
+    let mut rows: Vec<u8> = vec![];
+    let mut jet = 0;
+    let mut heights = vec![0];
+    let mut seen = HashMap::new();
+    for n in 0..count {
+        let rock = ROCKS[n % ROCKS.len()];
+        let (mut x, mut y) = (2usize, rows.len() + 3);
+        loop {
+            let pushed = if jets[jet] { x + 1 } else { x.wrapping_sub(1) };
+            jet = (jet + 1) % jets.len();
+            if pushed < 7 && fits(&rows, rock, pushed, y) {
+                x = pushed;
+            }
+            if y > 0 && fits(&rows, rock, x, y - 1) {
+                y -= 1;
+            } else {
+                break;
+            }
+        }
+        for (i, r) in rock.iter().enumerate() {
+            if y + i == rows.len() {
+                rows.push(0);
+            }
+            rows[y + i] |= r << x;
+        }
+        heights.push(rows.len() as u64);
+
+        let placed = n + 1;
+        if rows.len() >= PROFILE {
+            let key = (placed % ROCKS.len(), jet, rows[rows.len() - PROFILE..].to_vec());
+            if let Some(start) = seen.insert(key, placed) {
+                let period = placed - start;
+                let cycles = ((count - start) / period) as u64;
+                let growth = heights[placed] - heights[start];
+                return heights[start + (count - start) % period] + cycles * growth;
+            }
+        }
+    }
+    heights[count]
+}
+
+/// Random jet pattern of `size` pushes, the towers rebuilt row by row as bitmasks
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let jets: Vec<bool> = (0..size.max(1)).map(|_| rng.chance(50)).collect();
+    let input: String = jets.iter().map(|right| if *right { '>' } else { '<' }).collect();
+    Generated::solved(input, tower_height(&jets, 2022), tower_height(&jets, 1_000_000_000_000))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day17;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 200).check::<Day17>().unwrap();
+        }
+    }
+}
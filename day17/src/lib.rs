@@ -1,6 +1,41 @@
-use std::{iter::{Cycle, Enumerate, Peekable}, str::{Chars}, collections::{BTreeMap, VecDeque, HashMap}};
+pub mod generator;
 
-const PATTERN_WINDOW: usize = 8;
+use std::{iter::{Cycle, Enumerate, Peekable}, str::{Chars}, collections::HashMap};
+
+use common::{trace::{self, Event, Level}, Error, Json, Puzzle, Result};
+use grid::{Point, SparseGrid};
+
+const PATTERN_WINDOW: usize = 32;
+const SHAFT_WIDTH: isize = 7;
+const SHORT_TOWER: usize = 2022;
+const TALL_TOWER: usize = 1_000_000_000_000;
+
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    /// Jet pattern, only made of '<' and '>'
+    type Input = String;
+    type Answer1 = u128;
+    type Answer2 = u128;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let jets = input.trim_end();
+        check_jets(jets)?;
+        Ok(jets.to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        falling_rocks(input, SHORT_TOWER)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        falling_rocks(input, TALL_TOWER)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("jets", input.len())
+    }
+}
 
 #[derive(Debug)]
 enum Rock {
@@ -12,216 +47,150 @@ enum Rock {
 }
 
 impl Rock {
-    fn shape(&self, origin: &(usize, usize)) -> Vec<(usize, usize)> {
+    /// Offsets of the rock's pieces from its bottom left corner, `y` grows upwards
+    fn offsets(&self) -> &'static [(isize, isize)] {
         match self {
-            Rock::Flat => vec![
-                (origin.0    , origin.1),
-                (origin.0 + 1, origin.1),
-                (origin.0 + 2, origin.1),
-                (origin.0 + 3, origin.1),
-            ],
-            Rock::Tall => vec![
-                (origin.0, origin.1    ),
-                (origin.0, origin.1 + 1),
-                (origin.0, origin.1 + 2),
-                (origin.0, origin.1 + 3),
-            ],
-            Rock::Plus => vec![
-                (origin.0    , origin.1 + 1),
-                (origin.0 + 1, origin.1    ),
-                (origin.0 + 1, origin.1 + 1),
-                (origin.0 + 2, origin.1 + 1),
-                (origin.0 + 1, origin.1 + 2),
-            ],
-            Rock::L => vec![
-                (origin.0    , origin.1    ),
-                (origin.0 + 1, origin.1    ),
-                (origin.0 + 2, origin.1    ),
-                (origin.0 + 2, origin.1 + 1),
-                (origin.0 + 2, origin.1 + 2),
-            ],
-            Rock::Square => vec![
-                (origin.0    , origin.1    ),
-                (origin.0    , origin.1 + 1),
-                (origin.0 + 1, origin.1    ),
-                (origin.0 + 1, origin.1 + 1),
-            ]
+            Rock::Flat => &[(0, 0), (1, 0), (2, 0), (3, 0)],
+            Rock::Tall => &[(0, 0), (0, 1), (0, 2), (0, 3)],
+            Rock::Plus => &[(0, 1), (1, 0), (1, 1), (2, 1), (1, 2)],
+            Rock::L => &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+            Rock::Square => &[(0, 0), (0, 1), (1, 0), (1, 1)]
         }
     }
-}
 
-#[allow(dead_code)]
-fn print_shaft(shaft: &BTreeMap<usize, [bool; 7]>) {
-    shaft.iter().rev()
-        .for_each(
-            |(_, row)| {
-                print!("|");
-                row.iter()
-                    .for_each(
-                        |b| {
-                            if *b {
-                                print!("#")
-                            } else {
-                                print!(".")
-                            }
-                        }
-                    );
-                println!("|");
-            }
-        );
-    println!("+-------+");
+    fn shape(&self, origin: Point) -> impl Iterator<Item = Point> + '_ {
+        self.offsets().iter().map(move |offset| origin + Point::from(*offset))
+    }
 }
 
-fn collision(
-    rock: &Rock,
-    position: &(usize, usize),
-    shaft: &BTreeMap<usize, [bool; 7]>
-) -> bool {
-    let shape = rock.shape(position);
-    shape.into_iter()
+/// Draws the top of the shaft, the highest row first
+pub fn render_shaft(shaft: &SparseGrid<()>) -> String {
+    let rows = shaft.bounds()
+        .map(|(min, max)| (min.y..=max.y).rev().collect::<Vec<_>>())
+        .unwrap_or_default();
+    rows.into_iter()
         .map(
-            |(x, y)| {
-                if x >= 7 {
-                    true
-                } else {
-                    shaft.get(&y)
-                        .and_then(
-                            |row| Some(
-                                row.get(x)
-                                    .map(
-                                        |b| *b
-                                    )
-                                    .unwrap_or(false))
-                        )
-                        .unwrap_or(false)
-                }
+            |y| {
+                let row: String = (0..SHAFT_WIDTH)
+                    .map(|x| if shaft.contains(Point::new(x, y)) { '#' } else { '.' })
+                    .collect();
+                format!("|{}|\n", row)
             }
         )
-        .any(|b| b)
+        .collect::<String>() + "+-------+"
 }
 
-fn try_jet(jet: &char, position: &(usize, usize)) -> (usize, usize) {
-    match (jet, position.0) {
-        ('>', _) => (position.0 + 1, position.1),
-        ('<', 0) => (position.0, position.1),
-        ('<', _) => (position.0 - 1, position.1),
-        _ => panic!("Unresolved jet '{}' at {:?}", jet, position)
-    }
+fn collision(rock: &Rock, position: Point, shaft: &SparseGrid<()>) -> bool {
+    rock.shape(position)
+        .any(|p| p.x < 0 || p.x >= SHAFT_WIDTH || p.y < 0 || shaft.contains(p))
 }
 
-fn try_fall(position: &(usize, usize)) -> (usize, usize) {
-    match position.1 {
-        0 => position.clone(),
-        _ => (position.0, position.1 - 1)
+fn try_jet(jet: &char, position: Point) -> Point {
+    match jet {
+        '>' => position + Point::EAST,
+        '<' => position + Point::WEST,
+        // `check_jets` lets no other jet through
+        _ => unreachable!("Unresolved jet '{}' at {:?}", jet, position)
     }
 }
 
-fn tallest_point(shaft: &BTreeMap<usize, [bool; 7]>) -> usize {
-    shaft.keys().map(|k| *k + 1).max().unwrap_or(0)
+fn tallest_point(shaft: &SparseGrid<()>) -> usize {
+    shaft.bounds().map_or(0, |(_, max)| max.y as usize + 1)
 }
 
-fn resolve_rock(rock: &Rock, jets: &mut Peekable<Cycle<Enumerate<Chars>>>, shaft: &mut BTreeMap<usize, [bool; 7]>) {
-    const ROLLING_WINDOW: usize = 128;
-    let mut position = (2, tallest_point(shaft) + 3);
+/// Drops `rock` until it comes to rest, returning where it did
+fn resolve_rock(rock: &Rock, jets: &mut Peekable<Cycle<Enumerate<Chars>>>, shaft: &mut SparseGrid<()>) -> Point {
+    const ROLLING_WINDOW: isize = 128;
+    let mut position = Point::new(2, tallest_point(shaft) as isize + 3);
     for (_, jet) in jets {
-        let pushed = try_jet(&jet, &position);
-        position = if collision(&rock, &pushed, shaft) {
-            position
-        } else {
-            pushed
-        };
-        let fallen = try_fall(&position);
-        if collision(&rock, &fallen, shaft) || position.1 == 0 {
-            let shape = rock.shape(&position);
-            shape.into_iter()
-                .for_each(
-                    |(x, y)| {
-                        if !shaft.contains_key(&y) {
-                            shaft.insert(y, [false; 7]);
-                            if y >= ROLLING_WINDOW {
-                                shaft.remove(&(y - ROLLING_WINDOW));
-                            }
-                        }
-                        let row = shaft.get_mut(&y).unwrap();
-                        row[x] = true;
-                    }
-                );
+        let pushed = try_jet(&jet, position);
+        if !collision(rock, pushed, shaft) {
+            position = pushed;
+        }
+        // The shaft grows upwards, so falling is a step towards `y` 0
+        let fallen = position + Point::new(0, -1);
+        if collision(rock, fallen, shaft) {
+            for p in rock.shape(position) {
+                let new_row = (0..SHAFT_WIDTH).all(|x| !shaft.contains(Point::new(x, p.y)));
+                if new_row && p.y >= ROLLING_WINDOW {
+                    (0..SHAFT_WIDTH).for_each(|x| { shaft.remove(Point::new(x, p.y - ROLLING_WINDOW)); });
+                }
+                shaft.insert(p, ());
+            }
             break;
         } else {
             position = fallen;
         };
     };
+    position
+}
+
+fn check_jets(jets: &str) -> Result<()> {
+    if jets.is_empty() {
+        return Err(Error::invalid_state("No jets in the pattern"));
+    }
+    match jets.char_indices().find(|(_, c)| *c != '<' && *c != '>') {
+        Some((column, c)) => Err(Error::parse_at(0, column, jets, format!("Unknown jet '{}'", c))),
+        None => Ok(())
+    }
 }
 
-fn falling_rocks(jets: &str, stop_at: usize) -> u128 {
-    let rocks = vec![Rock::Flat, Rock::Plus, Rock::L, Rock::Tall, Rock::Square];
-    let mut rocks_iter = rocks.iter().cycle().enumerate();
+/// Top rows of the tower as bitmasks, the state a new rock falls onto
+fn skyline(shaft: &SparseGrid<()>, height: usize) -> Vec<u8> {
+    (height.saturating_sub(PATTERN_WINDOW)..height)
+        .map(
+            |y| {
+                (0..SHAFT_WIDTH)
+                    .filter(|x| shaft.contains(Point::new(*x, y as isize)))
+                    .fold(0, |row, x| row | 1 << x)
+            }
+        )
+        .collect()
+}
+
+pub fn falling_rocks(jets: &str, stop_at: usize) -> Result<u128> {
+    check_jets(jets)?;
+    let rocks = [Rock::Flat, Rock::Plus, Rock::L, Rock::Tall, Rock::Square];
     let mut jets = jets.chars().enumerate().cycle().peekable();
     
-    let mut shaft = BTreeMap::new();
+    let mut shaft = SparseGrid::new();
     
-    let mut cur = VecDeque::new();
+    let mut heights = vec![0];
     let mut repeat = HashMap::new();
-    while !repeat.contains_key(&cur) {
-        let (r_ind, rock) = rocks_iter.next().unwrap();
-        let starting_jet = jets.peek().unwrap().0;
-        if r_ind == stop_at {
-            break;
-        }
-        resolve_rock(rock, &mut jets, &mut shaft);
-
-        repeat.insert(cur.clone(), (r_ind, starting_jet, tallest_point(&shaft) as u128));
-        
-        if cur.len() == PATTERN_WINDOW {
-            cur.pop_front();
-
+    for r_ind in 0..stop_at {
+        let rest = resolve_rock(&rocks[r_ind % rocks.len()], &mut jets, &mut shaft);
+        let height = tallest_point(&shaft);
+        heights.push(height as u128);
+        trace::emit(Level::Trace, || Event::RockPlaced { rock: r_ind + 1, x: rest.x, y: rest.y, height });
+
+        let placed = r_ind + 1;
+        if height < PATTERN_WINDOW {
+            continue;
         }
-        cur.push_back(starting_jet);
-    }
-    // print_shaft(&shaft);
-    // println!("Repeats {:?}", repeat);
-    // println!("Cur ({}) {:?}", cur.len(), cur);
-    let first_pattern = repeat.get(&cur).unwrap().0 - 1;
-    // println!("First pattern {:?}", first_pattern);
-    let first_pattern_unbiased = first_pattern - PATTERN_WINDOW;
-    // println!("First pattern unbiased {:?}", first_pattern_unbiased);
-    let pattern_length = repeat.len() - first_pattern - 1;
-    // println!("pattern length {:?}", pattern_length);
-    let heights = repeat.iter()
-        .fold(
-            HashMap::new(),
-            |mut hm, (_, (r, _, he))| {
-                hm.insert(*r, *he);
-                hm
-            }
-        );
-    let head = heights[&first_pattern_unbiased];
-    let pattern = (heights[&(first_pattern_unbiased + pattern_length)] - head) * ((stop_at - first_pattern_unbiased) / pattern_length) as u128;
-    let tail = heights[&(first_pattern_unbiased - 1 + (stop_at - first_pattern_unbiased) % pattern_length)];
-    pattern + tail
-}
-
-pub fn falling_rocks_tower(input_path: &str, stop_at: usize) -> u128 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => falling_rocks(&content, stop_at),
-        Err(er) => {
-            println!("{}", er);
-            0
+        // The same rock pushed by the same jet onto the same top rows stacks the same way again
+        let state = (placed % rocks.len(), jets.peek().unwrap().0, skyline(&shaft, height));
+        if let Some(first_pattern) = repeat.insert(state, placed) {
+            let pattern_length = placed - first_pattern;
+            trace::emit(
+                Level::Debug,
+                || Event::PatternFound {
+                    first: first_pattern,
+                    length: pattern_length,
+                    growth: heights[placed] - heights[first_pattern]
+                }
+            );
+            let pattern = (heights[placed] - heights[first_pattern]) * ((stop_at - first_pattern) / pattern_length) as u128;
+            let tail = heights[first_pattern + (stop_at - first_pattern) % pattern_length];
+            return Ok(pattern + tail);
         }
     }
+    // Stopped before any pattern repeated, so the tower was fully simulated
+    Ok(heights[stop_at])
 }
 
-// pub fn release_pressure_with_help(input_path: &str) -> u32 {
-//     let content = std::fs::read_to_string(input_path);
-//     match content {
-//         Ok(content) => depressurize_with_help(&content),
-//         Err(er) => {
-//             println!("{}", er);
-//             0
-//         }
-//     }
-// }
+pub fn falling_rocks_tower(input_path: &str, stop_at: usize) -> Result<u128> {
+    falling_rocks(&Day17::parse_file(input_path)?, stop_at)
+}
 
 #[cfg(test)]
 mod tests {
@@ -232,11 +201,47 @@ r#">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>"#;
 
     #[test]
     fn test_input1() {
-        assert_eq!(falling_rocks(TEST_INP1, 2022), 3068)
+        assert_eq!(Day17::solve_part1(TEST_INP1).unwrap(), 3068)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(falling_rocks(TEST_INP1, 1_000_000_000_000), 1514285714288)
+        assert_eq!(Day17::solve_part2(TEST_INP1).unwrap(), 1514285714288)
+    }
+
+    #[test]
+    fn test_short_jet_patterns() {
+        // The tower repeats within a handful of rocks, well before a window of jets fills up
+        assert_eq!(Day17::solve_part1("<").unwrap(), 4448);
+        assert_eq!(Day17::solve_part1(">>><<").unwrap(), 3236)
+    }
+
+    #[test]
+    fn test_render_shaft() {
+        let mut shaft = SparseGrid::new();
+        let mut jets = TEST_INP1.chars().enumerate().cycle().peekable();
+        resolve_rock(&Rock::Flat, &mut jets, &mut shaft);
+        resolve_rock(&Rock::Plus, &mut jets, &mut shaft);
+        assert_eq!(render_shaft(&shaft), "|...#...|\n|..###..|\n|...#...|\n|..####.|\n+-------+")
+    }
+
+    #[test]
+    fn test_traced_rocks() {
+        let (height, events) = trace::collect(Level::Trace, || falling_rocks(TEST_INP1, 2).unwrap());
+        assert_eq!(height, 4);
+        assert_eq!(
+            events,
+            [
+                Event::RockPlaced { rock: 1, x: 2, y: 0, height: 1 },
+                Event::RockPlaced { rock: 2, x: 2, y: 1, height: 4 }
+            ]
+        )
+    }
+
+    #[test]
+    fn test_unknown_jet() {
+        assert!(matches!(Day17::parse(">><<v<>"), Err(Error::Parse { line: 1, column: 5, .. })));
+        assert!(matches!(falling_rocks(">><<v<>", 10), Err(Error::Parse { line: 1, column: 5, .. })));
+        assert!(matches!(falling_rocks("", 10), Err(Error::InvalidState(_))))
     }
 }
\ No newline at end of file
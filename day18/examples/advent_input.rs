@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day18::falling_rocks_surface("./day18/resources/input.txt", false);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
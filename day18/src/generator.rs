@@ -0,0 +1,73 @@
+use common::{Generated, Rng};
+
+const STEPS: [(isize, isize, isize); 6] = [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)];
+
+/// Cubes filling about half of a `size` wide box, faces counted on a padded voxel array
+///
+/// The exterior is flood filled from a corner of the padding, the outer surface is made of the faces touching it.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let side = size.max(1);
+    // One empty layer all around so the flood fill can wrap the droplet
+    let padded = side + 2;
+    let index = |x: usize, y: usize, z: usize| (x * padded + y) * padded + z;
+    let mut lava = vec![false; padded * padded * padded];
+    let mut cubes = vec![];
+    for x in 0..side {
+        for y in 0..side {
+            for z in 0..side {
+                if rng.chance(45) {
+                    lava[index(x + 1, y + 1, z + 1)] = true;
+                    cubes.push((x, y, z));
+                }
+            }
+        }
+    }
+    rng.shuffle(&mut cubes);
+
+    let neighbours = |(x, y, z): (usize, usize, usize)| {
+        STEPS.iter()
+            .map(move |(dx, dy, dz)| (x as isize + dx, y as isize + dy, z as isize + dz))
+            .filter(|(x, y, z)| [x, y, z].iter().all(|c| (0..padded as isize).contains(*c)))
+            .map(|(x, y, z)| (x as usize, y as usize, z as usize))
+    };
+    let mut outside = vec![false; lava.len()];
+    outside[0] = true;
+    let mut stack = vec![(0, 0, 0)];
+    while let Some(cell) = stack.pop() {
+        for (x, y, z) in neighbours(cell) {
+            let i = index(x, y, z);
+            if !lava[i] && !outside[i] {
+                outside[i] = true;
+                stack.push((x, y, z));
+            }
+        }
+    }
+
+    let (mut surface, mut exterior) = (0, 0);
+    for (x, y, z) in cubes.iter() {
+        for (nx, ny, nz) in neighbours((x + 1, y + 1, z + 1)) {
+            let i = index(nx, ny, nz);
+            surface += !lava[i] as u32;
+            exterior += outside[i] as u32;
+        }
+    }
+
+    let input = cubes.iter()
+        .map(|(x, y, z)| format!("{},{},{}", x, y, z))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(input, surface, exterior)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day18;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..10 {
+            generate(&mut Rng::new(seed), 10).check::<Day18>().unwrap();
+        }
+    }
+}
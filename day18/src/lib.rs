@@ -1,16 +1,45 @@
+pub mod generator;
+
 use std::collections::{HashSet, VecDeque};
 
-fn get_cubes(cubes: &str) -> HashSet<(usize, usize, usize)> {
+use common::{Error, Json, Puzzle, Result};
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    type Input = HashSet<(usize, usize, usize)>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        get_cubes(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(falling_rocks(input, false))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(falling_rocks(input, true))
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("cubes", input.len())
+    }
+}
+
+fn get_cubes(cubes: &str) -> Result<HashSet<(usize, usize, usize)>> {
     cubes.lines()
+        .enumerate()
         .map(
-            |cube| {
+            |(i, cube)| {
                 let coords: Vec<usize> = cube.split_terminator(",")
-                    .map(|c| c.parse().unwrap())
-                    .collect();
+                    .map(|c| common::parse_field(c, i, cube))
+                    .collect::<Result<_>>()?;
                 if let &[x, y, z] = coords.as_slice() {
-                    (x, y, z)
+                    Ok((x, y, z))
                 } else {
-                    panic!("Failed to parse cube '{:?}'", cube)
+                    Err(Error::parse(i, cube, "Expected 3 coordinates"))
                 }
             }
         )
@@ -49,9 +78,12 @@ fn get_total_surface(cubes: &HashSet<(usize, usize, usize)>, occupied: &mut Hash
 }
 
 fn get_pockets_surface(occupied: &HashSet<(usize, usize, usize)>) -> u32 {
-    let max_x = occupied.iter().map(|a| a.0).max_by(|a, b| a.cmp(&b)).unwrap();
-    let max_y = occupied.iter().map(|a| a.1).max_by(|a, b| a.cmp(&b)).unwrap();
-    let max_z = occupied.iter().map(|a| a.2).max_by(|a, b| a.cmp(&b)).unwrap();
+    if occupied.is_empty() {
+        return 0;
+    }
+    let max_x = occupied.iter().map(|a| a.0).max_by(|a, b| a.cmp(b)).unwrap();
+    let max_y = occupied.iter().map(|a| a.1).max_by(|a, b| a.cmp(b)).unwrap();
+    let max_z = occupied.iter().map(|a| a.2).max_by(|a, b| a.cmp(b)).unwrap();
     
     let mut air = HashSet::new();
     let mut queue = VecDeque::new();
@@ -97,10 +129,9 @@ fn get_pockets_surface(occupied: &HashSet<(usize, usize, usize)>) -> u32 {
     get_total_surface(&air, &mut occupied)
 }
 
-fn falling_rocks(cubes: &str, remove_pockets: bool) -> u32 {
+pub fn falling_rocks(cubes: &HashSet<(usize, usize, usize)>, remove_pockets: bool) -> u32 {
     let mut occupied = HashSet::new();
-    let cubes = get_cubes(cubes);
-    let total_surface = get_total_surface(&cubes, &mut occupied);
+    let total_surface = get_total_surface(cubes, &mut occupied);
     if remove_pockets {
         total_surface - get_pockets_surface(&occupied)
     } else {
@@ -108,28 +139,10 @@ fn falling_rocks(cubes: &str, remove_pockets: bool) -> u32 {
     }
 }
 
-pub fn falling_rocks_surface(input_path: &str, remove_pockets: bool) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => falling_rocks(&content, remove_pockets),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn falling_rocks_surface(input_path: &str, remove_pockets: bool) -> Result<u32> {
+    Ok(falling_rocks(&Day18::parse_file(input_path)?, remove_pockets))
 }
 
-// pub fn release_pressure_with_help(input_path: &str) -> u32 {
-//     let content = std::fs::read_to_string(input_path);
-//     match content {
-//         Ok(content) => depressurize_with_help(&content),
-//         Err(er) => {
-//             println!("{}", er);
-//             0
-//         }
-//     }
-// }
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,11 +164,16 @@ r#"2,2,2
 
     #[test]
     fn test_input1() {
-        assert_eq!(falling_rocks(TEST_INP1, false), 64)
+        assert_eq!(Day18::solve_part1(TEST_INP1).unwrap(), 64)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(falling_rocks(TEST_INP1, true), 58)
+        assert_eq!(Day18::solve_part2(TEST_INP1).unwrap(), 58)
+    }
+
+    #[test]
+    fn test_malformed_cube() {
+        assert!(matches!(Day18::parse("2,2,2\n1,2"), Err(Error::Parse { line: 2, .. })))
     }
 }
\ No newline at end of file
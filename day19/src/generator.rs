@@ -0,0 +1,94 @@
+use common::{Generated, Rng};
+
+/// Ore, clay and obsidian needed by the ore, clay, obsidian and geode robots
+type Costs = [[u32; 3]; 4];
+
+/// Most geodes cracked within `time_left` minutes, choosing which robot to save up for next
+fn max_geodes(costs: &Costs, robots: [u32; 4], stock: [u32; 4], time_left: u32, best: &mut u32) {
+    *best = (*best).max(stock[3] + robots[3] * time_left);
+    // A new geode robot every remaining minute is the most that can be hoped for
+    if stock[3] + robots[3] * time_left + time_left * time_left.saturating_sub(1) / 2 <= *best {
+        return;
+    }
+    for kind in (0..4).rev() {
+        // Resources can't be spent faster than the most expensive robot asks for them
+        if kind < 3 {
+            let most = costs.iter().map(|cost| cost[kind]).max().unwrap();
+            if robots[kind] >= most || stock[kind] + robots[kind] * time_left >= most * time_left {
+                continue;
+            }
+        }
+        let wait = (0..3)
+            .filter(|r| costs[kind][*r] > stock[*r])
+            .map(|r| (robots[r] > 0).then(|| (costs[kind][r] - stock[r]).div_ceil(robots[r])))
+            .try_fold(0, |wait, needed| needed.map(|needed| wait.max(needed)));
+        match wait {
+            Some(wait) if wait + 1 < time_left => {
+                let mut stock = stock;
+                (0..4).for_each(|r| stock[r] += robots[r] * (wait + 1));
+                (0..3).for_each(|r| stock[r] -= costs[kind][r]);
+                let mut robots = robots;
+                robots[kind] += 1;
+                max_geodes(costs, robots, stock, time_left - wait - 1, best);
+            },
+            _ => ()
+        }
+    }
+}
+
+fn cracked(costs: &Costs, minutes: u32) -> u32 {
+    let mut best = 0;
+    max_geodes(costs, [1, 0, 0, 0], [0; 4], minutes, &mut best);
+    best
+}
+
+/// `size` blueprints priced like the puzzle's, each searched depth first over the next robot to build
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let blueprints: Vec<Costs> = (0..size.max(1))
+        .map(
+            |_| {
+                let mut price = |low, high| rng.between(low, high) as u32;
+                [
+                    [price(2, 4), 0, 0],
+                    [price(2, 4), 0, 0],
+                    [price(2, 4), price(5, 20), 0],
+                    [price(2, 4), 0, price(5, 20)]
+                ]
+            }
+        )
+        .collect();
+
+    let input = blueprints.iter()
+        .enumerate()
+        .map(
+            |(i, costs)| format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+                Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+                i + 1, costs[0][0], costs[1][0], costs[2][0], costs[2][1], costs[3][0], costs[3][2]
+            )
+        )
+        .collect::<Vec<_>>()
+        .join("\n");
+    let quality: u32 = blueprints.iter()
+        .enumerate()
+        .map(|(i, costs)| (i as u32 + 1) * cracked(costs, 24))
+        .sum();
+    let hungry: u32 = blueprints.iter()
+        .take(3)
+        .map(|costs| cracked(costs, 32))
+        .product();
+    Generated::solved(input, quality, hungry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day19;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..2 {
+            generate(&mut Rng::new(seed), 2).check::<Day19>().unwrap();
+        }
+    }
+}
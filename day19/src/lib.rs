@@ -1,17 +1,49 @@
+pub mod generator;
+
 use std::collections::{VecDeque, BTreeSet};
 
+use common::{Error, Json, Puzzle, Result};
+
+// Kept well below `u32::MAX` so adding the build minute can't overflow
+const UNREACHABLE: u32 = u16::MAX as u32;
+
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = Vec<Blueprint>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_blueprints(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(idler_game(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(idler_game_hungry_elephants(input))
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("blueprints", input.len())
+    }
+}
+
 #[derive(Debug)]
-struct Blueprint {
+pub struct Blueprint {
     ore: u32,
     clay: u32,
     obsidian: (u32, u32),
     geode: (u32, u32)
 }
 
-fn parse_blueprints(blueprints: &str) -> Vec<Blueprint> {
+fn parse_blueprints(blueprints: &str) -> Result<Vec<Blueprint>> {
     blueprints.lines()
+        .enumerate()
         .map(
-            |l| {
+            |(i, l)| {
                 let spl = l.split_whitespace();
                 let vspl = Vec::from_iter(spl);
                 if let [
@@ -21,14 +53,16 @@ fn parse_blueprints(blueprints: &str) -> Vec<Blueprint> {
                     "Each", "obsidian", "robot", "costs", obs_ore, "ore", "and", obs_clay, "clay.",
                     "Each", "geode", "robot", "costs", geo_ore, "ore", "and", geo_obs, "obsidian."
                 ] = vspl.as_slice() {
-                    Blueprint {
-                        ore: ore.parse().unwrap(),
-                        clay: clay.parse().unwrap(),
-                        obsidian: (obs_ore.parse().unwrap(), obs_clay.parse().unwrap()),
-                        geode: (geo_ore.parse().unwrap(), geo_obs.parse().unwrap())
-                    }
+                    Ok(
+                        Blueprint {
+                            ore: common::parse_field(ore, i, l)?,
+                            clay: common::parse_field(clay, i, l)?,
+                            obsidian: (common::parse_field(obs_ore, i, l)?, common::parse_field(obs_clay, i, l)?),
+                            geode: (common::parse_field(geo_ore, i, l)?, common::parse_field(geo_obs, i, l)?)
+                        }
+                    )
                 } else {
-                    panic!("Failed to parse blueprint '{}'", l)
+                    Err(Error::parse(i, l, "Failed to parse blueprint"))
                 }
             }
         )
@@ -41,21 +75,10 @@ fn time_to_robot(blueprint: &Blueprint, robots: &[u32; 4], storage: &[u32; 4]) -
         blueprint.geode.1.max(storage[2]) - storage[2]
     );
     let time_to_geode = if robots[2] > 0 {
-        (
-            (req_for_geode.0 / robots[0]) + if req_for_geode.0 % robots[0] == 0 {
-                0
-            } else {
-                1
-            }
-        ).max(
-            req_for_geode.1 / robots[2] + if req_for_geode.1 % robots[2] == 0 {
-                0
-            } else {
-                1
-            }
-        )
+        req_for_geode.0.div_ceil(robots[0])
+            .max(req_for_geode.1.div_ceil(robots[2]))
     } else {
-        std::u16::MAX as u32
+        UNREACHABLE
     };
 
     let req_for_obsidian = (
@@ -63,37 +86,17 @@ fn time_to_robot(blueprint: &Blueprint, robots: &[u32; 4], storage: &[u32; 4]) -
         blueprint.obsidian.1.max(storage[1]) - storage[1]
     );
     let time_to_obsidian = if robots[1] > 0 {
-        (
-            (req_for_obsidian.0 / robots[0]) + if req_for_obsidian.0 % robots[0] == 0 {
-                0
-            } else {
-                1
-            }
-        ).max(
-            (req_for_obsidian.1 / robots[1]) + if req_for_obsidian.1 % robots[1] == 0 {
-                0
-            } else {
-                1
-            }
-        )
-    }else {
-        std::u16::MAX as u32
+        req_for_obsidian.0.div_ceil(robots[0])
+            .max(req_for_obsidian.1.div_ceil(robots[1]))
+    } else {
+        UNREACHABLE
     };
 
     let req_for_clay = blueprint.clay.max(storage[0]) - storage[0];
-    let time_to_clay = (req_for_clay / robots[0]) + if req_for_clay % robots[0] == 0 {
-        0
-    } else {
-        1
-    };
-    
+    let time_to_clay = req_for_clay.div_ceil(robots[0]);
 
     let req_for_ore = blueprint.ore.max(storage[0]) - storage[0];
-    let time_to_ore = (req_for_ore / robots[0]) + if req_for_ore % robots[0] == 0 {
-        0
-    } else {
-        1
-    };
+    let time_to_ore = req_for_ore.div_ceil(robots[0]);
 
     [time_to_ore, time_to_clay, time_to_obsidian, time_to_geode]
 }
@@ -109,12 +112,17 @@ fn geode_cracking(blueprint: &Blueprint, time_limit: u32) -> u32 {
     let mut ended = BTreeSet::new();
     while !queue.is_empty() {
         let (target, time_left, robots, storage) = queue.pop_back().unwrap();
+        // Even building a geode robot every remaining minute can't beat the best branch
+        let best_case = storage[3] + robots[3] * time_left + time_left * time_left.saturating_sub(1) / 2;
+        if ended.last().is_some_and(|best| *best >= best_case) {
+            continue;
+        }
         // Timestep can't be lower than 1
         let timestep = time_to_robot(blueprint, &robots, &storage)[target] + 1;
         if time_left <= timestep {
             ended.insert(storage[3] + (robots[3] * time_left));
         } else {
-            let mut n_storage = storage.clone();
+            let mut n_storage = storage;
             (0..4).for_each(
                 |i| n_storage[i] += robots[i] * timestep
             );
@@ -130,10 +138,11 @@ fn geode_cracking(blueprint: &Blueprint, time_limit: u32) -> u32 {
                     n_storage[0] -= blueprint.geode.0;
                     n_storage[2] -= blueprint.geode.1;
                 },
-                _ => panic!("Unknown robot")
+                // Robots to build next are only ever picked from the four kinds
+                _ => unreachable!("Unknown robot")
             }
 
-            let mut n_robots = robots.clone();
+            let mut n_robots = robots;
             n_robots[target] += 1;
 
             (0..4).for_each(
@@ -149,7 +158,7 @@ fn geode_cracking(blueprint: &Blueprint, time_limit: u32) -> u32 {
                         1 => blueprint.obsidian.1 <= n_robots[1],
                         2 => blueprint.geode.1 <= n_robots[2],
                         3 => false,
-                        _ => panic!("Unknown robot")
+                        _ => unreachable!("Unknown robot")
                     };
                     if dont_branch {
                         ended.insert(n_storage[3] + (n_robots[3] * (time_left - timestep)));
@@ -166,11 +175,10 @@ fn geode_cracking(blueprint: &Blueprint, time_limit: u32) -> u32 {
     }
     ended.into_iter()
         .max()
-        .unwrap()
+        .unwrap_or(0)
 }
 
-fn idler_game(blueprints: &str) -> u32 {
-    let blueprints = parse_blueprints(blueprints);
+fn idler_game(blueprints: &[Blueprint]) -> u32 {
     blueprints.iter().enumerate()
         .map(
             |(i, bp)| {
@@ -180,19 +188,11 @@ fn idler_game(blueprints: &str) -> u32 {
         .sum()
 }
 
-pub fn run_idler_game(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => idler_game(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn run_idler_game(input_path: &str) -> Result<u32> {
+    Day19::part1(&Day19::parse_file(input_path)?)
 }
 
-fn idler_game_hungry_elephants(blueprints: &str) -> u32 {
-    let blueprints = parse_blueprints(blueprints);
+fn idler_game_hungry_elephants(blueprints: &[Blueprint]) -> u32 {
     blueprints.iter()
         .take(3)
         .map(
@@ -203,15 +203,8 @@ fn idler_game_hungry_elephants(blueprints: &str) -> u32 {
         .product()
 }
 
-pub fn run_idler_game_hungry_elephants(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => idler_game_hungry_elephants(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn run_idler_game_hungry_elephants(input_path: &str) -> Result<u32> {
+    Day19::part2(&Day19::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -231,157 +224,157 @@ mod tests {
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 4);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 2);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 0, 0, 0];
         let storage = [1, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 3);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 0, 0, 0];
         let storage = [2, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 2);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 0);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 0, 0, 0];
         let storage = [3, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 1);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 0);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 0, 0, 0];
         let storage = [4, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 0);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 0);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 0, 0, 0];
         let storage = [5, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 0);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 0);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [2, 0, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 2);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [3, 0, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 2);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [4, 0, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 1);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [5, 0, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 1);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [8, 0, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[0], 1);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[1], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 1, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 14);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 2, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 7);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 3, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 5);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 4, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 4);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 5, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 3);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 6, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 3);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [1, 7, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 3);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [2, 7, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 2);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [2, 13, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 2);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [2, 14, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 2);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [3, 14, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
         
         let robots = [3, 15, 0, 0];
         let storage = [0, 0, 0, 0];
         assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 1);
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 65535);
 
         let robots = [1, 0, 1, 0];
         let storage = [0, 0, 0, 0];
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 7);
 
         let robots = [7, 0, 1, 0];
         let storage = [0, 0, 0, 0];
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 7);
 
         let robots = [1, 0, 7, 0];
         let storage = [0, 0, 0, 0];
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 2);
 
         let robots = [2, 0, 7, 0];
         let storage = [0, 0, 0, 0];
-        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], std::u32::MAX);
+        assert_eq!(time_to_robot(&bp, &robots, &storage)[2], 65535);
         assert_eq!(time_to_robot(&bp, &robots, &storage)[3], 1);
         
         let robots = [100, 100, 100, 100];
@@ -398,11 +391,17 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
 
     #[test]
     fn test_input1() {
-        assert_eq!(idler_game(TEST_INP1), 33)
+        assert_eq!(Day19::solve_part1(TEST_INP1).unwrap(), 33)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(idler_game_hungry_elephants(TEST_INP1), 3596)
+        assert_eq!(Day19::solve_part2(TEST_INP1).unwrap(), 3472)
+    }
+
+    #[test]
+    fn test_malformed_blueprint() {
+        let blueprints = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs two ore.";
+        assert!(matches!(Day19::parse(blueprints), Err(Error::Parse { line: 1, .. })))
     }
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+use crate::game::{Game, Outcome, Round, OUTCOME_SYMBOLS};
+
+/// What each of the player's symbols stands for, in the order of the symbols decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mapping {
+    Shapes(Vec<usize>),
+    Outcomes(Vec<Outcome>)
+}
+
+/// One way of reading the player's column and the total score of the guide read that way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoding {
+    pub symbols: Vec<char>,
+    pub mapping: Mapping,
+    pub total: u32
+}
+
+/// Every bijection of the player's symbols to the game's shapes and to the outcomes, scored over a guide
+///
+/// Shapes are decoded from the game's player symbols and outcomes from `OUTCOME_SYMBOLS`. A guide using
+/// a symbol outside one of those sets can't be read that way, and gets no decoding of that kind.
+#[derive(Debug, Clone)]
+pub struct DecoderSearch<'a> {
+    game: &'a Game,
+    decodings: Vec<Decoding>
+}
+
+/// Every ordering of `0..n`, in lexicographic order so the identity comes first
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut orderings = vec![];
+    for first in 0..n {
+        for rest in permutations(n - 1) {
+            let mut ordering = vec![first];
+            ordering.extend(rest.into_iter().map(|i| if i >= first { i + 1 } else { i }));
+            orderings.push(ordering);
+        }
+    }
+    orderings
+}
+
+/// Rounds counted by opponent shape and symbol index, `None` if a symbol isn't one of `symbols`
+fn tally(game: &Game, rounds: &[Round], symbols: &[char]) -> Option<Vec<Vec<u32>>> {
+    let mut counts = vec![vec![0; symbols.len()]; game.shapes().len()];
+    for round in rounds {
+        let symbol = symbols.iter().position(|symbol| *symbol == round.response)?;
+        counts[round.opponent][symbol] += 1;
+    }
+    Some(counts)
+}
+
+impl<'a> DecoderSearch<'a> {
+    pub fn new(game: &'a Game, rounds: &[Round]) -> Self {
+        let mut decodings = vec![];
+        let shape_symbols: Vec<char> = game.shapes().iter().map(|shape| shape.player_symbol).collect();
+        if let Some(counts) = tally(game, rounds, &shape_symbols) {
+            for shapes in permutations(shape_symbols.len()) {
+                let total = counts.iter()
+                    .enumerate()
+                    .flat_map(|(opponent, row)| row.iter().zip(&shapes).map(move |(n, player)| n * game.score(*player, opponent)))
+                    .sum();
+                decodings.push(Decoding { symbols: shape_symbols.clone(), mapping: Mapping::Shapes(shapes), total });
+            }
+        }
+        if let Some(counts) = tally(game, rounds, &OUTCOME_SYMBOLS) {
+            for ordering in permutations(OUTCOME_SYMBOLS.len()) {
+                let outcomes: Vec<Outcome> = ordering.iter().map(|i| Outcome::ALL[*i]).collect();
+                let total = counts.iter()
+                    .enumerate()
+                    .flat_map(
+                        |(opponent, row)| row.iter().zip(&outcomes).map(
+                            move |(n, outcome)| n * game.score(game.response(opponent, *outcome), opponent)
+                        )
+                    )
+                    .sum();
+                decodings.push(Decoding { symbols: OUTCOME_SYMBOLS.to_vec(), mapping: Mapping::Outcomes(outcomes), total });
+            }
+        }
+        DecoderSearch { game, decodings }
+    }
+
+    /// Shape readings first, then outcome readings, each starting with the puzzle's own
+    pub fn decodings(&self) -> &[Decoding] {
+        &self.decodings
+    }
+
+    /// Highest scoring decoding, the first found on ties
+    pub fn best(&self) -> Option<&Decoding> {
+        self.decodings.iter().rev().max_by_key(|decoding| decoding.total)
+    }
+
+    /// Lowest scoring decoding, the first found on ties
+    pub fn worst(&self) -> Option<&Decoding> {
+        self.decodings.iter().min_by_key(|decoding| decoding.total)
+    }
+
+    /// Decodings under which the guide scores `total`
+    pub fn consistent_with(&self, total: u32) -> Vec<&Decoding> {
+        self.decodings.iter().filter(|decoding| decoding.total == total).collect()
+    }
+
+    /// Describes a decoding with the game's shape names, e.g. `X=Rock Y=Paper Z=Scissors`
+    pub fn describe(&self, decoding: &Decoding) -> String {
+        let meanings: Vec<String> = match &decoding.mapping {
+            Mapping::Shapes(shapes) => shapes.iter().map(|shape| self.game.shapes()[*shape].name.clone()).collect(),
+            Mapping::Outcomes(outcomes) => outcomes.iter().map(|outcome| outcome.to_string()).collect()
+        };
+        decoding.symbols.iter()
+            .zip(meanings)
+            .map(|(symbol, meaning)| format!("{}={}", symbol, meaning))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permutations_in_order() {
+        assert_eq!(permutations(3), [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]]);
+        assert_eq!(permutations(5).len(), 120)
+    }
+}
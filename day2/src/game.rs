@@ -0,0 +1,257 @@
+use std::fmt::Display;
+
+use common::{tokens, Error, Result};
+
+/// Player column symbols when they're read as the outcome to reach, lose, draw and win
+pub const OUTCOME_SYMBOLS: [char; 3] = ['X', 'Y', 'Z'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Lose => write!(f, "Lose"),
+            Outcome::Draw => write!(f, "Draw"),
+            Outcome::Win => write!(f, "Win")
+        }
+    }
+}
+
+/// One of a game's shapes, with the symbols each column of the guide writes it as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape {
+    pub name: String,
+    pub opponent_symbol: char,
+    pub player_symbol: char,
+    pub score: u32
+}
+
+impl Shape {
+    pub fn new(name: &str, opponent_symbol: char, player_symbol: char, score: u32) -> Self {
+        Shape { name: name.to_string(), opponent_symbol, player_symbol, score }
+    }
+}
+
+/// Game of an odd number of shapes in a cycle, each beating the half of the others right before it
+///
+/// With Rock, Paper, Scissors in that order, Paper beats Rock and Rock beats Scissors, wrapping around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    shapes: Vec<Shape>,
+    /// Points for losing, drawing and winning a round
+    outcome_scores: [u32; 3]
+}
+
+/// How the player's column of the guide is read, the first part's way or the second's
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    Shape,
+    Outcome
+}
+
+/// A line of the strategy guide, the opponent's shape and the player's symbol still to be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: usize,
+    pub response: char,
+    /// Line of the guide the round was read from, counted from 0
+    pub line_index: usize,
+    /// That line as written
+    pub line: String,
+    /// Where the player's symbol is in the line, counted from 0
+    pub response_column: usize
+}
+
+impl Game {
+    pub fn new(shapes: Vec<Shape>, outcome_scores: [u32; 3]) -> Result<Self> {
+        if shapes.len() < 3 || shapes.len().is_multiple_of(2) {
+            return Err(Error::invalid_state(format!("A cyclic game needs an odd number of shapes, not {}", shapes.len())));
+        }
+        for (i, shape) in shapes.iter().enumerate() {
+            let clash = shapes[..i].iter().find(
+                |other| other.opponent_symbol == shape.opponent_symbol || other.player_symbol == shape.player_symbol
+            );
+            if let Some(other) = clash {
+                return Err(Error::invalid_state(format!("'{}' and '{}' share a symbol", other.name, shape.name)));
+            }
+        }
+        Ok(Game { shapes, outcome_scores })
+    }
+
+    /// The classic game, A/X Rock, B/Y Paper and C/Z Scissors, scoring 0, 3 and 6 for the outcomes
+    pub fn rock_paper_scissors() -> Self {
+        let shapes = vec![Shape::new("Rock", 'A', 'X', 1), Shape::new("Paper", 'B', 'Y', 2), Shape::new("Scissors", 'C', 'Z', 3)];
+        Game::new(shapes, [0, 3, 6]).unwrap()
+    }
+
+    /// Rock, Spock, Paper, Lizard and Scissors, from A/V to E/Z and scoring 1 to 5 in that order
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        let shapes = vec![
+            Shape::new("Rock", 'A', 'V', 1),
+            Shape::new("Spock", 'B', 'W', 2),
+            Shape::new("Paper", 'C', 'X', 3),
+            Shape::new("Lizard", 'D', 'Y', 4),
+            Shape::new("Scissors", 'E', 'Z', 5)
+        ];
+        Game::new(shapes, [0, 3, 6]).unwrap()
+    }
+
+    pub fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome_scores[outcome as usize]
+    }
+
+    pub fn outcome(&self, player: usize, opponent: usize) -> Outcome {
+        let n = self.shapes.len();
+        match (player + n - opponent) % n {
+            0 => Outcome::Draw,
+            ahead if ahead <= n / 2 => Outcome::Win,
+            _ => Outcome::Lose
+        }
+    }
+
+    /// Shape reaching `outcome` against `opponent`, the closest one around the cycle when several do
+    pub fn response(&self, opponent: usize, outcome: Outcome) -> usize {
+        let n = self.shapes.len();
+        match outcome {
+            Outcome::Lose => (opponent + n - 1) % n,
+            Outcome::Draw => opponent,
+            Outcome::Win => (opponent + 1) % n
+        }
+    }
+
+    /// Points for playing `player` against `opponent`
+    pub fn score(&self, player: usize, opponent: usize) -> u32 {
+        self.shapes[player].score + self.outcome_score(self.outcome(player, opponent))
+    }
+
+    pub fn opponent_shape(&self, symbol: char) -> Option<usize> {
+        self.shapes.iter().position(|shape| shape.opponent_symbol == symbol)
+    }
+
+    pub fn player_shape(&self, symbol: char) -> Option<usize> {
+        self.shapes.iter().position(|shape| shape.player_symbol == symbol)
+    }
+
+    /// Reads a `<opponent> <player>` line, the player's symbol being either a shape's or an outcome's, as
+    /// the first line of a guide
+    pub fn parse_round(&self, line: &str) -> Option<Round> {
+        self.read_round(0, line).ok()
+    }
+
+    fn read_round(&self, line_index: usize, line: &str) -> Result<Round> {
+        let [(opponent_column, opponent), (response_column, response)] = tokens(line)[..] else {
+            return Err(Error::parse(line_index, line, "Expected '<opponent> <player>'"));
+        };
+        let opponent = single_char(opponent)
+            .and_then(|symbol| self.opponent_shape(symbol))
+            .ok_or_else(|| Error::parse_at(line_index, opponent_column, line, format!("Unknown opponent symbol '{}'", opponent)))?;
+        let response = single_char(response)
+            .filter(|symbol| self.player_shape(*symbol).is_some() || OUTCOME_SYMBOLS.contains(symbol))
+            .ok_or_else(|| Error::parse_at(line_index, response_column, line, format!("Unknown player symbol '{}'", response)))?;
+        Ok(Round { opponent, response, line_index, line: line.to_string(), response_column })
+    }
+
+    /// Reads every line of the guide as a round, ignoring blank lines at its end
+    pub fn parse_guide(&self, input: &str) -> Result<Vec<Round>> {
+        input.trim_end()
+            .lines()
+            .enumerate()
+            .map(|(i, l)| self.read_round(i, l))
+            .collect()
+    }
+
+    /// Every line of the guide that isn't a round, each as the error parsing reports for it
+    pub fn guide_errors(&self, input: &str) -> Vec<Error> {
+        input.trim_end()
+            .lines()
+            .enumerate()
+            .filter_map(|(i, l)| self.read_round(i, l).err())
+            .collect()
+    }
+
+    /// Shape played in the round, the player's symbol read as given
+    pub fn play(&self, round: &Round, reading: Reading) -> Result<usize> {
+        match reading {
+            Reading::Shape => {
+                self.player_shape(round.response)
+                    .ok_or_else(|| Error::invalid_state(format!("'{}' is no shape", round.response)))
+            },
+            Reading::Outcome => {
+                OUTCOME_SYMBOLS.iter()
+                    .position(|symbol| *symbol == round.response)
+                    .map(|i| self.response(round.opponent, Outcome::ALL[i]))
+                    .ok_or_else(|| Error::invalid_state(format!("'{}' is no outcome", round.response)))
+            }
+        }
+    }
+
+    /// Score of the round reading the player's symbol as the shape to play
+    pub fn score_by_shape(&self, round: &Round) -> Result<u32> {
+        Ok(self.score(self.play(round, Reading::Shape)?, round.opponent))
+    }
+
+    /// Score of the round reading the player's symbol as the outcome to reach
+    pub fn score_by_outcome(&self, round: &Round) -> Result<u32> {
+        Ok(self.score(self.play(round, Reading::Outcome)?, round.opponent))
+    }
+}
+
+fn single_char(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyclic_beats() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shapes().iter().position(|shape| shape.name == name).unwrap();
+        for (winner, loser) in [("Scissors", "Paper"), ("Paper", "Rock"), ("Rock", "Lizard"), ("Lizard", "Spock"), ("Spock", "Scissors")] {
+            assert_eq!(game.outcome(shape(winner), shape(loser)), Outcome::Win);
+            assert_eq!(game.outcome(shape(loser), shape(winner)), Outcome::Lose);
+        }
+        for opponent in 0..5 {
+            for outcome in Outcome::ALL {
+                assert_eq!(game.outcome(game.response(opponent, outcome), opponent), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn reject_invalid_games() {
+        let shapes = Game::rock_paper_scissors().shapes().to_vec();
+        assert!(Game::new(shapes[..2].to_vec(), [0, 3, 6]).is_err());
+        let mut clashing = shapes.clone();
+        clashing[2].player_symbol = 'X';
+        assert!(Game::new(clashing, [0, 3, 6]).is_err());
+    }
+
+    #[test]
+    fn score_lizard_spock_guide() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let rounds = game.parse_guide("A X\nE V\nD Z\nB W").unwrap();
+        // Paper covers Rock, Rock crushes Scissors, Scissors decapitate Lizard, Spock ties
+        let by_shape: Vec<_> = rounds.iter().map(|round| game.score_by_shape(round).unwrap()).collect();
+        assert_eq!(by_shape, [9, 7, 11, 5]);
+        // Read as outcomes, V and W are no outcome
+        let by_outcome: Vec<_> = rounds.iter().map(|round| game.score_by_outcome(round).ok()).collect();
+        assert_eq!(by_outcome, [Some(5), None, Some(11), None]);
+        assert!(matches!(game.parse_guide("A X\nF X"), Err(Error::Parse { line: 2, .. })))
+    }
+}
@@ -0,0 +1,35 @@
+use common::{Generated, Rng};
+
+/// Strategy guide of `size` rounds, scored arithmetically with the shapes numbered 0 to 2
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let rounds: Vec<(usize, usize)> = (0..size.max(1))
+        .map(|_| (rng.below(3), rng.below(3)))
+        .collect();
+
+    let input = rounds.iter()
+        .map(|(opponent, column)| format!("{} {}", ['A', 'B', 'C'][*opponent], ['X', 'Y', 'Z'][*column]))
+        .collect::<Vec<_>>()
+        .join("\n");
+    // The column is the shape to play, beating the opponent when it's the next one around
+    let part1: usize = rounds.iter()
+        .map(|(opponent, shape)| shape + 1 + (shape + 4 - opponent) % 3 * 3)
+        .sum();
+    // The column is the outcome, lose, draw or win
+    let part2: usize = rounds.iter()
+        .map(|(opponent, outcome)| (opponent + outcome + 2) % 3 + 1 + outcome * 3)
+        .sum();
+    Generated::solved(input, part1, part2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day2;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 100).check::<Day2>().unwrap();
+        }
+    }
+}
@@ -1,128 +1,190 @@
-enum Play {
-    Rock,
-    Paper,
-    Scissor
-}
+pub mod generator;
+
+mod decoder;
+mod game;
+mod report;
+
+use common::{Json, Puzzle, Result};
+
+pub use decoder::{Decoding, DecoderSearch, Mapping};
+pub use game::{Game, Outcome, Reading, Round, Shape, OUTCOME_SYMBOLS};
+pub use report::{RoundReport, Tournament};
+
+pub struct Day2;
 
-fn enemy_play(p: &str) -> Play {
-    match p.trim() {
-        "A" => Play::Rock,
-        "B" => Play::Paper,
-        "C" => Play::Scissor,
-        _ => panic!("Unknown play")
+impl Puzzle for Day2 {
+    type Input = Vec<Round>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Game::rock_paper_scissors().parse_guide(input)
     }
-}
 
-fn player_play(p: &str) -> Play {
-    match p.trim() {
-        "X" => Play::Rock,
-        "Y" => Play::Paper,
-        "Z" => Play::Scissor,
-        _ => panic!("Unknown play")
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        score_guide(&Game::rock_paper_scissors(), input, Game::score_by_shape)
     }
-}
 
-fn player_play_updated(e: &Play, p: &str) -> Play {
-    match (e, p.trim()) {
-        (Play::Rock, "X") => Play::Scissor,
-        (Play::Rock, "Y") => Play::Rock,
-        (Play::Rock, "Z") => Play::Paper,
-        (Play::Paper, "X") => Play::Rock,
-        (Play::Paper, "Y") => Play::Paper,
-        (Play::Paper, "Z") => Play::Scissor,
-        (Play::Scissor, "X") => Play::Paper,
-        (Play::Scissor, "Y") => Play::Scissor,
-        (Play::Scissor, "Z") => Play::Rock,
-        _ => panic!("Unknown play")
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        score_guide(&Game::rock_paper_scissors(), input, Game::score_by_outcome)
     }
-}
 
-fn round_score(e: &Play, p: &Play) -> u32 {
-    match (e, p) {
-        (Play::Rock, Play::Rock) => 1 + 3,
-        (Play::Rock, Play::Paper) => 2 + 6,
-        (Play::Rock, Play::Scissor) => 3 + 0,
-        (Play::Paper, Play::Rock) => 1 + 0,
-        (Play::Paper, Play::Paper) => 2 + 3,
-        (Play::Paper, Play::Scissor) => 3 + 6,
-        (Play::Scissor, Play::Rock) => 1 + 6,
-        (Play::Scissor, Play::Paper) => 2 + 0,
-        (Play::Scissor, Play::Scissor) => 3 + 3,
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("rounds", input.len())
+    }
+
+    /// Wins, draws, losses and total points of each part's reading of the guide
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        let game = Game::rock_paper_scissors();
+        Ok(
+            Json::object()
+                .with("by_shape", Tournament::new(&game, input, Reading::Shape)?.tally())
+                .with("by_outcome", Tournament::new(&game, input, Reading::Outcome)?.tally())
+        )
     }
 }
 
-fn compute_round(input: &str) -> u32 {
-    let mut c = input.split_whitespace();
-    round_score(&enemy_play(c.next().unwrap()), &player_play(c.next().unwrap()))
+/// Total score of the guide, each round scored by `score`, either reading of the player's column
+pub fn score_guide(game: &Game, rounds: &[Round], score: impl Fn(&Game, &Round) -> Result<u32>) -> Result<u32> {
+    rounds.iter().map(|round| score(game, round)).sum()
 }
 
-fn compute_round_updated(input: &str) -> u32 {
-    let mut c = input.split_whitespace();
-    let e = enemy_play(c.next().unwrap());
-    round_score(&e, &player_play_updated(&e, c.next().unwrap()))
+pub fn calculate_strategy_guide_score(input_path: &str) -> Result<u32> {
+    Day2::part1(&Day2::parse_file(input_path)?)
 }
 
-pub fn calculate_strategy_guide_score(input_path: &str) -> Option<u32> {
-    if let Ok(input) = std::fs::read_to_string(input_path) {
-        let score: u32 = input.split_terminator("\n")
-            .into_iter()
-            .map(compute_round)
-            .sum();
-        Some(score)
-    } else {
-        None
-    }
+pub fn calculate_strategy_guide_score_updated(input_path: &str) -> Result<u32> {
+    Day2::part2(&Day2::parse_file(input_path)?)
 }
 
-pub fn calculate_strategy_guide_score_updated(input_path: &str) -> Option<u32> {
-    if let Ok(input) = std::fs::read_to_string(input_path) {
-        let score: u32 = input.split_terminator("\n")
-            .into_iter()
-            .map(compute_round_updated)
-            .sum();
-        Some(score)
-    } else {
-        None
-    }
+/// Round by round breakdown of the guide, failing with the first line that isn't a round
+pub fn strategy_guide_report(input_path: &str, reading: Reading) -> Result<String> {
+    let game = Game::rock_paper_scissors();
+    Ok(Tournament::new(&game, &Day2::parse_file(input_path)?, reading)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::Error;
+
+    fn compute_round(input: &str) -> Option<u32> {
+        let game = Game::rock_paper_scissors();
+        game.score_by_shape(&game.parse_round(input)?).ok()
+    }
+
+    fn compute_round_updated(input: &str) -> Option<u32> {
+        let game = Game::rock_paper_scissors();
+        game.score_by_outcome(&game.parse_round(input)?).ok()
+    }
 
     #[test]
     fn test_1() {
         let input = "A Y";
-        assert_eq!(compute_round(input), 8)
+        assert_eq!(compute_round(input), Some(8))
     }
 
     #[test]
     fn test_2() {
         let input = "B X";
-        assert_eq!(compute_round(input), 1)
+        assert_eq!(compute_round(input), Some(1))
     }
 
     #[test]
     fn test_3() {
         let input = "C Z";
-        assert_eq!(compute_round(input), 6)
+        assert_eq!(compute_round(input), Some(6))
     }
 
     #[test]
     fn test_part2_1() {
         let input = "A Y";
-        assert_eq!(compute_round_updated(input), 4)
+        assert_eq!(compute_round_updated(input), Some(4))
     }
 
     #[test]
     fn test_part2_2() {
         let input = "B X";
-        assert_eq!(compute_round_updated(input), 1)
+        assert_eq!(compute_round_updated(input), Some(1))
     }
 
     #[test]
     fn test_part2_3() {
         let input = "C Z";
-        assert_eq!(compute_round_updated(input), 7)
+        assert_eq!(compute_round_updated(input), Some(7))
+    }
+
+    #[test]
+    fn test_decoder_search() {
+        let game = Game::rock_paper_scissors();
+        let rounds = Day2::parse("A Y\nB X\nC Z").unwrap();
+        let search = DecoderSearch::new(&game, &rounds);
+        assert_eq!(search.decodings().len(), 12);
+        let best = search.best().unwrap();
+        assert_eq!((search.describe(best), best.total), (String::from("X=Scissors Y=Paper Z=Rock"), 24));
+        let worst = search.worst().unwrap();
+        assert_eq!((search.describe(worst), worst.total), (String::from("X=Rock Y=Scissors Z=Paper"), 6));
+        let consistent: Vec<_> = search.consistent_with(12)
+            .into_iter()
+            .map(|decoding| search.describe(decoding))
+            .collect();
+        assert_eq!(consistent, ["X=Lose Y=Draw Z=Win"]);
+        assert_eq!(search.consistent_with(15).len(), 8);
+        assert!(search.consistent_with(100).is_empty())
+    }
+
+    #[test]
+    fn test_unknown_play() {
+        let guide = "A Y\nB Q\nC Z";
+        assert!(matches!(Day2::parse(guide), Err(Error::Parse { line: 2, .. })))
+    }
+
+    #[test]
+    fn test_guide_errors() {
+        let positions: Vec<_> = Game::rock_paper_scissors().guide_errors("A Y\nB Q\nC Z\nD X\nA\nB  Zz")
+            .iter()
+            .map(|er| match er {
+                Error::Parse { line, column, .. } => (*line, *column),
+                _ => (0, 0)
+            })
+            .collect();
+        assert_eq!(positions, [(2, 3), (4, 1), (5, 1), (6, 4)])
+    }
+
+    #[test]
+    fn test_tournament() {
+        let game = Game::rock_paper_scissors();
+        let rounds = Day2::parse("A Y\nB X\nC Z").unwrap();
+        let tournament = Tournament::new(&game, &rounds, Reading::Outcome).unwrap();
+        let running: Vec<_> = tournament.rounds().iter().map(|round| round.running_total).collect();
+        assert_eq!(running, [4, 5, 12]);
+        assert_eq!(
+            tournament.rounds()[2],
+            RoundReport { line: 3, opponent: 2, player: 0, outcome: Outcome::Win, shape_points: 1, outcome_points: 6, running_total: 12 }
+        );
+        assert_eq!(
+            tournament.to_string().lines().last(),
+            Some("1 wins, 1 draws, 1 losses, 12 points")
+        );
+        let by_shape = Tournament::new(&game, &rounds, Reading::Shape).unwrap();
+        assert_eq!((by_shape.count(Outcome::Win), by_shape.count(Outcome::Draw), by_shape.total()), (1, 1, 15))
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        assert_eq!(Day2::parse("A Y\nB X\nC Z\n\n").unwrap(), Day2::parse("A Y\nB X\nC Z").unwrap());
+        assert!(matches!(Day2::parse("A Y\n\nC Z"), Err(Error::Parse { line: 2, .. })))
+    }
+
+    #[test]
+    fn test_tournament_unplayable_round() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let rounds = game.parse_guide("A X\nE V\nD Z").unwrap();
+        let er = Tournament::new(&game, &rounds, Reading::Outcome).unwrap_err();
+        assert!(matches!(er, Error::Parse { line: 2, column: 3, ref content, .. } if content == "E V"));
+        // Located as written, however the symbols are spaced
+        let rounds = game.parse_guide("A X\n\tE   V \nD Z").unwrap();
+        let er = Tournament::new(&game, &rounds, Reading::Outcome).unwrap_err();
+        assert!(matches!(er, Error::Parse { line: 2, column: 6, ref content, .. } if content == "\tE   V "))
     }
 }
\ No newline at end of file
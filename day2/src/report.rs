@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use common::{Error, Json, Result};
+
+use crate::game::{Game, Outcome, Reading, Round};
+
+/// Breakdown of one round, with the score of the guide up to and including it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundReport {
+    /// Line of the guide, counted from 1
+    pub line: usize,
+    pub opponent: usize,
+    pub player: usize,
+    pub outcome: Outcome,
+    pub shape_points: u32,
+    pub outcome_points: u32,
+    pub running_total: u32
+}
+
+/// Every round of a guide played out under one reading of the player's column
+#[derive(Debug, Clone)]
+pub struct Tournament<'a> {
+    game: &'a Game,
+    rounds: Vec<RoundReport>
+}
+
+impl<'a> Tournament<'a> {
+    /// Plays `rounds`, expected in the order of the guide's lines as `Game::parse_guide` gives them
+    ///
+    /// A round that can't be played under `reading` fails as a parse error at the player's symbol in its line.
+    pub fn new(game: &'a Game, rounds: &[Round], reading: Reading) -> Result<Self> {
+        let mut running_total = 0;
+        let mut reports = Vec::with_capacity(rounds.len());
+        for round in rounds {
+            let player = game.play(round, reading)
+                .map_err(|er| Error::parse_at(round.line_index, round.response_column, &round.line, er.to_string()))?;
+            let outcome = game.outcome(player, round.opponent);
+            let (shape_points, outcome_points) = (game.shapes()[player].score, game.outcome_score(outcome));
+            running_total += shape_points + outcome_points;
+            reports.push(
+                RoundReport { line: round.line_index + 1, opponent: round.opponent, player, outcome, shape_points, outcome_points, running_total }
+            );
+        }
+        Ok(Tournament { game, rounds: reports })
+    }
+
+    pub fn rounds(&self) -> &[RoundReport] {
+        &self.rounds
+    }
+
+    pub fn total(&self) -> u32 {
+        self.rounds.last().map_or(0, |round| round.running_total)
+    }
+
+    /// Rounds ending in `outcome` for the player
+    pub fn count(&self, outcome: Outcome) -> usize {
+        self.rounds.iter().filter(|round| round.outcome == outcome).count()
+    }
+
+    /// Outcome counts and the total, without the rounds
+    pub fn tally(&self) -> Json {
+        Json::object()
+            .with("wins", self.count(Outcome::Win))
+            .with("draws", self.count(Outcome::Draw))
+            .with("losses", self.count(Outcome::Lose))
+            .with("total", self.total())
+    }
+}
+
+/// One tab separated line per round, then the outcome counts
+impl Display for Tournament<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "line\topponent\tplayer\toutcome\tshape\toutcome_points\trunning_total")?;
+        for round in &self.rounds {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                round.line,
+                self.game.shapes()[round.opponent].name,
+                self.game.shapes()[round.player].name,
+                round.outcome,
+                round.shape_points,
+                round.outcome_points,
+                round.running_total
+            )?;
+        }
+        write!(
+            f,
+            "{} wins, {} draws, {} losses, {} points",
+            self.count(Outcome::Win),
+            self.count(Outcome::Draw),
+            self.count(Outcome::Lose),
+            self.total()
+        )
+    }
+}
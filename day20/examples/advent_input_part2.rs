@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day20::decrypt_coordinates("./day20/resources/input.txt", 10, 811589153);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+use common::{Generated, Rng};
+
+/// Sum of the 1000th, 2000th and 3000th numbers after the zero, once mixed `rounds` times
+fn grove_coordinates(message: &[i64], rounds: usize, key: i64) -> i64 {
+    let mut mixed: Vec<(usize, i64)> = message.iter().map(|v| v * key).enumerate().collect();
+    // Moving a number never counts itself, hence the cycle one shorter than the message
+    let cycle = message.len() as i64 - 1;
+    for _ in 0..rounds {
+        for original in 0..message.len() {
+            let from = mixed.iter().position(|(i, _)| *i == original).unwrap();
+            let number = mixed.remove(from);
+            let to = (from as i64 + number.1).rem_euclid(cycle);
+            mixed.insert(to as usize, number);
+        }
+    }
+    let zero = mixed.iter().position(|(_, v)| *v == 0).unwrap();
+    [1000, 2000, 3000].iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()].1)
+        .sum()
+}
+
+/// `size` numbers (at least 3) with a single zero, mixed by removing and reinserting them in a vector
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut message: Vec<i64> = (1..size.max(3))
+        .map(|_| if rng.chance(50) { rng.between(1, 10_000) } else { -rng.between(1, 10_000) })
+        .collect();
+    message.insert(rng.below(message.len() + 1), 0);
+
+    let input = message.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(input, grove_coordinates(&message, 1, 1), grove_coordinates(&message, 10, 811_589_153))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day20;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..10 {
+            generate(&mut Rng::new(seed), 200).check::<Day20>().unwrap();
+        }
+    }
+}
@@ -1,12 +1,44 @@
-fn build_message(code: &str, key: i64) -> Vec<i64> {
+pub mod generator;
+
+use common::{Error, Json, Puzzle, Result};
+
+const DECRYPTION_KEY: i64 = 811_589_153;
+const DECRYPTION_ROUNDS: usize = 10;
+
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    type Input = Vec<i64>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_message(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        decrypt(input, 1, 1)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        decrypt(input, DECRYPTION_ROUNDS, DECRYPTION_KEY)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("numbers", input.len())
+            .with("zeros", input.iter().filter(|n| **n == 0).count())
+    }
+}
+
+fn build_message(code: &str) -> Result<Vec<i64>> {
     code.lines()
-        .map(
-            |l| l.parse::<i64>().unwrap() * key
-        )
+        .enumerate()
+        .map(|(i, l)| common::parse_field(l, i, l))
         .collect()
 }
 
-fn mix(mixing: &mut Vec<usize>, index: usize, mov: i64) {
+fn mix(mixing: &mut [usize], index: usize, mov: i64) {
     let cur_i = mixing.iter().position(|i| i == &index).unwrap();
     let extra = (mov % (mixing.len() - 1) as i64) as isize;
     let mut dest = cur_i as isize + extra;
@@ -27,7 +59,7 @@ fn mix(mixing: &mut Vec<usize>, index: usize, mov: i64) {
     }
 }
 
-fn index_to_value(index: &Vec<usize>, value: &Vec<i64>) -> Vec<i64> {
+fn index_to_value(index: &[usize], value: &[i64]) -> Vec<i64> {
     index.iter()
         .map(
             |i| value[*i]
@@ -35,8 +67,21 @@ fn index_to_value(index: &Vec<usize>, value: &Vec<i64>) -> Vec<i64> {
         .collect()
 }
 
-fn decrypt(code: &str, rounds: usize, key: i64) -> i64 {
-    let message = build_message(&code, key);
+pub fn decrypt(message: &[i64], rounds: usize, key: i64) -> Result<i64> {
+    let message = message.iter()
+        .map(
+            |m| {
+                m.checked_mul(key)
+                    .ok_or_else(|| Error::invalid_state(format!("Value {} overflows when applying key {}", m, key)))
+            }
+        )
+        .collect::<Result<Vec<i64>>>()?;
+    if message.len() < 2 {
+        return Err(Error::invalid_state("Message needs at least 2 numbers to mix"));
+    }
+    if !message.contains(&0) {
+        return Err(Error::invalid_state("Message has no 0 to count coordinates from"));
+    }
     let mut mixing: Vec<usize> = (0..message.len()).collect();
     for _i in 0..rounds {
         message.iter().enumerate()
@@ -49,31 +94,13 @@ fn decrypt(code: &str, rounds: usize, key: i64) -> i64 {
     let mixing: Vec<i64> = index_to_value(&mixing, &message);
     let mut it = mixing.into_iter().cycle();
     it.find(|i| i == &0);
-    it.nth(999).unwrap() + it.nth(999).unwrap() + it.nth(999).unwrap()
+    Ok(it.nth(999).unwrap() + it.nth(999).unwrap() + it.nth(999).unwrap())
 }
 
-pub fn decrypt_coordinates(input_path: &str, rounds: usize, key: i64) -> i64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => decrypt(&content, rounds, key),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn decrypt_coordinates(input_path: &str, rounds: usize, key: i64) -> Result<i64> {
+    decrypt(&Day20::parse_file(input_path)?, rounds, key)
 }
 
-// pub fn run_idler_game_hungry_elephants(input_path: &str) -> u32 {
-//     let content = std::fs::read_to_string(input_path);
-//     match content {
-//         Ok(content) => idler_game_hungry_elephants(&content),
-//         Err(er) => {
-//             println!("{}", er);
-//             0
-//         }
-//     }
-// }
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,11 +116,16 @@ r#"1
 
     #[test]
     fn test_input1() {
-        assert_eq!(decrypt(TEST_INP1, 1, 1), 3)
+        assert_eq!(Day20::solve_part1(TEST_INP1).unwrap(), 3)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(decrypt(TEST_INP1, 10, 811589153), 1623178306)
+        assert_eq!(Day20::solve_part2(TEST_INP1).unwrap(), 1623178306)
+    }
+
+    #[test]
+    fn test_missing_zero() {
+        assert!(matches!(Day20::solve_part1("1\n2\n-3"), Err(Error::InvalidState(_))))
     }
 }
\ No newline at end of file
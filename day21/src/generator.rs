@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+
+use common::{Generated, Rng};
+
+use crate::{HUMAN, ROOT};
+
+/// Keeps every value exact once the solver turns them into floats
+const LIMIT: i64 = 1 << 40;
+
+struct Troop<'a> {
+    rng: &'a mut Rng,
+    names: HashSet<String>,
+    lines: Vec<String>
+}
+
+impl Troop<'_> {
+    fn name(&mut self) -> String {
+        loop {
+            let name: String = (0..4).map(|_| (b'a' + self.rng.below(26) as u8) as char).collect();
+            if name != ROOT && name != HUMAN && self.names.insert(name.clone()) {
+                break name;
+            }
+        }
+    }
+
+    fn yell(&mut self, name: &str, left: &str, op: char, right: &str) {
+        self.lines.push(format!("{}: {} {} {}", name, left, op, right));
+    }
+
+    /// Monkey yelling `value` (at least 1) through up to `depth` operations on positive numbers
+    fn expression(&mut self, value: i64, depth: usize) -> String {
+        let name = self.name();
+        let divisor = (2..10).find(|d| value % d == 0 && self.rng.chance(50));
+        match (depth, self.rng.below(4)) {
+            (0, _) => self.lines.push(format!("{}: {}", name, value)),
+            (_, 0) if value > 1 => {
+                let left = self.rng.between(1, value - 1);
+                let (l, r) = (self.expression(left, depth - 1), self.expression(value - left, depth - 1));
+                self.yell(&name, &l, '+', &r);
+            },
+            (_, 1) if divisor.is_some() => {
+                let divisor = divisor.unwrap();
+                let (l, r) = (self.expression(value / divisor, depth - 1), self.expression(divisor, depth - 1));
+                self.yell(&name, &l, '*', &r);
+            },
+            (_, 2) if value < LIMIT / 10 => {
+                let divisor = self.rng.between(2, 9);
+                let (l, r) = (self.expression(value * divisor, depth - 1), self.expression(divisor, depth - 1));
+                self.yell(&name, &l, '/', &r);
+            },
+            _ => {
+                let right = self.rng.between(1, 100);
+                let (l, r) = (self.expression(value + right, depth - 1), self.expression(right, depth - 1));
+                self.yell(&name, &l, '-', &r);
+            }
+        }
+        name
+    }
+}
+
+/// Monkeys on a chain of `size` operations from the human to the root, the other branches built around known values
+///
+/// The chain only adds, subtracts and multiplies so it stays linear in what the human yells, and its value is
+/// followed both for the yelled number and for the one balancing the root, which the root's other side is then
+/// built to yell.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let yelled = rng.between(1, 5_000);
+    let balancing = loop {
+        let balancing = rng.between(1, 5_000);
+        if balancing != yelled {
+            break balancing;
+        }
+    };
+    let mut troop = Troop { rng, names: HashSet::new(), lines: vec![format!("{}: {}", HUMAN, yelled)] };
+
+    let (mut chain, mut values) = (HUMAN.to_string(), (yelled, balancing));
+    for _ in 0..size.max(1) {
+        let (low, high) = (values.0.min(values.1), values.0.max(values.1));
+        let depth = troop.rng.below(3);
+        let name = troop.name();
+        match troop.rng.below(4) {
+            1 if low > 1 => {
+                let other = troop.rng.between(1, low - 1);
+                let side = troop.expression(other, depth);
+                troop.yell(&name, &chain, '-', &side);
+                values = (values.0 - other, values.1 - other);
+            },
+            2 if high < LIMIT / 2 => {
+                let other = high + troop.rng.between(1, 100);
+                let side = troop.expression(other, depth);
+                troop.yell(&name, &side, '-', &chain);
+                values = (other - values.0, other - values.1);
+            },
+            3 if high < LIMIT / 10 => {
+                let factor = troop.rng.between(2, 9);
+                let side = troop.expression(factor, depth);
+                troop.yell(&name, &side, '*', &chain);
+                values = (values.0 * factor, values.1 * factor);
+            },
+            _ => {
+                let other = troop.rng.between(1, 100);
+                let side = troop.expression(other, depth);
+                troop.yell(&name, &chain, '+', &side);
+                values = (values.0 + other, values.1 + other);
+            }
+        }
+        chain = name;
+    }
+    let side = troop.expression(values.1, 3);
+    troop.yell(ROOT, &side, '+', &chain);
+
+    let mut lines = troop.lines;
+    rng.shuffle(&mut lines);
+    Generated::solved(lines.join("\n"), values.0 + values.1, balancing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day21;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 40).check::<Day21>().unwrap();
+        }
+    }
+}
@@ -1,6 +1,41 @@
+pub mod generator;
+
 use std::collections::HashMap;
 
-enum Operation {
+use common::{Error, Json, Puzzle, Result};
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    /// What each monkey yells, by monkey name
+    type Input = HashMap<String, Operation>;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_operations(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(monkey_math(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        monkey_riddle(input)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("monkeys", input.len())
+            .with("yelling_numbers", input.values().filter(|op| matches!(op, Operation::Const(_))).count())
+    }
+}
+
+#[derive(Clone)]
+pub enum Operation {
     Const(f64),
     Add(String, String),
     Sub(String, String),
@@ -8,16 +43,26 @@ enum Operation {
     Div(String, String),
 }
 
-fn build_operations(code: &str) -> HashMap<String, Operation> {
-    code.lines()
-        .fold(
+impl Operation {
+    fn operands(&self) -> Vec<&String> {
+        match self {
+            Operation::Const(_) => vec![],
+            Operation::Add(l, r) | Operation::Sub(l, r) | Operation::Mult(l, r) | Operation::Div(l, r) => vec![l, r]
+        }
+    }
+}
+
+fn build_operations(code: &str) -> Result<HashMap<String, Operation>> {
+    let operations: HashMap<String, Operation> = code.lines()
+        .enumerate()
+        .try_fold(
             HashMap::new(),
-            |mut hm, l| {
-                let spl: Vec<_> = l.split(' ').collect();
+            |mut hm, (i, line)| {
+                let spl: Vec<_> = line.split(' ').collect();
                 match spl[1..] {
                     [a] => hm.insert(
                         spl[0].trim_end_matches(':').to_string(),
-                        Operation::Const(a.parse().unwrap())
+                        Operation::Const(common::parse_field(a, i, line)?)
                     ),
                     [l, "+", r] => hm.insert(
                         spl[0].trim_end_matches(':').to_string(),
@@ -35,11 +80,20 @@ fn build_operations(code: &str) -> HashMap<String, Operation> {
                         spl[0].trim_end_matches(':').to_string(),
                         Operation::Div(l.to_string(), r.to_string())
                     ),
-                    _ => panic!("Unresolved operation '{}'", l)
+                    _ => return Err(Error::parse(i, line, "Unresolved operation"))
                 };
-                hm
+                Ok(hm)
             }
-        )
+        )?;
+    if !operations.contains_key(ROOT) {
+        return Err(Error::invalid_state(format!("No '{}' monkey", ROOT)));
+    }
+    for (monkey, op) in &operations {
+        if let Some(unknown) = op.operands().into_iter().find(|o| !operations.contains_key(*o)) {
+            return Err(Error::invalid_state(format!("Monkey '{}' waits on unknown monkey '{}'", monkey, unknown)));
+        }
+    }
+    Ok(operations)
 }
 
 fn do_math(operations: &HashMap<String, Operation>, monkey: &String) -> f64 {
@@ -53,20 +107,12 @@ fn do_math(operations: &HashMap<String, Operation>, monkey: &String) -> f64 {
     }
 }
 
-fn monkey_math(code: &str) -> i64 {
-    let math = build_operations(&code);
-    do_math(&math, &String::from("root")) as i64
+fn monkey_math(math: &HashMap<String, Operation>) -> i64 {
+    do_math(math, &String::from(ROOT)) as i64
 }
 
-pub fn execute_monkey_math(input_path: &str) -> i64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => monkey_math(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn execute_monkey_math(input_path: &str) -> Result<i64> {
+    Day21::part1(&Day21::parse_file(input_path)?)
 }
 
 fn unwrap_operation(operations: &HashMap<String, Operation>, monkey: &String) -> (f64, f64) {
@@ -74,51 +120,52 @@ fn unwrap_operation(operations: &HashMap<String, Operation>, monkey: &String) ->
         Operation::Const(a) => (*a, *a),
         Operation::Add(l, r) => {
             (
-                do_math(&operations, l),
-                do_math(&operations, r)
+                do_math(operations, l),
+                do_math(operations, r)
             )
         },
         Operation::Sub(l, r) => {
             (
-                do_math(&operations, l),
-                do_math(&operations, r)
+                do_math(operations, l),
+                do_math(operations, r)
             )
         },
         Operation::Mult(l, r) => {
             (
-                do_math(&operations, l),
-                do_math(&operations, r)
+                do_math(operations, l),
+                do_math(operations, r)
             )
         },
         Operation::Div(l, r) => {
             (
-                do_math(&operations, l),
-                do_math(&operations, r)
+                do_math(operations, l),
+                do_math(operations, r)
             )
         }
     }
 }
 
 fn update_human(operations: &mut HashMap<String, Operation>, step: f64) {
-    if let Operation::Const(human) = operations[&String::from("humn")] {
-        operations.insert(String::from("humn"), Operation::Const(human + step))
-    } else {
-        panic!("Failed to unwrap 'humn'");
-    };
+    if let Some(Operation::Const(human)) = operations.get_mut(HUMAN) {
+        *human += step;
+    }
 }
 
-fn monkey_riddle(code: &str) -> i64 {
-    let mut math = build_operations(&code);
+fn monkey_riddle(math: &HashMap<String, Operation>) -> Result<i64> {
+    let mut math = math.clone();
+    if !matches!(math.get(HUMAN), Some(Operation::Const(_))) {
+        return Err(Error::invalid_state(format!("'{}' must yell a number", HUMAN)));
+    }
 
     loop {
-        let a_x = do_math(&math, &String::from("humn"));
-        let (a_yc1, a_yc2) = unwrap_operation(&math, &String::from("root"));
-        if (a_yc1 - a_yc2).abs() < std::f64::EPSILON {
-            break a_x as i64;
+        let a_x = do_math(&math, &String::from(HUMAN));
+        let (a_yc1, a_yc2) = unwrap_operation(&math, &String::from(ROOT));
+        if (a_yc1 - a_yc2).abs() < f64::EPSILON {
+            break Ok(a_x as i64);
         }
     
         update_human(&mut math, -a_x);
-        let (b_yc1, b_yc2) = unwrap_operation(&math, &String::from("root"));
+        let (b_yc1, b_yc2) = unwrap_operation(&math, &String::from(ROOT));
     
         let (a_y, b_y, y) = if a_yc1 == b_yc1 {
             (a_yc2, b_yc2, b_yc1)
@@ -134,15 +181,8 @@ fn monkey_riddle(code: &str) -> i64 {
     }
 }
 
-pub fn solve_monkey_riddle(input_path: &str) -> i64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => monkey_riddle(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn solve_monkey_riddle(input_path: &str) -> Result<i64> {
+    Day21::part2(&Day21::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -168,11 +208,16 @@ hmdt: 32"#;
 
     #[test]
     fn test_input1() {
-        assert_eq!(monkey_math(TEST_INP1), 152)
+        assert_eq!(Day21::solve_part1(TEST_INP1).unwrap(), 152)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(monkey_riddle(TEST_INP1), 301)
+        assert_eq!(Day21::solve_part2(TEST_INP1).unwrap(), 301)
+    }
+
+    #[test]
+    fn test_unknown_monkey() {
+        assert!(matches!(Day21::parse("root: abcd + efgh\nabcd: 4"), Err(Error::InvalidState(_))))
     }
 }
\ No newline at end of file
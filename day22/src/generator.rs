@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use common::{Generated, Rng};
+
+/// Faces layouts with known edge wrappings, on a 4x4 grid of faces
+const NETS: [[&str; 4]; 3] = [
+    ["..#.", "####", "..#.", "...."],
+    ["..#.", "###.", "..##", "...."],
+    [".##.", ".#..", "##..", "#..."]
+];
+/// Board steps for the east, south, west and north headings, in password order
+const HEADINGS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+type Vector = [i64; 3];
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vector, k: i64) -> Vector {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+/// Where a tile sits on the cube: its centre in doubled coordinates, the face normal, and the board's east and
+/// south directions
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    centre: Vector,
+    normal: Vector,
+    east: Vector,
+    south: Vector
+}
+
+impl Placement {
+    fn direction(&self, heading: usize) -> Vector {
+        [self.east, self.south, scale(self.east, -1), scale(self.south, -1)][heading]
+    }
+
+    fn heading(&self, direction: Vector) -> usize {
+        (0..4).find(|h| self.direction(*h) == direction).unwrap()
+    }
+
+    /// Next tile centre and normal going `direction`, folding over the cube's edge when leaving the face
+    fn step(&self, direction: Vector, size: i64) -> (Vector, Vector) {
+        let next = add(self.centre, scale(direction, 2));
+        let on_face = (0..3)
+            .filter(|axis| self.normal[*axis] == 0)
+            .all(|axis| (1..2 * size).contains(&next[axis]));
+        if on_face {
+            (next, self.normal)
+        } else {
+            (add(add(self.centre, direction), scale(self.normal, -1)), direction)
+        }
+    }
+
+    /// Same as `step`, also carrying the board directions over the edge
+    fn fold(&self, heading: usize, size: i64) -> Placement {
+        let direction = self.direction(heading);
+        let (centre, normal) = self.step(direction, size);
+        let mut next = Placement { centre, normal, ..*self };
+        if normal != self.normal {
+            // The direction walked along turns into the old face's inwards direction
+            let turned = scale(self.normal, if heading < 2 { -1 } else { 1 });
+            match heading {
+                0 | 2 => next.east = turned,
+                _ => next.south = turned
+            }
+        }
+        next
+    }
+}
+
+fn tile_at(board: &[Vec<u8>], x: isize, y: isize) -> u8 {
+    if x < 0 || y < 0 {
+        return b' ';
+    }
+    board.get(y as usize).and_then(|row| row.get(x as usize)).copied().unwrap_or(b' ')
+}
+
+fn walk_flat(board: &[Vec<u8>], start: (isize, isize), path: &[(usize, Option<char>)]) -> u64 {
+    let side = board.len() as isize;
+    let ((mut x, mut y), mut heading) = (start, 0);
+    for (moves, turn) in path {
+        let (dx, dy) = HEADINGS[heading];
+        for _ in 0..*moves {
+            let (mut nx, mut ny) = ((x + dx).rem_euclid(side), (y + dy).rem_euclid(side));
+            while tile_at(board, nx, ny) == b' ' {
+                (nx, ny) = ((nx + dx).rem_euclid(side), (ny + dy).rem_euclid(side));
+            }
+            if tile_at(board, nx, ny) == b'#' {
+                break;
+            }
+            (x, y) = (nx, ny);
+        }
+        heading = match turn {
+            Some('R') => (heading + 1) % 4,
+            Some(_) => (heading + 3) % 4,
+            None => heading
+        };
+    }
+    1000 * (y + 1) as u64 + 4 * (x + 1) as u64 + heading as u64
+}
+
+fn walk_cube(board: &[Vec<u8>], size: usize, start: (isize, isize), path: &[(usize, Option<char>)]) -> u64 {
+    let size = size as i64;
+    // Folds the net by spreading the placements from the start tile, laid on the cube's z = 0 face
+    let mut placements = HashMap::from([
+        (start, Placement { centre: [1, 1, 0], normal: [0, 0, -1], east: [1, 0, 0], south: [0, 1, 0] })
+    ]);
+    let mut stack = vec![start];
+    while let Some((x, y)) = stack.pop() {
+        let placement = placements[&(x, y)];
+        for (heading, (dx, dy)) in HEADINGS.iter().enumerate() {
+            let next = (x + dx, y + dy);
+            if tile_at(board, next.0, next.1) != b' ' && !placements.contains_key(&next) {
+                placements.insert(next, placement.fold(heading, size));
+                stack.push(next);
+            }
+        }
+    }
+    let tiles: HashMap<_, _> = placements.iter().map(|(tile, p)| ((p.centre, p.normal), *tile)).collect();
+
+    let (mut position, mut heading) = (start, 0);
+    for (moves, turn) in path {
+        for _ in 0..*moves {
+            let placement = placements[&position];
+            let direction = placement.direction(heading);
+            let (centre, normal) = placement.step(direction, size);
+            let next = tiles[&(centre, normal)];
+            if tile_at(board, next.0, next.1) == b'#' {
+                break;
+            }
+            // Crossing an edge, the walk carries on heading into the face
+            let direction = if normal == placement.normal { direction } else { scale(placement.normal, -1) };
+            heading = placements[&next].heading(direction);
+            position = next;
+        }
+        heading = match turn {
+            Some('R') => (heading + 1) % 4,
+            Some(_) => (heading + 3) % 4,
+            None => heading
+        };
+    }
+    1000 * (position.1 + 1) as u64 + 4 * (position.0 + 1) as u64 + heading as u64
+}
+
+/// Cube with `size` wide faces unfolded along one of the nets the solver knows, and a path of `10 * size` moves
+///
+/// The flat walk wraps around rows and columns, the cube walk folds the net in space and follows the tiles'
+/// positions on the cube's surface.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.max(1);
+    let net = rng.pick(&NETS);
+    let mut board: Vec<Vec<u8>> = (0..size * 4)
+        .map(
+            |y| {
+                (0..size * 4)
+                    .map(|x| if net[y / size].as_bytes()[x / size] == b'#' { b'.' } else { b' ' })
+                    .collect()
+            }
+        )
+        .collect();
+    for tile in board.iter_mut().flatten().filter(|tile| **tile == b'.') {
+        if rng.chance(12) {
+            *tile = b'#';
+        }
+    }
+    let start = board[0].iter().position(|tile| *tile != b' ').unwrap();
+    board[0][start] = b'.';
+    let start = (start as isize, 0);
+
+    let path: Vec<(usize, Option<char>)> = (0..size * 10)
+        .map(|i| (rng.below(size * 2) + 1, (i + 1 < size * 10).then(|| *rng.pick(&['L', 'R']))))
+        .collect();
+
+    let rows: Vec<String> = board.iter()
+        .map(|row| String::from_utf8_lossy(row).trim_end().to_string())
+        .filter(|row| !row.is_empty())
+        .collect();
+    let instructions: String = path.iter()
+        .map(|(moves, turn)| format!("{}{}", moves, turn.map_or(String::new(), String::from)))
+        .collect();
+    let input = format!("{}\n\n{}", rows.join("\n"), instructions);
+    Generated::solved(input, walk_flat(&board, start, &path), walk_cube(&board, size, start, &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day22;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..24 {
+            generate(&mut Rng::new(seed), 6).check::<Day22>().unwrap();
+        }
+    }
+}
@@ -1,5 +1,45 @@
+pub mod generator;
+
 mod nets;
 
+use common::{Error, Json, Puzzle, Result};
+use grid::{Grid, Point};
+
+/// Tiles of a board folding into a cube with faces of `size`, and the path to follow on it
+pub struct Board {
+    size: usize,
+    tiles: Grid<BoardTile>,
+    commands: Vec<Command>
+}
+
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    type Input = Board;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let size = face_size(input)?;
+        let (tiles, commands) = build_board_and_commands(input, size)?;
+        Ok(Board { size, tiles, commands })
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        trace(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        trace_cube(input)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("face_size", input.size)
+            .with("commands", input.commands.len())
+    }
+}
+
 #[derive(Debug)]
 enum Command {
     Move(usize),
@@ -23,11 +63,8 @@ impl BoardTile {
         }
     }
 
-    fn is_void(&self) -> bool {
-        match self {
-            Self::Void => true,
-            _ => false
-        }
+    pub fn is_void(&self) -> bool {
+        matches!(self, Self::Void)
     }
 }
 
@@ -58,6 +95,15 @@ impl Heading {
         }
     }
 
+    fn step(&self) -> Point {
+        match self {
+            Self::North => Point::NORTH,
+            Self::South => Point::SOUTH,
+            Self::East => Point::EAST,
+            Self::West => Point::WEST
+        }
+    }
+
     fn rotate_counterclockwise(&self) -> Self {
         match self {
             Self::North => Self::West,
@@ -68,69 +114,51 @@ impl Heading {
     }
 }
 
-fn to_position(flat: usize, board_size: usize) -> (usize, usize) {
-    (flat % (board_size * 4), flat / (board_size * 4))
-}
-
-fn to_index((pos_x, pos_y): &(usize, usize), board_size: usize) -> usize {
-    pos_x + pos_y * (board_size * 4)
+/// Draws the board's tiles, padded to the 4x4 faces layout every net fits in
+pub fn render_board(board: &Board) -> String {
+    board.tiles.render(
+        |tile| match tile {
+            BoardTile::Void => ' ',
+            BoardTile::Open => '.',
+            BoardTile::Wall => '#'
+        }
+    )
 }
 
-#[allow(dead_code)]
-fn print_board(
-    board: &Vec<BoardTile>,
-    pos: &(usize, usize),
-    heading: &Heading,
-    board_size: usize
-) {
-    let mut prev = 0;
-    println!("===");
-    board.iter().enumerate()
-        .for_each(
-            |(flat, b)| {
-                let (x, y) = to_position(flat, board_size); 
-                if prev != y {
-                    println!();
-                    prev = y;
-                }
-                if &(x, y) == pos {
-                    match heading {
-                        Heading::North => print!("^"),
-                        Heading::South => print!("v"),
-                        Heading::East => print!(">"),
-                        Heading::West => print!("<"),
-                    }
-                } else {
-                    match b {
-                        BoardTile::Void => print!(" "),
-                        BoardTile::Open => print!("."),
-                        BoardTile::Wall => print!("#"),
-                    }
-                }
-            }
-        );
-    println!();
+fn face_size(board: &str) -> Result<usize> {
+    let tiles: usize = board.lines()
+        .take_while(|l| !l.trim().is_empty())
+        .map(|l| l.chars().filter(|c| *c != ' ').count())
+        .sum();
+    let size = ((tiles / 6) as f64).sqrt() as usize;
+    if size == 0 || size * size * 6 != tiles {
+        Err(Error::invalid_state(format!("{} tiles don't fold into a cube", tiles)))
+    } else {
+        Ok(size)
+    }
 }
 
-fn build_board_and_commands(board: &str, board_size: usize) -> (Vec<BoardTile>, Vec<Command>) {
-    let mut tiles = vec![BoardTile::Void; (board_size * 4).pow(2)];
+fn build_board_and_commands(board: &str, board_size: usize) -> Result<(Grid<BoardTile>, Vec<Command>)> {
+    let mut tiles = Grid::new(board_size * 4, board_size * 4, BoardTile::Void);
     let mut commands = Vec::new();
 
     let lines: Vec<_> = board.lines().collect();
     if let [head @ .., tail] = lines.as_slice() {
-        for (y, b) in head.into_iter().enumerate() {
+        for (y, b) in head.iter().enumerate() {
             for (x, c) in b.chars().enumerate() {
+                let tile = tiles.get_mut(Point::new(x as isize, y as isize))
+                    .ok_or_else(|| Error::parse_at(y, x, b, format!("Tile outside a {0}x{0} board", board_size * 4)))?;
                 match c {
-                    ' ' => tiles[to_index(&(x, y), board_size)] = BoardTile::Void,
-                    '.' => tiles[to_index(&(x, y), board_size)] = BoardTile::Open,
-                    '#' => tiles[to_index(&(x, y), board_size)] = BoardTile::Wall,
-                    _ => panic!("Undefined board tile '{}'", c)
+                    ' ' => *tile = BoardTile::Void,
+                    '.' => *tile = BoardTile::Open,
+                    '#' => *tile = BoardTile::Wall,
+                    _ => return Err(Error::parse_at(y, x, b, format!("Undefined board tile '{}'", c)))
                 };
             }
         }
 
         let mut mov = 0;
-        for c in tail.chars() {
+        for (x, c) in tail.chars().enumerate() {
             match c {
                 '0'..='9' => mov = mov * 10 + c.to_digit(10).unwrap() as usize,
                 'R' => {
@@ -143,216 +171,114 @@ fn build_board_and_commands(board: &str, board_size: usize) -> (Vec<BoardTile>,
                     mov = 0;
                     commands.push(Command::RotateCouter);
                 },
-                _ => panic!("Unknown command '{}'", c)
+                _ => return Err(Error::parse_at(head.len(), x, tail, format!("Unknown command '{}'", c)))
             }
         }
         if mov != 0 {
             commands.push(Command::Move(mov));
         }
     } else {
-        panic!("Input too short.")
+        return Err(Error::invalid_state("Input too short."))
     }
-    (tiles, commands)
+    Ok((tiles, commands))
 }
 
-fn vertical_update(x: usize, y: usize, val: isize, board_size: usize) -> (usize, usize) {
-    if y == 0 && val < 0 {
-        (x, (board_size * 4) - 1)
-    } else if y == (board_size * 4) - 1 && val > 0 {
-        (x, 0)
-    } else {
-        (x, (y as isize + val) as usize)
-    }
+fn first_tile(board: &Grid<BoardTile>) -> Result<Point> {
+    board.iter()
+        .find(|(_, tile)| !tile.is_void())
+        .map(|(p, _)| p)
+        .ok_or_else(|| Error::invalid_state("Board has no tiles"))
 }
 
-fn horizontal_update(x: usize, y: usize, val: isize, board_size: usize) -> (usize, usize) {
-    if x == 0 && val < 0 {
-        ((board_size * 4) - 1, y)
-    } else if x == (board_size * 4) - 1 && val > 0 {
-        (0, y)
-    } else {
-        ((x as isize + val) as usize, y)
-    }
+fn password(position: Point, heading: &Heading) -> u64 {
+    1000 * (position.y + 1) as u64 + 4 * (position.x + 1) as u64 + heading.value()
 }
 
-fn make_move(
-    board: &Vec<BoardTile>,
-    (mut pos_x, mut pos_y): &(usize, usize),
-    heading: &Heading,
-    mut mov: usize,
-    board_size: usize
-) -> (usize, usize) {
-    let step: isize = if let Heading::North | Heading::West = heading {
-        -1
-    } else {
-        1
-    };
-    let update_fn = match heading {
-        Heading::North | Heading::South => {
-            vertical_update
-        },
-        Heading::East | Heading::West => {
-            horizontal_update
-        }
-    };
+fn make_move(board: &Grid<BoardTile>, mut position: Point, heading: &Heading, mut mov: usize) -> Point {
+    let step = heading.step();
     while mov > 0 {
-        let (px, py) = update_fn(pos_x, pos_y, step, board_size);
-        (pos_x, pos_y) = match board[to_index(&(px, py), board_size)] {
-            BoardTile::Void => {
-                let (mut vx, mut vy) = (px, py); 
-                loop {
-                    (vx, vy) = match board[to_index(&(vx, vy), board_size)] {
-                        BoardTile::Void => update_fn(vx, vy, step, board_size),
-                        BoardTile::Open => {
-                            mov -= 1;
-                            break (vx, vy)
-                        },
-                        BoardTile::Wall => {
-                            mov = 0;
-                            break (pos_x, pos_y)
-                        }
-                    }
-                }
-            },
-            BoardTile::Open => {
+        // Walking off the board, or into the void between faces, wraps around to the other side
+        let mut next = board.wrap(position + step);
+        while board[next].is_void() {
+            next = board.wrap(next + step);
+        }
+        match board[next] {
+            BoardTile::Wall => break,
+            _ => {
                 mov -= 1;
-                (px, py)
-            },
-            BoardTile::Wall => {
-                mov = 0;
-                (pos_x, pos_y)
+                position = next;
             }
         }
     }
-    (pos_x, pos_y)
+    position
 }
 
-fn trace(board: &str, board_size: usize) -> u64 {
-    let (board, commands) = build_board_and_commands(&board, board_size);
-        
-    let flat = board.iter()
-        .position(|b| !matches!(b, BoardTile::Void))
-        .unwrap();
-    let (mut pos_x, mut pos_y) = to_position(flat, board_size);
+fn trace(Board { tiles: board, commands, .. }: &Board) -> Result<u64> {
+    let mut position = first_tile(board)?;
     let mut heading = Heading::East;
 
-    for c in commands.into_iter() {
+    for c in commands.iter() {
         match c {
-            Command::Move(mov) => {
-                (pos_x, pos_y) = make_move(
-                    &board,
-                    &(pos_x, pos_y),
-                    &heading,
-                    mov,
-                    board_size
-                );
-            },
-            Command::RotateClock => {
-                heading = match &heading {
-                    Heading::North => Heading::East,
-                    Heading::South => Heading::West,
-                    Heading::East => Heading::South,
-                    Heading::West => Heading::North,
-                }
-            },
-            Command::RotateCouter => {
-                heading = match &heading {
-                    Heading::North => Heading::West,
-                    Heading::South => Heading::East,
-                    Heading::East => Heading::North,
-                    Heading::West => Heading::South,
-                }
-            }
+            Command::Move(mov) => position = make_move(board, position, &heading, *mov),
+            Command::RotateClock => heading = heading.rotate_clockwise(),
+            Command::RotateCouter => heading = heading.rotate_counterclockwise()
         }
     }
     
-    1000 * (pos_y + 1) as u64 + 4 * (pos_x + 1) as u64 + heading.value()
+    Ok(password(position, &heading))
 }
 
-pub fn trace_path(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => trace(&content, 50_usize),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn trace_path(input_path: &str) -> Result<u64> {
+    Day22::part1(&Day22::parse_file(input_path)?)
 }
 
 fn make_move_cube(
-    board: &Vec<BoardTile>,
-    (mut pos_x, mut pos_y): &(usize, usize),
+    board: &Grid<BoardTile>,
+    mut position: Point,
     heading: &Heading,
     mut mov: usize,
     board_size: usize,
     net: &nets::Net
-) -> ((usize, usize), Heading) {
+) -> Result<(Point, Heading)> {
     let mut mheading = *heading;
     while mov > 0 {
-        let step: isize = if let Heading::North | Heading::West = mheading {
-            -1
+        let next = board.wrap(position + mheading.step());
+        let (next, nheading) = if board[next].is_void() {
+            net.move_to_adj_face(position, &mheading, board_size)?
         } else {
-            1
+            (next, mheading)
         };
-        let (px, py) = match mheading {
-            Heading::North | Heading::South => {
-                vertical_update(pos_x, pos_y, step, board_size)
-            },
-            Heading::East | Heading::West => {
-                horizontal_update(pos_x, pos_y, step, board_size)
-            }
-        };
-        (pos_x, pos_y) = match board[to_index(&(px, py), board_size)] {
-            BoardTile::Void => {
-                let (npos, nheading) = net.move_to_adj_face(&(pos_x, pos_y), heading, board_size);
-                match board[to_index(&npos, board_size)] {
-                    BoardTile::Void => panic!("Moved into a void face"),
-                    BoardTile::Wall => {
-                        mov = 0;
-                        (pos_x, pos_y)
-                    },
-                    BoardTile::Open => {
-                        mov -= 1;
-                        mheading = nheading;
-                        npos
-                    }
-                }
-            },
+        match board[next] {
+            BoardTile::Void => return Err(Error::invalid_state("Moved into a void face")),
+            BoardTile::Wall => break,
             BoardTile::Open => {
                 mov -= 1;
-                (px, py)
-            },
-            BoardTile::Wall => {
-                mov = 0;
-                (pos_x, pos_y)
+                position = next;
+                mheading = nheading;
             }
         }
     }
-    ((pos_x, pos_y), mheading)
+    Ok((position, mheading))
 }
 
-fn trace_cube(board: &str, board_size: usize) -> u64 {
-    let (board, commands) = build_board_and_commands(&board, board_size);
-    let net = nets::Net::find_net(&board, board_size);
+fn trace_cube(Board { size: board_size, tiles: board, commands }: &Board) -> Result<u64> {
+    let net = nets::Net::find_net(board, *board_size)
+        .ok_or_else(|| Error::invalid_state("Board doesn't fold into a known cube net"))?;
         
-    let first_open = board.iter()
-        .position(|b| !matches!(b, BoardTile::Void))
-        .unwrap();
-    let (mut pos_x, mut pos_y) = to_position(first_open, board_size);
+    let mut position = first_tile(board)?;
     let mut heading = Heading::East;
 
-    for c in commands.into_iter() {
+    for c in commands.iter() {
         match c {
             Command::Move(mov) => {
-                ((pos_x, pos_y), heading) = make_move_cube(
-                    &board,
-                    &(pos_x, pos_y),
+                (position, heading) = make_move_cube(
+                    board,
+                    position,
                     &heading,
-                    mov,
-                    board_size,
+                    *mov,
+                    *board_size,
                     &net
-                );
+                )?;
             },
             Command::RotateClock => {
                 heading = heading.rotate_clockwise()
@@ -363,18 +289,11 @@ fn trace_cube(board: &str, board_size: usize) -> u64 {
         }
     }
     
-    1000 * (pos_y + 1) as u64 + 4 * (pos_x + 1) as u64 + heading.value()
+    Ok(password(position, &heading))
 }
 
-pub fn trace_path_on_cube(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => trace_cube(&content, 50_usize),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn trace_path_on_cube(input_path: &str) -> Result<u64> {
+    Day22::part2(&Day22::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -399,11 +318,42 @@ r#"        ...#
 
     #[test]
     fn test_input1() {
-        assert_eq!(trace(TEST_INP1, 4_usize), 6032)
+        assert_eq!(Day22::solve_part1(TEST_INP1).unwrap(), 6032)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(trace_cube(TEST_INP1, 4_usize), 5031)
+        assert_eq!(Day22::solve_part2(TEST_INP1).unwrap(), 5031)
+    }
+
+    /// The example's board without walls, following `path`
+    fn open_board(path: &str) -> String {
+        TEST_INP1.replace('#', ".").replace("10R5L5R10L4R5L5", path)
+    }
+
+    #[test]
+    fn test_round_the_cube_west() {
+        // Sixteen steps straight ahead go round the cube back to the start, one edge at a time here
+        assert_eq!(Day22::solve_part2(&open_board("0R4R9L0R7")).unwrap(), 5038)
+    }
+
+    #[test]
+    fn test_round_the_cube_east() {
+        // Crossing two edges in a single move, the second from the heading the first left it with
+        assert_eq!(Day22::solve_part2(&open_board("0R4L16")).unwrap(), 5036)
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let input = TEST_INP1.replace("5L5R10", "5L5U10");
+        assert!(matches!(Day22::parse(&input), Err(Error::Parse { line: 14, column: 7, .. })))
+    }
+
+    #[test]
+    fn test_unsupported_net() {
+        let input = " .\n....\n .\n\n1R1";
+        let board = Day22::parse(input).unwrap();
+        assert!(Day22::part1(&board).is_ok());
+        assert!(matches!(Day22::part2(&board), Err(Error::InvalidState(ref reason)) if reason.contains("Net1_180")))
     }
 }
\ No newline at end of file
@@ -1,6 +1,10 @@
+use common::{Error, Result};
+use grid::{Grid, Point};
+
 use crate::{BoardTile, Heading};
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Net {
     Net1_0,
     Net1_90,
@@ -17,10 +21,10 @@ pub enum Net {
 }
 
 impl Net {
-    pub fn find_net(board: &Vec<BoardTile>, board_size: usize) -> Self {
+    pub fn find_net(board: &Grid<BoardTile>, board_size: usize) -> Option<Self> {
         let grid:Vec<_> = (0..16).map(
                 |i| {
-                    if board[crate::to_index(&((i % 4) * board_size, (i / 4) * board_size), board_size)].is_void() {
+                    if board[Point::new(((i % 4) * board_size) as isize, ((i / 4) * board_size) as isize)].is_void() {
                         0
                     } else {
                         1
@@ -32,52 +36,52 @@ impl Net {
             [0, 0, 1, 0,
              1, 1, 1, 1,
              0, 0, 1, 0,
-             0, 0, 0, 0] => Net::Net1_0,
+             0, 0, 0, 0] => Some(Net::Net1_0),
             [0, 1, 0, 0,
              1, 1, 1, 0,
              0, 1, 0, 0,
-             0, 1, 0, 0] => Net::Net1_90,
+             0, 1, 0, 0] => Some(Net::Net1_90),
             [0, 1, 0, 0,
              1, 1, 1, 1,
              0, 1, 0, 0,
-             0, 0, 0, 0] => Net::Net1_180,
+             0, 0, 0, 0] => Some(Net::Net1_180),
             [0, 1, 0, 0,
              0, 1, 0, 0,
              1, 1, 1, 0,
-             0, 1, 0, 0] => Net::Net1_270,
+             0, 1, 0, 0] => Some(Net::Net1_270),
             [0, 0, 1, 0,
              1, 1, 1, 0,
              0, 0, 1, 1,
-             0, 0, 0, 0] => Net::Net2_0,
+             0, 0, 0, 0] => Some(Net::Net2_0),
             [0, 0, 1, 0,
              1, 1, 1, 0,
              0, 1, 0, 0,
-             0, 1, 0, 0] => Net::Net2_90,
+             0, 1, 0, 0] => Some(Net::Net2_90),
             [1, 1, 0, 0,
              0, 1, 1, 1,
              0, 1, 0, 0,
-             0, 0, 0, 0] => Net::Net2_180,
+             0, 0, 0, 0] => Some(Net::Net2_180),
             [0, 1, 0, 0,
              0, 1, 0, 0,
              1, 1, 1, 0,
-             1, 0, 0, 0] => Net::Net2_270,
+             1, 0, 0, 0] => Some(Net::Net2_270),
             [0, 1, 1, 0,
              0, 1, 0, 0,
              1, 1, 0, 0,
-             1, 0, 0, 0] => Net::Net3_0,
+             1, 0, 0, 0] => Some(Net::Net3_0),
             [1, 0, 0, 0,
              1, 1, 1, 0,
              0, 0, 1, 1,
-             0, 0, 0, 0] => Net::Net3_90,
+             0, 0, 0, 0] => Some(Net::Net3_90),
             [0, 0, 1, 0,
              0, 1, 1, 0,
              0, 1, 0, 0,
-             1, 1, 0, 0] => Net::Net3_180,
+             1, 1, 0, 0] => Some(Net::Net3_180),
             [1, 1, 0, 0,
              0, 1, 1, 1,
              0, 0, 0, 1,
-             0, 0, 0, 0] => Net::Net3_270,
-            _ => panic!("Unresolved Cube net")
+             0, 0, 0, 0] => Some(Net::Net3_270),
+            _ => None
         }
     }
 
@@ -157,8 +161,8 @@ impl Net {
         (npos, *entry_heading)
     }
 
-    pub fn get_edge_wrappings(&self, exit_gridface: usize, exit_heading: &Heading) -> (usize, Heading) {
-        match (self, exit_gridface, exit_heading) {
+    pub fn get_edge_wrappings(&self, exit_gridface: usize, exit_heading: &Heading) -> Result<(usize, Heading)> {
+        let wrapping = match (self, exit_gridface, exit_heading) {
             // ..#.
             // ####
             // ..#.
@@ -187,9 +191,9 @@ impl Net {
             (Net::Net1_0, 10, Heading::South) => (4, Heading::North),
             (Net::Net1_0, 10, Heading::East) => (7, Heading::North),
             (Net::Net1_0, 10, Heading::West) => (5, Heading::North),
-            (Net::Net1_90, _, _) => todo!(),
-            (Net::Net1_180, _, _) => todo!(),
-            (Net::Net1_270, _, _) => todo!(),
+            (Net::Net1_90 | Net::Net1_180 | Net::Net1_270, _, _) => {
+                return Err(Error::invalid_state(format!("Folding net '{:?}' isn't supported", self)));
+            },
             // ..#.
             // ###.
             // ..##
@@ -201,7 +205,7 @@ impl Net {
             (Net::Net2_0, 4, Heading::North) => (2, Heading::South),
             (Net::Net2_0, 4, Heading::South) => (10, Heading::North),
             (Net::Net2_0, 4, Heading::East) => (5, Heading::East),
-            (Net::Net2_0, 4, Heading::West) => (7, Heading::North),
+            (Net::Net2_0, 4, Heading::West) => (11, Heading::North),
             (Net::Net2_0, 5, Heading::North) => (2, Heading::East),
             (Net::Net2_0, 5, Heading::South) => (10, Heading::East),
             (Net::Net2_0, 5, Heading::East) => (6, Heading::East),
@@ -246,16 +250,18 @@ impl Net {
             (Net::Net3_0, 12, Heading::South) => (2, Heading::South),
             (Net::Net3_0, 12, Heading::East) => (9, Heading::North),
             (Net::Net3_0, 12, Heading::West) => (1, Heading::South),
-            _ => panic!("Net '{:?}' does not have face '{}'", self, exit_gridface)
-        }
+            _ => return Err(Error::invalid_state(format!("Net '{:?}' does not have face '{}'", self, exit_gridface)))
+        };
+        Ok(wrapping)
     }
 
     pub fn move_to_adj_face(
         &self,
-        pos: &(usize, usize),
+        position: Point,
         heading: &Heading,
         board_size: usize
-    ) -> ((usize, usize), Heading) {
+    ) -> Result<(Point, Heading)> {
+        let pos = &(position.x as usize, position.y as usize);
         let (x, y) = pos;
         let gridface = (x / board_size) + ((y / board_size) * 4);
         let up_edge = y % board_size == 0 && matches!(heading, Heading::North);
@@ -263,10 +269,11 @@ impl Net {
         let right_edge = x % board_size == (board_size - 1) && matches!(heading, Heading::East);
         let left_edge = x % board_size == 0 && matches!(heading, Heading::West);
         if up_edge || down_edge || right_edge || left_edge {
-            let (entry_gridface, entry_heading) = self.get_edge_wrappings(gridface, heading);
-            Self::edge_wrapping(pos, entry_gridface, heading, &entry_heading, board_size)
+            let (entry_gridface, entry_heading) = self.get_edge_wrappings(gridface, heading)?;
+            let ((x, y), heading) = Self::edge_wrapping(pos, entry_gridface, heading, &entry_heading, board_size);
+            Ok((Point::new(x as isize, y as isize), heading))
         } else {
-            panic!("Not in an face edge or facing into edge.")
+            Err(Error::invalid_state("Not in an face edge or facing into edge."))
         }
     }
 }
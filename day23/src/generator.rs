@@ -0,0 +1,89 @@
+use std::collections::{HashMap, HashSet};
+
+use common::{Generated, Rng};
+
+/// Squares checked before moving north, south, west and east, the step being the middle one
+const LOOKS: [[(i64, i64); 3]; 4] = [
+    [(-1, -1), (0, -1), (1, -1)],
+    [(-1, 1), (0, 1), (1, 1)],
+    [(-1, -1), (-1, 0), (-1, 1)],
+    [(1, -1), (1, 0), (1, 1)]
+];
+
+/// Moves the elves once, `false` when none of them moved
+fn spread(elves: &mut HashSet<(i64, i64)>, round: usize) -> bool {
+    let taken = |(x, y): (i64, i64), (dx, dy): &(i64, i64)| elves.contains(&(x + dx, y + dy));
+    let proposals: Vec<_> = elves.iter()
+        .filter_map(
+            |elf| {
+                let crowded = (-1..=1).any(|dx| (-1..=1).any(|dy| (dx, dy) != (0, 0) && taken(*elf, &(dx, dy))));
+                let look = (0..4)
+                    .map(|i| LOOKS[(round + i) % 4])
+                    .find(|look| crowded && !look.iter().any(|step| taken(*elf, step)))?;
+                Some((*elf, (elf.0 + look[1].0, elf.1 + look[1].1)))
+            }
+        )
+        .collect();
+    let mut wanted = HashMap::new();
+    for (_, target) in proposals.iter() {
+        *wanted.entry(*target).or_insert(0) += 1;
+    }
+    let mut moved = false;
+    for (elf, target) in proposals {
+        if wanted[&target] == 1 {
+            elves.remove(&elf);
+            elves.insert(target);
+            moved = true;
+        }
+    }
+    moved
+}
+
+fn empty_squares(elves: &HashSet<(i64, i64)>) -> i64 {
+    let (xs, ys): (Vec<_>, Vec<_>) = elves.iter().copied().unzip();
+    let width = xs.iter().max().unwrap() - xs.iter().min().unwrap() + 1;
+    let height = ys.iter().max().unwrap() - ys.iter().min().unwrap() + 1;
+    width * height - elves.len() as i64
+}
+
+/// Elves on about half the squares of a `size` wide grove, spread round by round on a set of positions
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let side = size.max(1);
+    let mut rows: Vec<Vec<char>> = (0..side)
+        .map(|_| (0..side).map(|_| if rng.chance(50) { '#' } else { '.' }).collect())
+        .collect();
+    rows[rng.below(side)][rng.below(side)] = '#';
+    let mut elves: HashSet<(i64, i64)> = rows.iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, c)| **c == '#').map(move |(x, _)| (x as i64, y as i64)))
+        .collect();
+
+    let mut round = 0;
+    let mut empty = None;
+    while spread(&mut elves, round) {
+        round += 1;
+        if round == 10 {
+            empty = Some(empty_squares(&elves));
+        }
+    }
+    let stable = round + 1;
+
+    let input = rows.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(input, empty.unwrap_or_else(|| empty_squares(&elves)), stable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day23;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..5 {
+            generate(&mut Rng::new(seed), 12).check::<Day23>().unwrap();
+        }
+    }
+}
@@ -1,117 +1,86 @@
-use std::collections::{BTreeSet, BTreeMap};
-
-#[allow(dead_code)]
-fn print_positions(positions: &BTreeSet<(isize, isize)>) {
-    let pos_bounds = position_bounds(positions);
-    // println!("{:?}", pos_bounds);
-    (pos_bounds.0.1..=pos_bounds.1.1).rev().for_each(
-        |y| {
-            (pos_bounds.0.0..=pos_bounds.1.0).for_each(
-                |x| {
-                    if positions.contains(&(x, y)) {
-                        print!("#")
-                    } else {
-                        print!(".")
-                    }
-                }
-            );
-            println!();
-        }
-    )
-}
+pub mod generator;
 
-fn position_bounds(positions: &BTreeSet<(isize, isize)>) -> ((isize, isize), (isize, isize)) {
-    (
-        (
-            positions.iter().map(|(x, _)| *x).min_by(|lx, rx| lx.cmp(rx)).unwrap(),
-            positions.iter().map(|(_, y)| *y).min_by(|ly, ry| ly.cmp(ry)).unwrap()
-        ),
-        (
-            positions.iter().map(|(x, _)| *x).max_by(|lx, rx| lx.cmp(rx)).unwrap(),
-            positions.iter().map(|(_, y)| *y).max_by(|ly, ry| ly.cmp(ry)).unwrap()
-        )
-)
-}
+use std::collections::{HashMap, HashSet};
 
-fn initial_positions(plots: &str) -> BTreeSet<(isize, isize)> {
-    let mut bt = BTreeSet::new();
-    plots.lines().rev().enumerate()
-        .for_each(
-            |(y, l)| {
-                l.chars().enumerate()
-                    .for_each(
-                        |(x, c)| {
-                            if c == '#' {
-                                bt.insert((x as isize, y as isize));
-                            }
-                        }
-                    )
-            }
-        );
-    bt
-}
+use common::{Error, Json, Puzzle, Result};
+use grid::{Point, SparseGrid};
+
+const SPREAD_ROUNDS: usize = 10;
+
+/// Step every elf considers, in the order of the first round, along with the plots that must be free for it
+const PROPOSALS: [(Point, [Point; 3]); 4] = [
+    (Point::NORTH, [Point::NORTH_WEST, Point::NORTH, Point::NORTH_EAST]),
+    (Point::SOUTH, [Point::SOUTH_WEST, Point::SOUTH, Point::SOUTH_EAST]),
+    (Point::WEST, [Point::NORTH_WEST, Point::WEST, Point::SOUTH_WEST]),
+    (Point::EAST, [Point::NORTH_EAST, Point::EAST, Point::SOUTH_EAST])
+];
+
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    /// Positions of every elf
+    type Input = SparseGrid<()>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        initial_positions(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(game_of_plating(input, SPREAD_ROUNDS).0)
+    }
 
-fn check_north(pos: &(isize, isize), positions: &BTreeSet<(isize, isize)>) -> bool {
-    let nw = !positions.contains(&(pos.0 - 1, pos.1 + 1));
-    let n = !positions.contains(&(pos.0, pos.1 + 1));
-    let ne = !positions.contains(&(pos.0 + 1, pos.1 + 1));
-    nw && n && ne
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(game_of_plating(input, usize::MAX).1)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("elves", input.len())
+    }
 }
 
-fn check_south(pos: &(isize, isize), positions: &BTreeSet<(isize, isize)>) -> bool {
-    let sw = !positions.contains(&(pos.0 - 1, pos.1 - 1));
-    let s = !positions.contains(&(pos.0, pos.1 - 1));
-    let se = !positions.contains(&(pos.0 + 1, pos.1 - 1));
-    sw && s && se
+/// Draws the smallest rectangle holding every elf
+pub fn render_positions(positions: &SparseGrid<()>) -> String {
+    positions.render('.', |_| '#')
 }
 
-fn check_east(pos: &(isize, isize), positions: &BTreeSet<(isize, isize)>) -> bool {
-    let ne = !positions.contains(&(pos.0 + 1, pos.1 + 1));
-    let e = !positions.contains(&(pos.0 + 1, pos.1));
-    let se = !positions.contains(&(pos.0 + 1, pos.1 - 1));
-    ne && e && se
+fn initial_positions(plots: &str) -> Result<SparseGrid<()>> {
+    let positions = SparseGrid::parse(plots, '.', |c| (c == '#').then_some(()))?;
+    if positions.is_empty() {
+        return Err(Error::invalid_state("No elves on the plots"));
+    }
+    Ok(positions)
 }
 
-fn check_west(pos: &(isize, isize), positions: &BTreeSet<(isize, isize)>) -> bool {
-    let nw = !positions.contains(&(pos.0 - 1, pos.1 + 1));
-    let w = !positions.contains(&(pos.0 - 1, pos.1));
-    let sw = !positions.contains(&(pos.0 - 1, pos.1 - 1));
-    nw && w && sw
+fn is_free(pos: Point, plots: &[Point; 3], positions: &SparseGrid<()>) -> bool {
+    plots.iter().all(|p| !positions.contains(pos + *p))
 }
 
-fn round(positions: &BTreeSet<(isize, isize)>, round: usize) -> BTreeSet<(isize, isize)> {
-    let intents: BTreeMap<_, _> = positions.iter()
+fn round(positions: &SparseGrid<()>, round: usize) -> SparseGrid<()> {
+    let intents: HashMap<_, _> = positions.points()
         .map(
             |pos| {
-                let round_tests = vec![
-                    (check_north(pos, positions), (pos.0, pos.1 + 1)),
-                    (check_south(pos, positions), (pos.0, pos.1 - 1)),
-                    (check_west(pos, positions), (pos.0 - 1, pos.1)),
-                    (check_east(pos, positions), (pos.0 + 1, pos.1))
-                ];
-                if round_tests.iter().all(|(b, _)| *b) {
-                    (pos, *pos)
+                if positions.neighbours8(pos).next().is_none() {
+                    (pos, pos)
                 } else {
-                    let round_cycle = round_tests.iter().cycle();
-                    let mut round_intent = None;
-                    for (test, intent) in round_cycle.skip(round).take(4) {
-                        if *test {
-                            round_intent = Some(intent);
-                            break;
-                        }
-                    }
-                    let fround_intent = round_intent.unwrap_or(pos);
-                    (pos, *fround_intent)
+                    let intent = PROPOSALS.iter()
+                        .cycle()
+                        .skip(round % PROPOSALS.len())
+                        .take(PROPOSALS.len())
+                        .find(|(_, plots)| is_free(pos, plots, positions))
+                        .map_or(pos, |(step, _)| pos + *step);
+                    (pos, intent)
                 }
             }
         )
         .collect();
     let unique_intents = intents.values()
         .fold(
-            (BTreeSet::new(), BTreeSet::new()),
+            (HashSet::new(), HashSet::new()),
             |(mut uniq, mut dup), int| {
                 if dup.contains(int) {
-                    ()
+                    // Already known to be contested
                 } else if uniq.contains(int) {
                     uniq.remove(int);
                     dup.insert(int);   
@@ -123,39 +92,30 @@ fn round(positions: &BTreeSet<(isize, isize)>, round: usize) -> BTreeSet<(isize,
         ).0;
         
     intents.iter()
-        .filter_map(
+        .map(
             |(pos, int)| {
                 if unique_intents.contains(&int) {
-                    Some(*int)
+                    (*int, ())
                 } else {
-                    Some(**pos)
+                    (*pos, ())
                 }
             } 
         )
         .collect()
 }
 
-fn empty_plots(positions: &BTreeSet<(isize, isize)>) -> u64 {
-    let (minbounds, maxbounds) = position_bounds(positions);
-    (minbounds.0..=maxbounds.0).fold(
+fn empty_plots(positions: &SparseGrid<()>) -> u64 {
+    positions.bounds().map_or(
         0,
-        |cum, x| {
-            cum + (minbounds.1..=maxbounds.1).fold(
-                0,
-                |ycum, y| {
-                    if positions.contains(&(x, y)) {
-                        ycum
-                    } else {
-                        ycum + 1
-                    }
-                }
-            )
+        |(min, max)| {
+            let area = (max.x - min.x + 1) * (max.y - min.y + 1);
+            area as u64 - positions.len() as u64
         }
     )
 }
 
-fn game_of_plating(plots: &str, rounds: usize) -> (u64, u64) {
-    let mut positions = initial_positions(plots);
+fn game_of_plating(positions: &SparseGrid<()>, rounds: usize) -> (u64, u64) {
+    let mut positions = positions.clone();
     let mut stable_round = 0;
     for r in 0..rounds {
         let tmp = round(&positions, r);
@@ -169,26 +129,12 @@ fn game_of_plating(plots: &str, rounds: usize) -> (u64, u64) {
     (empty_plots(&positions), stable_round as u64)
 }
 
-pub fn elfs_game_of_plating(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => game_of_plating(&content, 10).0,
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn elfs_game_of_plating(input_path: &str) -> Result<u64> {
+    Day23::part1(&Day23::parse_file(input_path)?)
 }
 
-pub fn elfs_game_of_plating_till_stable(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => game_of_plating(&content, std::usize::MAX).1,
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn elfs_game_of_plating_till_stable(input_path: &str) -> Result<u64> {
+    Day23::part2(&Day23::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -206,11 +152,23 @@ r#"....#..
 
     #[test]
     fn test_input1() {
-        assert_eq!(game_of_plating(TEST_INP1, 10).0, 110)
+        assert_eq!(Day23::solve_part1(TEST_INP1).unwrap(), 110)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(game_of_plating(TEST_INP1, std::usize::MAX).1, 20)
+        assert_eq!(Day23::solve_part2(TEST_INP1).unwrap(), 20)
+    }
+
+    #[test]
+    fn test_render_positions() {
+        let positions = Day23::parse(".....\n..##.\n..#..\n.....\n..##.\n.....").unwrap();
+        let spread = (0..3).fold(positions, |positions, r| round(&positions, r));
+        assert_eq!(render_positions(&spread), "..#..\n....#\n#....\n....#\n.....\n..#..")
+    }
+
+    #[test]
+    fn test_unknown_plot() {
+        assert!(matches!(Day23::parse("..#\n.x#"), Err(Error::Parse { line: 2, column: 2, .. })))
     }
 }
\ No newline at end of file
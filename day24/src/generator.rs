@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use common::{Generated, Rng};
+
+/// Blizzards of the valley, each sorted by the row or column it moves along
+struct Valley {
+    width: i64,
+    height: i64,
+    right: Vec<Vec<i64>>,
+    left: Vec<Vec<i64>>,
+    down: Vec<Vec<i64>>,
+    up: Vec<Vec<i64>>
+}
+
+impl Valley {
+    /// Whether a blizzard is on the inner square (`x`, `y`) at `minute`, inner squares counted from 0
+    fn blocked(&self, minute: i64, x: i64, y: i64) -> bool {
+        self.right[y as usize].iter().any(|start| (start + minute).rem_euclid(self.width) == x)
+            || self.left[y as usize].iter().any(|start| (start - minute).rem_euclid(self.width) == x)
+            || self.down[x as usize].iter().any(|start| (start + minute).rem_euclid(self.height) == y)
+            || self.up[x as usize].iter().any(|start| (start - minute).rem_euclid(self.height) == y)
+    }
+
+    /// Minute of arrival at `to`, leaving `from` at `minute`, positions counted on the walled map
+    fn trip(&self, mut minute: i64, from: (i64, i64), to: (i64, i64)) -> Option<i64> {
+        let mut reached = HashSet::from([from]);
+        while !reached.contains(&to) {
+            if minute > 10_000 {
+                return None;
+            }
+            minute += 1;
+            reached = reached.iter()
+                .flat_map(|(x, y)| [(*x, *y), (x + 1, *y), (x - 1, *y), (*x, y + 1), (*x, y - 1)])
+                .filter(
+                    |(x, y)| {
+                        let inside = (1..=self.width).contains(x) && (1..=self.height).contains(y);
+                        (*x, *y) == from || (*x, *y) == to || (inside && !self.blocked(minute, x - 1, y - 1))
+                    }
+                )
+                .collect();
+        }
+        Some(minute)
+    }
+}
+
+/// Blizzard basin `size` squares wide and a third as high, the elves' positions expanded minute by minute
+///
+/// Each blizzard's square is found from its starting one and the minute, rather than moving the whole basin.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let width = size.max(2) as i64;
+    let height = (size / 3).max(2) as i64;
+    loop {
+        let mut valley = Valley {
+            width,
+            height,
+            right: vec![vec![]; height as usize],
+            left: vec![vec![]; height as usize],
+            down: vec![vec![]; width as usize],
+            up: vec![vec![]; width as usize]
+        };
+        let mut rows = vec![format!("#.{}", "#".repeat(width as usize))];
+        for y in 0..height {
+            let mut row = String::from("#");
+            for x in 0..width {
+                let symbol = if rng.chance(35) { *rng.pick(&['>', '<', 'v', '^']) } else { '.' };
+                match symbol {
+                    '>' => valley.right[y as usize].push(x),
+                    '<' => valley.left[y as usize].push(x),
+                    'v' => valley.down[x as usize].push(y),
+                    '^' => valley.up[x as usize].push(y),
+                    _ => ()
+                }
+                row.push(symbol);
+            }
+            row.push('#');
+            rows.push(row);
+        }
+        rows.push(format!("{}.#", "#".repeat(width as usize)));
+
+        let (entry, exit) = ((1, 0), (width, height + 1));
+        let there = valley.trip(0, entry, exit);
+        let back = there.and_then(|there| valley.trip(there, exit, entry));
+        let again = back.and_then(|back| valley.trip(back, entry, exit));
+        // Only basins the solver can cross within its time limit
+        if let (Some(there), Some(back), Some(again)) = (there, back, again) {
+            if there.max(back - there).max(again - back) <= 1000 {
+                return Generated::solved(rows.join("\n"), there, again);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day24;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..10 {
+            generate(&mut Rng::new(seed), 24).check::<Day24>().unwrap();
+        }
+    }
+}
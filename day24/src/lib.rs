@@ -1,199 +1,171 @@
+pub mod generator;
+
 use std::collections::BTreeSet;
 
+use common::{trace::{self, Event, Level}, Error, Json, Puzzle, Result};
+use grid::{Grid, Point};
+
+const TRIP_LIMIT: u64 = 1000;
+
+pub struct Day24;
+
+impl Puzzle for Day24 {
+    type Input = Grid<BoardTile>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_board(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        blizzard_pathing(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        blizzard_pathing_2_trips(input)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        let blizzards: usize = input.iter()
+            .map(
+                |(_, tile)| match tile {
+                    BoardTile::Blizzards(n, s, e, w) => [n, s, e, w].iter().filter(|b| ***b).count(),
+                    BoardTile::Wall => 0
+                }
+            )
+            .sum();
+        Json::object()
+            .with("width", input.width())
+            .with("height", input.height())
+            .with("blizzards", blizzards)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum BoardTile {
+pub enum BoardTile {
     Wall,
     // North, South, East, West
     Blizzards(bool, bool, bool, bool)
 }
 
+impl BoardTile {
+    fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(Self::Wall),
+            '.' => Some(Self::Blizzards(false, false, false, false)),
+            '^' => Some(Self::Blizzards(true, false, false, false)),
+            'v' => Some(Self::Blizzards(false, true, false, false)),
+            '>' => Some(Self::Blizzards(false, false, true, false)),
+            '<' => Some(Self::Blizzards(false, false, false, true)),
+            _ => None
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Wall => '#',
+            Self::Blizzards(false, false, false, false) => '.',
+            Self::Blizzards(true, false, false, false) => '^',
+            Self::Blizzards(false, true, false, false) => 'v',
+            Self::Blizzards(false, false, true, false) => '>',
+            Self::Blizzards(false, false, false, true) => '<',
+            Self::Blizzards(_, _, _, _) => 'N'
+        }
+    }
+
+    fn is_clear(&self) -> bool {
+        matches!(self, Self::Blizzards(false, false, false, false))
+    }
+}
+
 impl std::fmt::Display for BoardTile {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Wall => '#',
-                Self::Blizzards(false, false, false, false) => '.',
-                Self::Blizzards(true, false, false, false) => '^',
-                Self::Blizzards(false, true, false, false) => 'v',
-                Self::Blizzards(false, false, true, false) => '>',
-                Self::Blizzards(false, false, false, true) => '<',
-                Self::Blizzards(_, _, _, _) => 'N'
-            }
-        )
+        write!(f, "{}", self.symbol())
     }
 }
 
-#[allow(dead_code)]
-fn print_board(board: &Vec<Vec<BoardTile>>) {
-    board.iter()
-        .for_each(
-            |row| {
-                row.iter()
-                    .for_each(
-                        |t| print!("{}", t)
-                    );
-                println!();
-            }
-        )
+/// Draws the valley, `N` marking the tiles where several blizzards meet
+pub fn render_board(board: &Grid<BoardTile>) -> String {
+    board.render(BoardTile::symbol)
 }
 
-fn build_board(board: &str) -> Vec<Vec<BoardTile>> {
-    board.lines()
-        .map(
-            |l| {
-                l.chars()
-                    .map(
-                        |c| {
-                            match c {
-                                '#' => BoardTile::Wall,
-                                '.' => BoardTile::Blizzards(false, false, false, false),
-                                '^' => BoardTile::Blizzards(true, false, false, false),
-                                'v' => BoardTile::Blizzards(false, true, false, false),
-                                '>' => BoardTile::Blizzards(false, false, true, false),
-                                '<' => BoardTile::Blizzards(false, false, false, true),
-                                _ => panic!("Unknown tile type '{}'", c)
-                            }
-                        }
-                    )
-                    .collect()
-            }
-        )
-        .collect()
+fn build_board(board: &str) -> Result<Grid<BoardTile>> {
+    let tiles = Grid::parse(board, BoardTile::from_symbol)?;
+    if tiles.height() < 3 || tiles.width() < 3 {
+        return Err(Error::invalid_state("Board must be at least 3x3"));
+    }
+    Ok(tiles)
 }
 
-fn resolve_blizzards(board: &Vec<Vec<BoardTile>>) -> Vec<Vec<BoardTile>> {
-    board.iter().enumerate()
-        .map(
-            |(y, row)| {
-                if y > 0 && y < board.len() - 1 {
-                    row.iter().enumerate()
-                        .map(
-                            |(x, tile)| {
-                                if x > 0 && x < board[y].len() - 1 {
-                                    let north_test = if y == 1 {
-                                        board[board.len() - 2][x]
-                                    } else {
-                                        board[y - 1][x]
-                                    };
-                                    let from_north = matches!(north_test, BoardTile::Blizzards(_, true, _, _));
-                                    let south_test = if y == board.len() - 2 {
-                                        board[1][x]
-                                    } else {
-                                        board[y + 1][x]
-                                    };
-                                    let from_south = matches!(south_test, BoardTile::Blizzards(true, _, _, _));
-                                    let east_test = if x == board[y].len() - 2 {
-                                        board[y][1]
-                                    } else {
-                                        board[y][x + 1]
-                                    };
-                                    let from_east = matches!(east_test, BoardTile::Blizzards(_, _, _, true));
-                                    let west_test = if x == 1 {
-                                        board[y][board[y].len() - 2]
-                                    } else {
-                                        board[y][x - 1]
-                                    };
-                                    let from_west = matches!(west_test, BoardTile::Blizzards(_, _, true, _));
-                                    BoardTile::Blizzards(from_south, from_north, from_west, from_east)
-                                } else {
-                                    tile.clone()
-                                }
-                            }
-                        )
-                        .collect()
-                } else {
-                    row.clone()
-                }
-            }
-        )
-        .collect()
+fn resolve_blizzards(board: &Grid<BoardTile>) -> Grid<BoardTile> {
+    let (width, height) = (board.width() as isize, board.height() as isize);
+    // Blizzards reaching a wall come back from the opposite one
+    let inner = |p: Point| Point::new((p.x - 1).rem_euclid(width - 2) + 1, (p.y - 1).rem_euclid(height - 2) + 1);
+
+    let mut resolved = board.clone();
+    for p in board.points().filter(|p| p.x > 0 && p.y > 0 && p.x < width - 1 && p.y < height - 1) {
+        let from = |step: Point| board[inner(p + step)];
+        resolved[p] = BoardTile::Blizzards(
+            matches!(from(Point::SOUTH), BoardTile::Blizzards(true, _, _, _)),
+            matches!(from(Point::NORTH), BoardTile::Blizzards(_, true, _, _)),
+            matches!(from(Point::WEST), BoardTile::Blizzards(_, _, true, _)),
+            matches!(from(Point::EAST), BoardTile::Blizzards(_, _, _, true))
+        );
+    }
+    resolved
 }
 
-fn resolve_elves(
-    board: &Vec<Vec<BoardTile>>,
-    positions: &BTreeSet<(usize, usize)>
-) -> BTreeSet<(usize, usize)> {
-    let mut next_pos = BTreeSet::new();
+fn resolve_elves(board: &Grid<BoardTile>, positions: &BTreeSet<Point>) -> BTreeSet<Point> {
     positions.iter()
-        .for_each(
-            |(y, x)| {
-                if matches!(board[*y][*x], BoardTile::Blizzards(false, false, false, false)) {
-                    next_pos.insert((*y, *x));
-                }
-                if *y > 0 && matches!(board[y - 1][*x], BoardTile::Blizzards(false, false, false, false)) {
-                    next_pos.insert((y - 1, *x));
-                }
-                if *y < board.len() - 1 && matches!(board[y + 1][*x], BoardTile::Blizzards(false, false, false, false)) {
-                    next_pos.insert((y + 1, *x));
-                }
-                if *x > 0 && matches!(board[*y][x - 1], BoardTile::Blizzards(false, false, false, false)) {
-                    next_pos.insert((*y, x - 1));
-                }
-                if *x < board[*y].len() - 1 &&  matches!(board[*y][x + 1], BoardTile::Blizzards(false, false, false, false)) {
-                    next_pos.insert((*y, x + 1));
-                }
-            }
-        );
-    next_pos
+        .flat_map(|p| std::iter::once(*p).chain(board.neighbours4(*p)))
+        .filter(|p| board[*p].is_clear())
+        .collect()
 }
 
 fn trip(
-    mut board: Vec<Vec<BoardTile>>,
-    start_position: (usize, usize),
-    target: (usize, usize)
-) -> (Vec<Vec<BoardTile>>, u64) {
+    mut board: Grid<BoardTile>,
+    start_position: Point,
+    target: Point
+) -> Result<(Grid<BoardTile>, u64)> {
     let mut possible_pos = BTreeSet::from_iter(vec![start_position]);
     let mut elapsed = 0;
 
     while !possible_pos.contains(&target) {
-        if elapsed >= 1000 {
-            break;
+        if elapsed >= TRIP_LIMIT {
+            return Err(Error::invalid_state(format!("No path from {:?} to {:?} within {} minutes", start_position, target, TRIP_LIMIT)));
         }
         elapsed += 1;
         board = resolve_blizzards(&board);
         possible_pos = resolve_elves(&board, &possible_pos);
+        trace::emit(Level::Debug, || Event::BlizzardStep { minute: elapsed, reachable: possible_pos.len() });
     }
-    (board, elapsed)
+    Ok((board, elapsed))
 }
 
-fn blizzard_pathing(board: &str) -> u64 {
-    let board = build_board(board);
-    let target = (board.len() - 1, board[0].len() - 2);
-    trip(board, (0, 1), target).1
+fn entry_and_exit(board: &Grid<BoardTile>) -> (Point, Point) {
+    (Point::new(1, 0), Point::new(board.width() as isize - 2, board.height() as isize - 1))
 }
 
-pub fn blizzard_dodging(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => blizzard_pathing(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+fn blizzard_pathing(board: &Grid<BoardTile>) -> Result<u64> {
+    let (start, target) = entry_and_exit(board);
+    Ok(trip(board.clone(), start, target)?.1)
 }
 
-fn blizzard_pathing_2_trips(board: &str) -> u64 {
-    let board = build_board(board);
-    let start = (0, 1);
-    let target = (board.len() - 1, board[0].len() - 2);
-    let (board, trip1) = trip(board, start, target);
-    let (board, trip2) = trip(board, target, start);
-    let (_, trip3) = trip(board, start, target);
-    trip1 + trip2 + trip3
+pub fn blizzard_dodging(input_path: &str) -> Result<u64> {
+    Day24::part1(&Day24::parse_file(input_path)?)
 }
 
-pub fn blizzard_dodging_2_trips(input_path: &str) -> u64 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => blizzard_pathing_2_trips(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+fn blizzard_pathing_2_trips(board: &Grid<BoardTile>) -> Result<u64> {
+    let (start, target) = entry_and_exit(board);
+    let (board, trip1) = trip(board.clone(), start, target)?;
+    let (board, trip2) = trip(board, target, start)?;
+    let (_, trip3) = trip(board, start, target)?;
+    Ok(trip1 + trip2 + trip3)
+}
+
+pub fn blizzard_dodging_2_trips(input_path: &str) -> Result<u64> {
+    Day24::part2(&Day24::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -210,12 +182,17 @@ r#"#.######
 
     #[test]
     fn test_input1() {
-        assert_eq!(blizzard_pathing(TEST_INP1), 18)
+        assert_eq!(Day24::solve_part1(TEST_INP1).unwrap(), 18)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(blizzard_pathing_2_trips(TEST_INP1), 54)
+        assert_eq!(Day24::solve_part2(TEST_INP1).unwrap(), 54)
+    }
+
+    #[test]
+    fn test_render_board() {
+        assert_eq!(render_board(&Day24::parse(TEST_INP1).unwrap()), TEST_INP1)
     }
 
     #[test]
@@ -226,14 +203,14 @@ r#"#####
 #.^.#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #.^.#
 #...#
 #...#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -245,14 +222,14 @@ r#"#####
 #.v.#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #...#
 #...#
 #.v.#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -264,14 +241,14 @@ r#"#####
 #.>.#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #...#
 #..>#
 #...#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -283,14 +260,14 @@ r#"#####
 #.<.#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #...#
 #<..#
 #...#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -302,14 +279,14 @@ r#"#####
 #...#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #...#
 #...#
 #^^^#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -321,14 +298,14 @@ r#"#####
 #...#
 #vvv#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #vvv#
 #...#
 #...#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -340,14 +317,14 @@ r#"#####
 #..>#
 #..>#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #>..#
 #>..#
 #>..#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -359,14 +336,14 @@ r#"#####
 #<..#
 #<..#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #..<#
 #..<#
 #..<#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -378,14 +355,14 @@ r#"#####
 #.><#
 #.><#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #.<>#
 #.<>#
 #.<>#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -397,14 +374,14 @@ r#"#####
 #.^<#
 #...#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #.^>#
 #.<v#
 #...#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -416,14 +393,14 @@ r#"#####
 #<<<#
 #<<<#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #<<<#
 #<<<#
 #<<<#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
 
@@ -435,14 +412,19 @@ r#"#####
 #>>>#
 #>>>#
 #####"#
-        );
+        ).unwrap();
         let expected = build_board(
 r#"#####
 #>>>#
 #>>>#
 #>>>#
 #####"#
-        );
+        ).unwrap();
         assert!(resolve_blizzards(&blizzards).eq(&expected));
     }
+
+    #[test]
+    fn test_unknown_tile() {
+        assert!(matches!(Day24::parse("#.###\n#.x.#\n###.#"), Err(Error::Parse { line: 2, column: 3, .. })))
+    }
 }
\ No newline at end of file
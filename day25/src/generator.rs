@@ -0,0 +1,41 @@
+use common::{Generated, Rng};
+
+/// Balanced quinary digits, lowest first: shifting by 2 turns each remainder into a digit from '=' to '2'
+fn snafu(mut number: u64) -> String {
+    let mut digits = vec![];
+    while number > 0 {
+        digits.push(['=', '-', '0', '1', '2'][((number + 2) % 5) as usize]);
+        number = (number + 2) / 5;
+    }
+    digits.iter().rev().collect()
+}
+
+/// `size` fuel requirements written in SNAFU, the only answer being their sum
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let fuels: Vec<u64> = (0..size.max(1))
+        .map(
+            |_| {
+                let digits = rng.between(1, 12) as u32;
+                rng.between(1, 10_i64.pow(digits)) as u64
+            }
+        )
+        .collect();
+    let input = fuels.iter()
+        .map(|fuel| snafu(*fuel))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::new(input, Some(snafu(fuels.iter().sum())), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day25;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 100).check::<Day25>().unwrap();
+        }
+    }
+}
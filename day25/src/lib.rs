@@ -1,83 +1,96 @@
-fn snafu_to_decimal(snafu: &str) -> u64 {
-    snafu.chars().enumerate()
-        .fold(
-            0_i64,
-            |sum, (i, c)| {
-                let power = (snafu.len() - (i + 1)) as u32;
-                match c {
-                    '2' => (2 * 5_i64.pow(power)) + sum,
-                    '1' => (1 * 5_i64.pow(power)) + sum,
-                    '0' => sum,
-                    '-' => (-1 * 5_i64.pow(power)) + sum,
-                    '=' => (-2 * 5_i64.pow(power)) + sum,
-                    _ => panic!("Unknown SNAFU digit '{}'.", c)
-                }
-            }
-        ) as u64
-}
+pub mod generator;
+
+use common::{Error, Json, Puzzle, Result};
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    /// Fuel requirements, converted from SNAFU
+    type Input = Vec<i64>;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_fuels(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        input.iter()
+            .try_fold(0_i64, |sum, fuel| sum.checked_add(*fuel))
+            .map(decimal_to_snafu)
+            .ok_or_else(|| Error::invalid_state("Total fuel requirement out of range"))
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Self::Answer2> {
+        Err(Error::invalid_state("Day 25 has no second part"))
+    }
 
-fn decimal_to_snafu(decimal: u64) -> String {
-    let mut powers = vec![];
-    let mut divisor = decimal;
-    while divisor > 0 {
-        powers.push(divisor % 5);
-        divisor = divisor / 5;
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("requirements", input.len())
+            .with("total", input.iter().map(|fuel| *fuel as i128).sum::<i128>())
     }
-    powers.push(0);
-    powers.iter()
-        .scan(
-            0,
-            |carry, digit| {
-                let x = digit + *carry;
-                *carry = 0;
-                let c = match x {
-                    0 => "0",
-                    1 => "1",
-                    2 => "2",
-                    3 => {
-                        *carry = 1;
-                        "="
-                    },
-                    4 => {
-                        *carry = 1;
-                        "-"
-                    },
-                    5 => {
-                        *carry = 1;
-                        "0"
-                    },
-                    _ => panic!("Unresolved arm")
+}
+
+/// `None` when the number doesn't fit
+fn snafu_to_decimal(snafu: &str) -> Option<i64> {
+    // Wider while reading, as leading digits can take the number past the range its lower ones bring it
+    // back into
+    let number = snafu.chars()
+        .try_fold(
+            0_i128,
+            |sum, c| {
+                let digit = match c {
+                    '2' => 2,
+                    '1' => 1,
+                    '0' => 0,
+                    '-' => -1,
+                    '=' => -2,
+                    // `check_snafu` lets nothing else through
+                    _ => unreachable!("Unknown SNAFU digit '{}'", c)
                 };
-                Some(c)
+                sum.checked_mul(5)?.checked_add(digit)
             }
-        )
-        .collect::<Vec<_>>()
-        .join("")
-        .trim_end_matches('0')
-        .chars()
-        .rev()
-        .collect()
+        )?;
+    i64::try_from(number).ok()
 }
 
-fn sum_fuels(fuel: &str) -> u64 {
-    fuel.lines()
-        .map(|l| snafu_to_decimal(l))
-        .sum()
+/// Balanced quinary digits, lowest first: shifting by 2 turns each remainder into a digit from '=' to '2'
+fn decimal_to_snafu(decimal: i64) -> String {
+    if decimal == 0 {
+        return String::from("0");
+    }
+    let mut digits = vec![];
+    // Wide enough for the shift not to overflow
+    let mut rest = decimal as i128;
+    while rest != 0 {
+        digits.push(['=', '-', '0', '1', '2'][(rest + 2).rem_euclid(5) as usize]);
+        rest = (rest + 2).div_euclid(5);
+    }
+    digits.iter().rev().collect()
 }
 
-fn heat_fuel(fuel: &str) -> String {
-    decimal_to_snafu(sum_fuels(fuel))
+fn check_snafu(line_index: usize, snafu: &str) -> Result<()> {
+    match snafu.char_indices().find(|(_, c)| !matches!(c, '2' | '1' | '0' | '-' | '=')) {
+        Some((column, c)) => Err(Error::parse_at(line_index, column, snafu, format!("Unknown SNAFU digit '{}'", c))),
+        None => Ok(())
+    }
 }
 
-pub fn hot_air_ballon_fueling(input_path: &str) -> String {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => heat_fuel(&content),
-        Err(er) => {
-            println!("{}", er);
-            String::from("0")
-        }
-    }
+fn parse_fuels(fuel: &str) -> Result<Vec<i64>> {
+    fuel.lines()
+        .enumerate()
+        .map(
+            |(i, l)| {
+                check_snafu(i, l)?;
+                snafu_to_decimal(l).ok_or_else(|| Error::parse(i, l, "SNAFU number out of range"))
+            }
+        )
+        .collect()
+}
+
+pub fn hot_air_ballon_fueling(input_path: &str) -> Result<String> {
+    Day25::part1(&Day25::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -101,26 +114,26 @@ r#"1=-0-2
 
     #[test]
     fn test_input1() {
-        assert_eq!(heat_fuel(TEST_INP1), "2=-1=0")
+        assert_eq!(Day25::solve_part1(TEST_INP1).unwrap(), "2=-1=0")
     }
 
     #[test]
     fn snafu_to_decimal_test() {
-        assert_eq!(snafu_to_decimal("1"), 1);
-        assert_eq!(snafu_to_decimal("2"), 2);
-        assert_eq!(snafu_to_decimal("1="), 3);
-        assert_eq!(snafu_to_decimal("1-"), 4);
-        assert_eq!(snafu_to_decimal("10"), 5);
-        assert_eq!(snafu_to_decimal("11"), 6);
-        assert_eq!(snafu_to_decimal("12"), 7);
-        assert_eq!(snafu_to_decimal("2="), 8);
-        assert_eq!(snafu_to_decimal("2-"), 9);
-        assert_eq!(snafu_to_decimal("20"), 10);
-        assert_eq!(snafu_to_decimal("1=0"), 15);
-        assert_eq!(snafu_to_decimal("1-0"), 20);
-        assert_eq!(snafu_to_decimal("1=11-2"), 2022);
-        assert_eq!(snafu_to_decimal("1-0---0"), 12345);
-        assert_eq!(snafu_to_decimal("1121-1110-1=0"), 314159265);
+        assert_eq!(snafu_to_decimal("1"), Some(1));
+        assert_eq!(snafu_to_decimal("2"), Some(2));
+        assert_eq!(snafu_to_decimal("1="), Some(3));
+        assert_eq!(snafu_to_decimal("1-"), Some(4));
+        assert_eq!(snafu_to_decimal("10"), Some(5));
+        assert_eq!(snafu_to_decimal("11"), Some(6));
+        assert_eq!(snafu_to_decimal("12"), Some(7));
+        assert_eq!(snafu_to_decimal("2="), Some(8));
+        assert_eq!(snafu_to_decimal("2-"), Some(9));
+        assert_eq!(snafu_to_decimal("20"), Some(10));
+        assert_eq!(snafu_to_decimal("1=0"), Some(15));
+        assert_eq!(snafu_to_decimal("1-0"), Some(20));
+        assert_eq!(snafu_to_decimal("1=11-2"), Some(2022));
+        assert_eq!(snafu_to_decimal("1-0---0"), Some(12345));
+        assert_eq!(snafu_to_decimal("1121-1110-1=0"), Some(314159265));
     }
 
     #[test]
@@ -141,4 +154,24 @@ r#"1=-0-2
         assert_eq!(decimal_to_snafu(12345), "1-0---0");
         assert_eq!(decimal_to_snafu(314159265), "1121-1110-1=0");
     }
+
+    #[test]
+    fn test_negative_and_large() {
+        assert_eq!(snafu_to_decimal("-"), Some(-1));
+        assert_eq!(snafu_to_decimal("=11"), Some(-44));
+        assert_eq!(decimal_to_snafu(0), "0");
+        assert_eq!(decimal_to_snafu(-44), "=11");
+        assert_eq!(snafu_to_decimal(&decimal_to_snafu(i64::MAX)), Some(i64::MAX));
+        assert_eq!(snafu_to_decimal(&decimal_to_snafu(i64::MIN)), Some(i64::MIN));
+        assert_eq!(Day25::solve_part1("1=\n-\n-").unwrap(), "1");
+        assert!(matches!(Day25::parse(&"2".repeat(28)), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(Day25::parse(&"2".repeat(60)), Err(Error::Parse { line: 1, .. })));
+        let total = Day25::solve_part1(&[decimal_to_snafu(i64::MAX), String::from("1")].join("\n"));
+        assert!(matches!(total, Err(Error::InvalidState(_))))
+    }
+
+    #[test]
+    fn test_unknown_digit() {
+        assert!(matches!(Day25::parse("1=-0-2\n12a11"), Err(Error::Parse { line: 2, column: 3, .. })))
+    }
 }
\ No newline at end of file
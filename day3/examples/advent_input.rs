@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day3::organize_rucksacks("./day3/resources/input.txt");
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+use common::{Generated, Rng};
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Rucksacks for `size` groups of three elves
+///
+/// Each group splits the items between its elves, so only the badge is carried by all three, and each
+/// rucksack only has one of its own items in both compartments.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut rucksacks = vec![];
+    let mut misplaced = 0;
+    let mut badges = 0;
+
+    for _ in 0..size.max(1) {
+        // Priorities are the positions in ITEMS, starting at 1
+        let mut items: Vec<usize> = (1..=ITEMS.len()).collect();
+        rng.shuffle(&mut items);
+        let (badge, pools) = (items[0], &items[1..]);
+        badges += badge;
+
+        for pool in pools.chunks(pools.len() / 3) {
+            let shared = if rng.chance(10) { badge } else { pool[0] };
+            misplaced += shared;
+            let (left_pool, right_pool) = pool[1..].split_at(pool.len() / 2);
+            let badge_left = rng.chance(50);
+            let length = rng.between(4, 16) as usize;
+
+            let mut compartment = |own: &[usize], with_badge: bool| {
+                let mut compartment = vec![shared];
+                if with_badge && shared != badge {
+                    compartment.push(badge);
+                }
+                while compartment.len() < length {
+                    compartment.push(*rng.pick(own));
+                }
+                rng.shuffle(&mut compartment);
+                compartment
+            };
+            let left = compartment(left_pool, badge_left);
+            let right = compartment(right_pool, !badge_left);
+            rucksacks.push(
+                left.iter()
+                    .chain(right.iter())
+                    .map(|priority| ITEMS.as_bytes()[priority - 1] as char)
+                    .collect::<String>()
+            );
+        }
+    }
+
+    Generated::solved(rucksacks.join("\n"), misplaced, badges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day3;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 30).check::<Day3>().unwrap();
+        }
+    }
+}
@@ -1,79 +1,237 @@
-fn char_priority(c: char) -> u32 {
-    match c.cmp(&'a') {
-        std::cmp::Ordering::Less => (c as u32) - ('A' as u32) + 27,
-        _ => (c as u32) - ('a' as u32) + 1
+pub mod generator;
+
+use std::fmt::Display;
+
+use common::{Error, Json, Puzzle, Result};
+
+/// Set of items, bit `p` standing for the item of priority `p`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Items(u64);
+
+/// Priority of an item, 1 to 26 for `a` to `z` and 27 to 52 for `A` to `Z`
+pub fn priority(item: char) -> Option<u32> {
+    match item {
+        'a'..='z' => Some(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Some(item as u32 - 'A' as u32 + 27),
+        _ => None
     }
 }
 
-fn calculate_rucksack_error(rucksack: &str) -> u32 {
-    let (comp1, comp2) = rucksack.trim().split_at(rucksack.len() / 2);
-    let comp1_set = std::collections::BTreeSet::from_iter(comp1.chars());
-    let comp2_set = std::collections::BTreeSet::from_iter(comp2.chars());
-    let intersect = comp1_set.intersection(&comp2_set);
-    intersect.into_iter()
-        .map(|c| char_priority(*c))
-        .sum()
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => (b'a' + priority as u8 - 1) as char,
+        _ => (b'A' + priority as u8 - 27) as char
+    }
 }
 
-fn calculate_rucksacks_errors(rucksacks: &str) -> u32 {
-    let rucksacks = rucksacks.split_terminator("\n");
-    rucksacks
-        .map(
-            |rucksack| calculate_rucksack_error(rucksack)
-        )
-        .sum()
+impl Items {
+    /// Items of `content`, `None` if one of them isn't a letter
+    pub fn new(content: &str) -> Option<Self> {
+        content.chars().try_fold(Items(0), |items, c| Some(Items(items.0 | 1 << priority(c)?)))
+    }
+
+    pub fn contains(&self, item: char) -> bool {
+        priority(item).is_some_and(|priority| self.0 & 1 << priority != 0)
+    }
+
+    pub fn intersection(&self, other: &Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    pub fn union(&self, other: &Items) -> Items {
+        Items(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Items by increasing priority
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        (1..=52).filter(move |priority| self.0 & 1 << priority != 0).map(item)
+    }
+
+    pub fn priorities(&self) -> u32 {
+        (1..=52).filter(|priority| self.0 & 1 << priority != 0).sum()
+    }
+}
+
+impl Display for Items {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.iter().try_for_each(|item| write!(f, "{}", item))
+    }
+}
+
+/// A rucksack's items split in its two compartments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rucksack {
+    pub left: Items,
+    pub right: Items
 }
 
-pub fn organize_rucksacks(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => calculate_rucksacks_errors(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
+impl Rucksack {
+    /// Reads a line of items, both compartments holding as many
+    pub fn parse(line_index: usize, line: &str) -> Result<Self> {
+        let content = line.trim();
+        if content.is_empty() {
+            return Err(Error::parse(line_index, line, "Empty rucksack"));
         }
+        let offset = line.len() - line.trim_start().len();
+        if let Some((column, c)) = content.char_indices().find(|(_, c)| priority(*c).is_none()) {
+            return Err(Error::parse_at(line_index, offset + column, line, format!("Invalid item '{}'", c)));
+        }
+        if !content.len().is_multiple_of(2) {
+            return Err(Error::parse(line_index, line, format!("{} items can't fill two compartments evenly", content.len())));
+        }
+        let (left, right) = content.split_at(content.len() / 2);
+        let compartment = |half: &str, column: usize| Items::new(half)
+            .ok_or_else(|| Error::parse_at(line_index, offset + column, line, format!("Invalid items '{}'", half)));
+        Ok(Rucksack { left: compartment(left, 0)?, right: compartment(right, left.len())? })
+    }
+
+    pub fn items(&self) -> Items {
+        self.left.union(&self.right)
+    }
+
+    /// Items packed in both compartments
+    pub fn misplaced(&self) -> Items {
+        self.left.intersection(&self.right)
     }
 }
 
-fn calculate_badge(rucksack1: &str, rucksack2: &str, rucksack3: &str) -> u32 {
-    let ruck1 = std::collections::BTreeSet::from_iter(rucksack1.trim().chars());
-    let ruck2 = std::collections::BTreeSet::from_iter(rucksack2.trim().chars());
-    let ruck3 = std::collections::BTreeSet::from_iter(rucksack3.trim().chars());
-    std::collections::BTreeSet::from_iter(
-        ruck1.intersection(&ruck2).map(|c| *c)
-    )
-        .intersection(&ruck3)
-        .map(|c| char_priority(*c))
-        .sum()
-}
-
-fn calculate_badges(rucksacks: &str) -> u32 {
-    let rucksacks: Vec<&str> = rucksacks.split_terminator("\n").collect();
-    rucksacks
-        .chunks(3)
-        .map(
-            |group| {
-                if let [a, b, c] = group {
-                    calculate_badge(a, b, c)
-                } else {
-                    panic!("Chunk with more/less than 3 elfs.")
-                }
-            }
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    type Input = Vec<Rucksack>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.trim_end()
+            .lines()
+            .enumerate()
+            .map(|(i, rucksack)| Rucksack::parse(i, rucksack))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(
+            input.iter()
+                .map(|rucksack| rucksack.misplaced().priorities())
+                .sum()
         )
-        .sum()
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        badge_priorities(input, 3)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        summary(input, 3)
+    }
+
+    /// Items in both compartments of each rucksack, the items common to each group of three and
+    /// what's wrong with the groups
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        artefacts(input, 3)
+    }
 }
 
-pub fn organize_badges(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => calculate_badges(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
+/// Rucksacks and how many groups of `size` elves they make
+pub fn summary(rucksacks: &[Rucksack], size: usize) -> Json {
+    Json::object()
+        .with("rucksacks", rucksacks.len())
+        .with("groups", rucksacks.len().div_ceil(size.max(1)))
+}
+
+/// Items in both compartments of each rucksack, the items common to each group of `size` elves and
+/// what's wrong with the groups
+pub fn artefacts(rucksacks: &[Rucksack], size: usize) -> Result<Json> {
+    let diagnostics = check_groups(rucksacks, size)?;
+    Ok(
+        Json::object()
+            .with("misplaced", rucksacks.iter().map(|rucksack| rucksack.misplaced().to_string()).collect::<Json>())
+            .with("badges", rucksacks.chunks(size).map(|elves| common_items(elves).to_string()).collect::<Json>())
+            .with("diagnostics", diagnostics.iter().map(GroupDiagnostic::to_string).collect::<Json>())
+    )
+}
+
+pub fn organize_rucksacks(input_path: &str) -> Result<u32> {
+    Day3::part1(&Day3::parse_file(input_path)?)
+}
+
+/// Something keeping a group from having a single badge, groups are counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupDiagnostic {
+    /// The last group, with fewer elves than the others
+    Partial { group: usize, elves: usize },
+    NoBadge { group: usize },
+    SeveralBadges { group: usize, candidates: Items }
+}
+
+impl Display for GroupDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupDiagnostic::Partial { group, elves } => write!(f, "group {} only has {} elves", group, elves),
+            GroupDiagnostic::NoBadge { group } => write!(f, "group {} has no item in common", group),
+            GroupDiagnostic::SeveralBadges { group, candidates } => {
+                write!(f, "group {} has {} items in common: {}", group, candidates.len(), candidates)
+            }
         }
     }
 }
 
+/// Items carried by every elf of the group
+fn common_items(elves: &[Rucksack]) -> Items {
+    elves.iter()
+        .map(Rucksack::items)
+        .reduce(|common, items| common.intersection(&items))
+        .unwrap_or_default()
+}
+
+/// Every group of `size` elves, in input order, that doesn't have exactly one badge
+pub fn check_groups(rucksacks: &[Rucksack], size: usize) -> Result<Vec<GroupDiagnostic>> {
+    if size == 0 {
+        return Err(Error::invalid_state("Groups need at least one elf"));
+    }
+    Ok(
+        rucksacks.chunks(size)
+            .enumerate()
+            .filter_map(
+                |(i, elves)| {
+                    let common = common_items(elves);
+                    match common.len() {
+                        _ if elves.len() < size => Some(GroupDiagnostic::Partial { group: i + 1, elves: elves.len() }),
+                        0 => Some(GroupDiagnostic::NoBadge { group: i + 1 }),
+                        1 => None,
+                        _ => Some(GroupDiagnostic::SeveralBadges { group: i + 1, candidates: common })
+                    }
+                }
+            )
+            .collect()
+    )
+}
+
+/// Badge of each group of `size` elves, failing on the first group without exactly one
+pub fn badges(rucksacks: &[Rucksack], size: usize) -> Result<Vec<char>> {
+    if let Some(diagnostic) = check_groups(rucksacks, size)?.first() {
+        return Err(Error::invalid_state(format!("Badges unclear, {}", diagnostic)));
+    }
+    Ok(rucksacks.chunks(size).flat_map(|elves| common_items(elves).iter()).collect())
+}
+
+pub fn badge_priorities(rucksacks: &[Rucksack], size: usize) -> Result<u32> {
+    Ok(badges(rucksacks, size)?.into_iter().filter_map(priority).sum())
+}
+
+pub fn organize_badges(input_path: &str) -> Result<u32> {
+    Day3::part2(&Day3::parse_file(input_path)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,11 +246,77 @@ CrZsJsPPZsGzwwsLwLmpwMDw"#;
 
     #[test]
     fn test_input() {
-        assert_eq!(calculate_rucksacks_errors(TEST_INP), 157)
+        assert_eq!(Day3::solve_part1(TEST_INP).unwrap(), 157)
     }
 
     #[test]
     fn test_input_part2() {
-        assert_eq!(calculate_badges(TEST_INP), 70)
+        assert_eq!(Day3::solve_part2(TEST_INP).unwrap(), 70)
+    }
+
+    #[test]
+    fn test_invalid_item() {
+        let res = Day3::parse("vJrwpWtwJgWr\nqHRN1qRj");
+        assert!(matches!(res, Err(Error::Parse { line: 2, column: 5, .. })))
+    }
+
+    #[test]
+    fn test_invalid_item_column_in_raw_line() {
+        let res = Day3::parse("vJrwpWtwJgWr\n  qHRN1qRj");
+        assert!(matches!(res, Err(Error::Parse { line: 2, column: 7, .. })))
+    }
+
+    #[test]
+    fn test_empty_rucksack() {
+        let res = Day3::parse("vJrwpWtwJgWr\n  \nqHRNqRjq");
+        assert!(matches!(res, Err(Error::Parse { line: 2, column: 1, .. })));
+        assert_eq!(Day3::parse("vJrwpWtwJgWr\n\n").unwrap().len(), 1)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_odd_rucksack() {
+        let res = Day3::parse("vJrwpWtwJgWr\nqHRNqRj");
+        assert!(matches!(res, Err(Error::Parse { line: 2, .. })))
+    }
+
+    #[test]
+    fn test_item_reports() {
+        let rucksacks = Day3::parse(TEST_INP).unwrap();
+        let misplaced: String = rucksacks.iter().map(|rucksack| rucksack.misplaced().to_string()).collect();
+        assert_eq!(misplaced, "pLPvts");
+        assert_eq!(badges(&rucksacks, 3).unwrap(), ['r', 'Z']);
+        let items = Items::new("zaAZa").unwrap();
+        assert_eq!((items.to_string(), items.len(), items.priorities()), (String::from("azAZ"), 4, 1 + 26 + 27 + 52));
+        assert!(items.contains('Z') && !items.contains('b') && !items.contains('1'));
+        assert_eq!(Items::new("ab1"), None)
+    }
+
+    #[test]
+    fn test_group_diagnostics() {
+        let rucksacks = Day3::parse(TEST_INP).unwrap();
+        assert_eq!(
+            check_groups(&rucksacks, 2).unwrap(),
+            [
+                GroupDiagnostic::SeveralBadges { group: 1, candidates: Items::new("rsFMf").unwrap() },
+                GroupDiagnostic::SeveralBadges { group: 2, candidates: Items::new("qvwBT").unwrap() },
+                GroupDiagnostic::SeveralBadges { group: 3, candidates: Items::new("GJZ").unwrap() }
+            ]
+        );
+        let diagnostics = check_groups(&rucksacks, 4).unwrap();
+        assert_eq!(diagnostics, [GroupDiagnostic::NoBadge { group: 1 }, GroupDiagnostic::Partial { group: 2, elves: 2 }]);
+        assert_eq!(diagnostics[1].to_string(), "group 2 only has 2 elves");
+        assert!(badges(&rucksacks, 4).is_err());
+        assert!(check_groups(&rucksacks, 0).is_err());
+        assert_eq!(badge_priorities(&rucksacks, 3).unwrap(), 18 + 52)
+    }
+
+    #[test]
+    fn test_group_artefacts() {
+        let rucksacks = Day3::parse(TEST_INP).unwrap();
+        assert_eq!(summary(&rucksacks, 2).get("groups"), Some(&Json::from(3)));
+        let pairs = artefacts(&rucksacks, 2).unwrap();
+        assert_eq!(pairs.get("badges"), Some(&Json::from(vec!["frsFM", "qvwBT", "GJZ"])));
+        assert_eq!(artefacts(&rucksacks, 3).unwrap(), Day3::artefacts(&rucksacks).unwrap());
+        assert!(artefacts(&rucksacks, 0).is_err())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Assignments, Interval};
+
+/// How many elves of the whole camp are assigned each section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pairs: Vec<Assignments>,
+    /// Maximal runs of sections assigned to the same number of elves, by increasing section,
+    /// runs nobody is assigned to are left out
+    layers: Vec<(Interval, usize)>
+}
+
+/// Joins intervals following each other without a gap, expecting them sorted and disjoint
+fn merge(intervals: impl IntoIterator<Item = Interval>) -> Vec<Interval> {
+    let mut merged: Vec<Interval> = vec![];
+    for interval in intervals {
+        match merged.last_mut().and_then(|last| last.union(&interval)) {
+            Some(union) => *merged.last_mut().unwrap() = union,
+            None => merged.push(interval)
+        }
+    }
+    merged
+}
+
+impl Coverage {
+    pub fn new(pairs: &[Assignments]) -> Self {
+        // Sections past the last one assigned can be u64::MAX + 1
+        let mut changes: Vec<(u128, isize)> = pairs.iter()
+            .flat_map(|(a, b)| [a, b])
+            .flat_map(|elf| [(elf.start() as u128, 1), (elf.end() as u128 + 1, -1)])
+            .collect();
+        changes.sort_unstable();
+
+        let mut layers: Vec<(Interval, usize)> = vec![];
+        let (mut elves, mut from) = (0, 0);
+        for changes in changes.chunk_by(|a, b| a.0 == b.0) {
+            let section = changes[0].0;
+            if elves > 0 {
+                let run = Interval::new(from as u64, (section - 1) as u64).unwrap();
+                match layers.last_mut() {
+                    // Elves handing over at the boundary leave as many on both sides
+                    Some((last, count)) if *count == elves && last.end() as u128 + 1 == from => {
+                        *last = last.union(&run).unwrap();
+                    },
+                    _ => layers.push((run, elves))
+                }
+            }
+            elves = elves.checked_add_signed(changes.iter().map(|(_, change)| change).sum()).unwrap();
+            from = section;
+        }
+        Coverage { pairs: pairs.to_vec(), layers }
+    }
+
+    /// Number of elves assigned `section`
+    pub fn elves_at(&self, section: u64) -> usize {
+        let i = self.layers.partition_point(|(run, _)| run.end() < section);
+        match self.layers.get(i) {
+            Some((run, elves)) if run.start() <= section => *elves,
+            _ => 0
+        }
+    }
+
+    /// Sections assigned to at least one elf, as sorted disjoint intervals
+    pub fn covered(&self) -> Vec<Interval> {
+        merge(self.layers.iter().map(|(run, _)| *run))
+    }
+
+    /// Sections between the lowest and the highest assigned that nobody is assigned
+    pub fn gaps(&self) -> Vec<Interval> {
+        self.covered()
+            .windows(2)
+            .filter_map(|pair| Interval::new(pair[0].end() + 1, pair[1].start() - 1))
+            .collect()
+    }
+
+    /// Sections assigned to more than `elves` elves, as sorted disjoint intervals
+    pub fn covered_by_more_than(&self, elves: usize) -> Vec<Interval> {
+        merge(
+            self.layers.iter()
+                .filter(|(_, count)| *count > elves)
+                .map(|(run, _)| *run)
+        )
+    }
+
+    /// Most elves assigned the same section
+    pub fn max_elves(&self) -> usize {
+        self.layers.iter().map(|(_, elves)| *elves).max().unwrap_or(0)
+    }
+
+    /// Pairs, counted from 0 in input order, with either elf assigned a section of `range`
+    pub fn pairs_overlapping(&self, range: &Interval) -> Vec<usize> {
+        self.pairs.iter()
+            .enumerate()
+            .filter(|(_, (a, b))| a.overlaps(range) || b.overlaps(range))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
@@ -0,0 +1,39 @@
+use common::{Generated, Rng};
+
+const SECTIONS: usize = 99;
+
+/// `size` pairs of assignments, compared section by section
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut assignment = || {
+        let start = rng.between(1, SECTIONS as i64) as usize;
+        (start, rng.between(start as i64, SECTIONS as i64) as usize)
+    };
+    let pairs: Vec<_> = (0..size.max(1)).map(|_| (assignment(), assignment())).collect();
+
+    let input = pairs.iter()
+        .map(|((a, b), (c, d))| format!("{}-{},{}-{}", a, b, c, d))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (mut contained, mut overlapping) = (0, 0);
+    for (first, second) in pairs.iter() {
+        let covered = |(start, end): &(usize, usize), section: usize| *start <= section && section <= *end;
+        let common = (1..=SECTIONS).filter(|s| covered(first, *s) && covered(second, *s)).count();
+        let smallest = (first.1 - first.0).min(second.1 - second.0) + 1;
+        contained += (common == smallest) as usize;
+        overlapping += (common > 0) as usize;
+    }
+    Generated::solved(input, contained, overlapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day4;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 100).check::<Day4>().unwrap();
+        }
+    }
+}
@@ -1,64 +1,142 @@
-use std::ops::{RangeInclusive, Not};
+pub mod generator;
 
-fn assignment_to_range(assignment: &str) -> RangeInclusive<usize> {
-    assignment.split_once("-")
-        .and_then(|(a, b)| Some((a.trim(), b.trim())))
-        .and_then(|(a, b)| Some(a.parse().unwrap_or(std::usize::MAX)..=b.parse().unwrap_or(std::usize::MAX)))
-        .unwrap()
-}
+mod coverage;
+
+use common::{Error, Json, Puzzle, Result};
 
-fn pair_to_assignments(pair: &str) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
-    pair.split_once(",")
-        .map(|(a, b)| (assignment_to_range(a), assignment_to_range(b)))
-        .unwrap()
+pub use coverage::Coverage;
+
+/// Sections from `start` to `end`, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval {
+    start: u64,
+    end: u64
 }
 
-fn assignments_superset(assign1: RangeInclusive<usize>, assign2: RangeInclusive<usize>) -> bool {
-    let assign1 = std::collections::BTreeSet::from_iter(assign1);
-    let assign2 = std::collections::BTreeSet::from_iter(assign2);
-    assign1.is_superset(&assign2) || assign1.is_subset(&assign2)
+impl Interval {
+    /// `None` if the interval ends before it starts
+    pub fn new(start: u64, end: u64) -> Option<Self> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// Sections covered, never 0, and past a u64 for an interval of every ID
+    pub fn sections(&self) -> u128 {
+        (self.end - self.start) as u128 + 1
+    }
+
+    pub fn contains(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /// Sections covered by both
+    pub fn intersection_sections(&self, other: &Interval) -> u128 {
+        self.intersection(other).map_or(0, |common| common.sections())
+    }
+
+    /// The single interval covering both, `None` if there's a gap between them
+    pub fn union(&self, other: &Interval) -> Option<Interval> {
+        let (first, second) = if self.start <= other.start { (self, other) } else { (other, self) };
+        (second.start <= first.end.saturating_add(1)).then(|| Interval { start: first.start, end: first.end.max(second.end) })
+    }
+
+    /// Sections covered by either
+    pub fn union_sections(&self, other: &Interval) -> u128 {
+        self.sections() + other.sections() - self.intersection_sections(other)
+    }
 }
 
-fn find_assignments_supersets(pairs: &str) -> u32 {
-    pairs.split_terminator("\n")
-        .map(pair_to_assignments)
-        .map(|(a, b)| assignments_superset(a, b) as u32)
-        .sum()
+impl From<Interval> for Json {
+    fn from(interval: Interval) -> Self {
+        Json::from(vec![interval.start, interval.end])
+    }
 }
 
-pub fn organize_assignments_supersets(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => find_assignments_supersets(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
+pub type Assignments = (Interval, Interval);
+
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    type Input = Vec<Assignments>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.trim_end()
+            .lines()
+            .enumerate()
+            .map(|(i, pair)| pair_to_assignments(i, pair))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(
+            input.iter()
+                .map(|(a, b)| (a.contains(b) || b.contains(a)) as u32)
+                .sum()
+        )
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        Ok(
+            input.iter()
+                .map(|(a, b)| a.overlaps(b) as u32)
+                .sum()
+        )
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object()
+            .with("pairs", input.len())
+            .with("highest_section", input.iter().map(|(a, b)| a.end().max(b.end())).max())
+    }
+
+    /// Sections nobody is assigned between the lowest and highest assigned, and the most elves
+    /// assigned the same section
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        let coverage = Coverage::new(input);
+        Ok(
+            Json::object()
+                .with("gaps", coverage.gaps())
+                .with("max_elves", coverage.max_elves())
+        )
     }
 }
 
-fn assignments_overlap(assign1: RangeInclusive<usize>, assign2: RangeInclusive<usize>) -> bool {
-    let assign1 = std::collections::BTreeSet::from_iter(assign1);
-    let assign2 = std::collections::BTreeSet::from_iter(assign2);
-    assign1.is_disjoint(&assign2).not()
+fn assignment_to_interval(assignment: &str, line_index: usize, line: &str) -> Result<Interval> {
+    let (a, b) = assignment.split_once("-")
+        .ok_or_else(|| Error::parse(line_index, line, "Assignment without '-'"))?;
+    Interval::new(common::parse_field(a, line_index, line)?, common::parse_field(b, line_index, line)?)
+        .ok_or_else(|| Error::parse(line_index, line, format!("Assignment '{}' ends before it starts", assignment)))
 }
 
-fn find_assignments_overlaps(pairs: &str) -> u32 {
-    pairs.split_terminator("\n")
-        .map(pair_to_assignments)
-        .map(|(a, b)| assignments_overlap(a, b) as u32)
-        .sum()
+fn pair_to_assignments(line_index: usize, pair: &str) -> Result<Assignments> {
+    let (a, b) = pair.split_once(",")
+        .ok_or_else(|| Error::parse(line_index, pair, "Pair without ','"))?;
+    Ok((assignment_to_interval(a, line_index, pair)?, assignment_to_interval(b, line_index, pair)?))
 }
 
-pub fn organize_assignments_overlaps(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => find_assignments_overlaps(&content),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn organize_assignments_supersets(input_path: &str) -> Result<u32> {
+    Day4::part1(&Day4::parse_file(input_path)?)
+}
+
+pub fn organize_assignments_overlaps(input_path: &str) -> Result<u32> {
+    Day4::part2(&Day4::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -75,11 +153,56 @@ r#"2-4,6-8
 
     #[test]
     fn test_input() {
-        assert_eq!(find_assignments_supersets(TEST_INP), 2)
+        assert_eq!(Day4::solve_part1(TEST_INP).unwrap(), 2)
     }
 
     #[test]
     fn test_input_part2() {
-        assert_eq!(find_assignments_overlaps(TEST_INP), 4)
+        assert_eq!(Day4::solve_part2(TEST_INP).unwrap(), 4)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_malformed_pair() {
+        let res = Day4::parse("2-4,6-8\n2-3;4-5");
+        assert!(matches!(res, Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(Day4::parse("2-4,8-6"), Err(Error::Parse { line: 1, .. })));
+        assert!(matches!(Day4::parse("2-4,6-x"), Err(Error::Parse { line: 1, .. })))
+    }
+
+    #[test]
+    fn test_trailing_blank_lines() {
+        assert_eq!(Day4::parse("2-4,6-8\n2-3,4-5\n\n").unwrap().len(), 2);
+        assert!(matches!(Day4::parse("2-4,6-8\n\n2-3,4-5"), Err(Error::Parse { line: 2, .. })))
+    }
+
+    #[test]
+    fn test_wide_sections() {
+        let pairs = Day4::parse("1-18446744073709551615,5000000000-6000000000\n7-9,10-10000000000").unwrap();
+        assert_eq!((Day4::part1(&pairs).unwrap(), Day4::part2(&pairs).unwrap()), (1, 1));
+        let (whole, billion) = pairs[0];
+        assert_eq!(whole.sections(), u64::MAX as u128);
+        assert_eq!((whole.intersection_sections(&billion), whole.union_sections(&billion)), (1_000_000_001, u64::MAX as u128));
+        let (low, high) = pairs[1];
+        assert_eq!(low.union(&high), Interval::new(7, 10_000_000_000));
+        assert_eq!((low.intersection(&high), low.union_sections(&high)), (None, 3 + 9_999_999_991));
+        assert_eq!(low.union(&Interval::new(11, 12).unwrap()), None)
+    }
+
+    #[test]
+    fn test_coverage() {
+        let coverage = Coverage::new(&Day4::parse(TEST_INP).unwrap());
+        assert_eq!(coverage.covered(), [Interval::new(2, 9).unwrap()]);
+        assert!(coverage.gaps().is_empty());
+        let counts: Vec<_> = (1..=10).map(|section| coverage.elves_at(section)).collect();
+        assert_eq!(counts, [0, 4, 5, 7, 7, 8, 6, 4, 1, 0]);
+        assert_eq!(coverage.covered_by_more_than(5), [Interval::new(4, 7).unwrap()]);
+        assert_eq!(coverage.max_elves(), 8);
+        assert_eq!(coverage.pairs_overlapping(&Interval::new(8, 9).unwrap()), [0, 2, 3, 5]);
+
+        let camp = Coverage::new(&Day4::parse("1-3,8-9\n4-5,12-18446744073709551615").unwrap());
+        assert_eq!(camp.covered(), [Interval::new(1, 5).unwrap(), Interval::new(8, 9).unwrap(), Interval::new(12, u64::MAX).unwrap()]);
+        assert_eq!(camp.gaps(), [Interval::new(6, 7).unwrap(), Interval::new(10, 11).unwrap()]);
+        assert_eq!((camp.elves_at(u64::MAX), camp.elves_at(10)), (1, 0));
+        assert!(camp.covered_by_more_than(1).is_empty())
+    }
+}
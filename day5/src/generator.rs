@@ -0,0 +1,66 @@
+use common::{Generated, Rng};
+
+/// Drawing of up to 9 stacks followed by `size` valid moves, replayed on plain vectors
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let stack_count = rng.between(3, 9) as usize;
+    let stacks: Vec<Vec<char>> = (0..stack_count)
+        .map(
+            |_| {
+                (0..rng.between(0, 8))
+                    .map(|_| (b'A' + rng.below(26) as u8) as char)
+                    .collect()
+            }
+        )
+        .collect();
+
+    let (mut cargo_9000, mut cargo_9001) = (stacks.clone(), stacks.clone());
+    let mut moves = vec![];
+    for _ in 0..size.max(1) {
+        // Both cranes move the same amount of crates, only their order differs
+        let sources: Vec<usize> = (0..stack_count).filter(|s| !cargo_9000[*s].is_empty()).collect();
+        if sources.is_empty() {
+            break;
+        }
+        let source = *rng.pick(&sources);
+        let destination = (source + rng.between(1, stack_count as i64 - 1) as usize) % stack_count;
+        let height = cargo_9000[source].len();
+        let count = rng.between(1, height as i64) as usize;
+
+        let lifted = cargo_9000[source].split_off(height - count);
+        cargo_9000[destination].extend(lifted.into_iter().rev());
+        let lifted = cargo_9001[source].split_off(height - count);
+        cargo_9001[destination].extend(lifted);
+        moves.push(format!("move {} from {} to {}", count, source + 1, destination + 1));
+    }
+
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height).rev()
+        .map(
+            |level| {
+                stacks.iter()
+                    .map(|stack| stack.get(level).map_or(String::from("   "), |c| format!("[{}]", c)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        )
+        .collect();
+    lines.push((1..=stack_count).map(|i| format!(" {} ", i)).collect::<Vec<_>>().join(" "));
+    lines.push(String::new());
+    lines.extend(moves);
+
+    let tops = |cargo: &[Vec<char>]| cargo.iter().filter_map(|stack| stack.last()).collect::<String>();
+    Generated::solved(lines.join("\n"), tops(&cargo_9000), tops(&cargo_9001))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day5;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 50).check::<Day5>().unwrap();
+        }
+    }
+}
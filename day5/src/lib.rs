@@ -1,119 +1,344 @@
-#[derive(Clone)]
-struct Crate(char);
-#[derive(Clone)]
-struct Cargo(Vec<Vec<Crate>>);
-#[derive(Clone)]
-struct Move(usize, usize, usize);
-
-fn process_crate(line: &[char]) -> Option<Crate> {
-    match line {
-        ['[', c, ']', ' '] => Some(Crate(*c)),
-        [' ', ' ', ' ', ' '] => None,
-        ['[', c, ']'] => Some(Crate(*c)),
-        [' ', ' ', ' '] => None,
-        c => {
-            panic!("Cargo line malformed '{c:?}'")
+pub mod generator;
+
+mod planner;
+
+use std::fmt::Display;
+
+use common::{tokens, Error, Json, Puzzle, Result};
+
+pub use planner::{plan, Plan, Target, SEARCH_LIMIT};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Crate(String);
+
+/// A line of the procedure, `count` crates from stack `from` to stack `to`, stacks counted from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+    /// Line of the input the move was read from, counted from 0, and that line as written, `None` for
+    /// moves that weren't read from an input such as planned ones
+    pub source: Option<(usize, String)>
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// How a crane carries out a move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crane {
+    /// One crate at a time, reversing their order
+    CrateMover9000,
+    /// Every crate of the move at once, keeping their order
+    CrateMover9001,
+    /// At most that many crates at once, each lift keeping its crates' order
+    Limited(usize)
+}
+
+impl Crane {
+    /// Most crates moved in a single lift
+    pub fn capacity(&self) -> usize {
+        match self {
+            Crane::CrateMover9000 => 1,
+            Crane::CrateMover9001 => usize::MAX,
+            Crane::Limited(capacity) => *capacity
         }
     }
 }
 
-fn process_move(line: &str) -> Move {
-    let l = Vec::from_iter(line.split_whitespace());
-    let m: &[&str] = l.as_slice();
-    if let ["move", n, "from", s, "to", d] = m {
-        Move(n.parse().unwrap(), s.parse().unwrap(), d.parse().unwrap())
-    } else {
-        panic!("Malformed move '{:?}'", m)
+/// Makes a move already checked, returning the crates taken in their order on the stack they came from
+fn move_crates(stacks: &mut [Vec<Crate>], mov: &Move, capacity: usize) -> Vec<Crate> {
+    let from = &mut stacks[mov.from - 1];
+    let taken = from.split_off(from.len() - mov.count);
+    if mov.from == mov.to {
+        // Every lift puts its crates right back
+        from.extend_from_slice(&taken);
+        return taken;
+    }
+    // Each lift takes the top crates left, and the first lift's end up lowest
+    let to = &mut stacks[mov.to - 1];
+    for load in taken.rchunks(capacity) {
+        to.extend_from_slice(load);
     }
+    taken
+}
+
+/// A move carried out, with the crates it took in their order on the stack they came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lift {
+    mov: Move,
+    crane: Crane,
+    taken: Vec<Crate>
+}
+
+/// Stacks of crates, bottom to top, and the moves made on them so far
+///
+/// Moves can be undone back to the starting stacks, and the undone ones redone until a new move is made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cargo {
+    stacks: Vec<Vec<Crate>>,
+    done: Vec<Lift>,
+    undone: Vec<(Move, Crane)>
 }
 
-fn process_input(input: &str) -> (Cargo, Vec<Move>) {
-    let mut lines = input.split_terminator("\n");
-    let mut cargo_rows: Vec<Vec<Option<Crate>>> = vec![]; 
-    for line in &mut lines {
-        let chars = Vec::from_iter(line.chars());
-        if let [' ', '1', ' ', ' '] = chars.chunks(4).next().unwrap() {
-            break
-        } else {
-            cargo_rows.push(
-                chars.chunks(4).map(process_crate).collect()
-            );
+impl Cargo {
+    fn new(stacks: Vec<Vec<Crate>>) -> Self {
+        Cargo { stacks, done: vec![], undone: vec![] }
+    }
+
+    /// Reads a drawing of the stacks, crates as `[name]` over a last line labelling the stacks 1, 2, ...
+    ///
+    /// Each crate goes to the stack whose label is closest, so labels and crates of any width can be
+    /// lined up. Lines count from the drawing's first for errors, it's expected at the start of the input.
+    pub fn parse(drawing: &str) -> Result<Self> {
+        let lines: Vec<&str> = drawing.lines().collect();
+        let Some((label_line, crate_lines)) = lines.split_last() else {
+            return Err(Error::parse(0, drawing, "Drawing without stack labels"));
+        };
+        let label_index = crate_lines.len();
+        let mut centers = vec![];
+        for (column, label) in tokens(label_line) {
+            if label.parse::<usize>().ok() != Some(centers.len() + 1) {
+                let reason = format!("Expected stack label {}, found '{}'", centers.len() + 1, label);
+                return Err(Error::parse_at(label_index, column, label_line, reason));
+            }
+            // Doubled so the center of an even width stays whole
+            centers.push(2 * column + label.len());
+        }
+
+        let mut stacks = vec![vec![]; centers.len()];
+        for (i, line) in crate_lines.iter().enumerate().rev() {
+            let mut row: Vec<Option<(usize, Crate)>> = vec![None; centers.len()];
+            for (column, token) in tokens(line) {
+                let Some(name) = token.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) else {
+                    return Err(Error::parse_at(i, column, line, format!("Expected a crate, found '{}'", token)));
+                };
+                if name.is_empty() || name.contains(['[', ']']) {
+                    return Err(Error::parse_at(i, column, line, format!("Malformed crate '{}'", token)));
+                }
+                let center = 2 * column + token.len();
+                let stack = (0..centers.len())
+                    .min_by_key(|stack| centers[*stack].abs_diff(center))
+                    .ok_or_else(|| Error::parse_at(i, column, line, "Crate without any stack"))?;
+                if row[stack].replace((column, Crate(name.to_string()))).is_some() {
+                    return Err(Error::parse_at(i, column, line, format!("Second crate over stack {}", stack + 1)));
+                }
+            }
+            for (stack, c) in stacks.iter_mut().zip(row) {
+                match c {
+                    Some((column, c)) if stack.len() + i < label_index - 1 => {
+                        return Err(Error::parse_at(i, column, line, format!("Crate '{}' isn't on anything", c.0)));
+                    },
+                    Some((_, c)) => stack.push(c),
+                    None => ()
+                }
+            }
+        }
+        Ok(Cargo::new(stacks))
+    }
+
+    /// Every stack written bottom to top
+    pub fn stacks(&self) -> Vec<String> {
+        self.stacks.iter()
+            .map(|stack| stack.iter().map(|c| c.0.as_str()).collect())
+            .collect()
+    }
+
+    /// Crate on top of each stack, empty stacks left out
+    pub fn top_crates(&self) -> String {
+        self.stacks.iter()
+            .filter_map(|stack| stack.last())
+            .map(|c| c.0.as_str())
+            .collect()
+    }
+
+    /// Error about `mov`, quoting the line it was read from if any
+    fn move_error(mov: &Move, reason: String) -> Error {
+        match &mov.source {
+            Some((line_index, line)) => Error::parse(*line_index, line, reason),
+            None => Error::invalid_state(format!("{} for {}", reason, mov))
         }
     }
 
-    let mut cargo_columns = vec![vec![]; cargo_rows[0].len()];
-    for row in cargo_rows.into_iter().rev() {
-        for (i, c) in row.into_iter().enumerate() {
-            if let Some(crat) = c {
-                cargo_columns[i].push(crat);
-            } 
+    /// Checks both stacks of `mov` are in the cargo, whatever crates they hold
+    fn check_stacks(&self, mov: &Move) -> Result<()> {
+        for stack in [mov.from, mov.to] {
+            if !(1..=self.stacks.len()).contains(&stack) {
+                let reason = format!("No stack {} in a cargo of {} stacks", stack, self.stacks.len());
+                return Err(Cargo::move_error(mov, reason));
+            }
         }
+        Ok(())
     }
-    let cargo = Cargo(cargo_columns);
 
-    lines.next();
-    (cargo, lines.map(process_move).collect())
-}
+    fn check(&self, mov: &Move) -> Result<()> {
+        self.check_stacks(mov)?;
+        let available = self.stacks[mov.from - 1].len();
+        if available < mov.count {
+            return Err(Cargo::move_error(mov, format!("Stack {} only has {} crates", mov.from, available)));
+        }
+        Ok(())
+    }
 
-fn execute_moves(cargo: &mut Cargo, moves: &Vec<Move>) {
-    for mov in moves {
-        let Move(n, s, d) = mov;
-        for _ in 0..*n {
-            let tmp = cargo.0[*s - 1].pop().unwrap();
-            cargo.0[*d - 1].push(tmp);
+    fn lift(&mut self, mov: Move, crane: Crane) -> Result<()> {
+        if crane.capacity() == 0 {
+            return Err(Error::invalid_state("A crane has to lift at least one crate"));
         }
+        self.check(&mov)?;
+        let taken = move_crates(&mut self.stacks, &mov, crane.capacity());
+        self.done.push(Lift { mov, crane, taken });
+        Ok(())
+    }
+
+    /// Carries out `mov`, dropping the moves undone before
+    pub fn apply(&mut self, mov: Move, crane: Crane) -> Result<()> {
+        self.lift(mov, crane)?;
+        self.undone.clear();
+        Ok(())
+    }
+
+    /// Carries out every move in turn, stopping at the first one that can't be made
+    pub fn execute(&mut self, moves: &[Move], crane: Crane) -> Result<()> {
+        moves.iter().try_for_each(|mov| self.apply(mov.clone(), crane))
+    }
+
+    /// Puts back the crates of the last move made, `None` if there's none left
+    pub fn undo(&mut self) -> Option<Move> {
+        let Lift { mov, crane, taken } = self.done.pop()?;
+        let to = &mut self.stacks[mov.to - 1];
+        to.truncate(to.len() - mov.count);
+        self.stacks[mov.from - 1].extend(taken);
+        self.undone.push((mov.clone(), crane));
+        Some(mov)
+    }
+
+    /// Makes the last move undone again, with the same crane
+    pub fn redo(&mut self) -> Result<Option<Move>> {
+        let Some((mov, crane)) = self.undone.pop() else {
+            return Ok(None);
+        };
+        self.lift(mov.clone(), crane)?;
+        Ok(Some(mov))
     }
-}
 
-fn rearrenge_cargo(contents: &str) -> String {
-    let (mut cargo, moves) = process_input(contents);
-    execute_moves(&mut cargo, &moves);
-    cargo.0.iter()
-        .map(|v| v.last().unwrap().0.to_string())
-        .fold(String::from(""), |sum, c| sum + c.as_str())
+    /// Moves made so far, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &Move> {
+        self.done.iter().map(|lift| &lift.mov)
+    }
 }
 
-pub fn organize_cargo(input_path: &str) -> String {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => rearrenge_cargo(&content),
-        Err(er) => {
-            println!("{}", er);
-            String::new()
+/// Draws the stacks the way `Cargo::parse` reads them, every cell as wide as the widest crate or label
+impl Display for Cargo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.stacks.iter()
+            .flatten()
+            .map(|c| c.0.len() + 2)
+            .chain([self.stacks.len().to_string().len()])
+            .max()
+            .unwrap_or(0);
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let cells: Vec<String> = self.stacks.iter()
+                .map(|stack| format!("{:^width$}", stack.get(level).map_or(String::new(), |c| format!("[{}]", c.0))))
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
         }
+        let labels: Vec<String> = (1..=self.stacks.len())
+            .map(|label| format!("{:^width$}", label))
+            .collect();
+        write!(f, "{}", labels.join(" "))
     }
 }
 
-fn execute_moves_9001(cargo: &mut Cargo, moves: &Vec<Move>) {
-    for mov in moves {
-        let Move(n, s, d) = mov;
-        let mut q = vec![];
-        for _ in 0..*n {
-            q.push(cargo.0[*s - 1].pop().unwrap());
-        }
-        for c in q.into_iter().rev() {
-            cargo.0[*d - 1].push(c);
-        }
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    type Input = (Cargo, Vec<Move>);
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        process_input(input)
+    }
+
+    fn part1((cargo, moves): &Self::Input) -> Result<Self::Answer1> {
+        Ok(rearrange(cargo, moves, Crane::CrateMover9000)?.top_crates())
+    }
+
+    fn part2((cargo, moves): &Self::Input) -> Result<Self::Answer2> {
+        Ok(rearrange(cargo, moves, Crane::CrateMover9001)?.top_crates())
+    }
+
+    fn summary((cargo, moves): &Self::Input) -> Json {
+        Json::object()
+            .with("stacks", cargo.stacks.len())
+            .with("crates", cargo.stacks.iter().map(Vec::len).sum::<usize>())
+            .with("moves", moves.len())
+    }
+
+    /// Final stacks left by each crane, every stack written bottom to top
+    fn artefacts((cargo, moves): &Self::Input) -> Result<Json> {
+        Ok(
+            Json::object()
+                .with("stacks_9000", rearrange(cargo, moves, Crane::CrateMover9000)?.stacks())
+                .with("stacks_9001", rearrange(cargo, moves, Crane::CrateMover9001)?.stacks())
+        )
     }
 }
 
-fn rearrenge_cargo_9001(contents: &str) -> String {
-    let (mut cargo, moves) = process_input(contents);
-    execute_moves_9001(&mut cargo, &moves);
-    cargo.0.iter()
-        .map(|v| v.last().unwrap().0.to_string())
-        .fold(String::from(""), |sum, c| sum + c.as_str())
+fn process_move(line_index: usize, line: &str) -> Result<Move> {
+    let l = Vec::from_iter(line.split_whitespace());
+    let m: &[&str] = l.as_slice();
+    if let ["move", n, "from", s, "to", d] = m {
+        Ok(
+            Move {
+                count: common::parse_field(n, line_index, line)?,
+                from: common::parse_field(s, line_index, line)?,
+                to: common::parse_field(d, line_index, line)?,
+                source: Some((line_index, line.to_string()))
+            }
+        )
+    } else {
+        Err(Error::parse(line_index, line, "Malformed move"))
+    }
 }
 
-pub fn organize_cargo_9001(input_path: &str) -> String {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => rearrenge_cargo_9001(&content),
-        Err(er) => {
-            println!("{}", er);
-            String::new()
-        }
+fn process_input(input: &str) -> Result<(Cargo, Vec<Move>)> {
+    let lines: Vec<&str> = input.split_terminator("\n").collect();
+    let drawing_height = lines.iter()
+        .position(|line| line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let cargo = Cargo::parse(&lines[..drawing_height].join("\n"))?;
+
+    let moves: Vec<Move> = lines.iter()
+        .enumerate()
+        .skip(drawing_height + 1)
+        .map(|(i, line)| process_move(i, line))
+        .collect::<Result<_>>()?;
+    // Crate counts depend on the moves before, only the stacks can be checked up front
+    for mov in &moves {
+        cargo.check_stacks(mov)?;
     }
+    Ok((cargo, moves))
+}
+
+/// The cargo after every move, made by `crane`
+pub fn rearrange(cargo: &Cargo, moves: &[Move], crane: Crane) -> Result<Cargo> {
+    let mut cargo = cargo.clone();
+    cargo.execute(moves, crane)?;
+    Ok(cargo)
+}
+
+pub fn organize_cargo(input_path: &str) -> Result<String> {
+    Day5::part1(&Day5::parse_file(input_path)?)
+}
+
+pub fn organize_cargo_9001(input_path: &str) -> Result<String> {
+    Day5::part2(&Day5::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -133,11 +358,175 @@ move 1 from 1 to 2"#;
 
     #[test]
     fn test_input() {
-        assert_eq!(rearrenge_cargo(TEST_INP), "CMZ")
+        assert_eq!(Day5::solve_part1(TEST_INP).unwrap(), "CMZ")
     }
 
     #[test]
     fn test_input_part2() {
-        assert_eq!(rearrenge_cargo_9001(TEST_INP), "MCD")
+        assert_eq!(Day5::solve_part2(TEST_INP).unwrap(), "MCD")
+    }
+
+    #[test]
+    fn test_input_reader() {
+        let cargo = Day5::parse_reader(std::io::BufReader::new(TEST_INP.as_bytes())).unwrap();
+        assert_eq!(Day5::part1(&cargo).unwrap(), "CMZ");
+        assert_eq!(Day5::part2(&cargo).unwrap(), "MCD")
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Day5::report(&Day5::parse(TEST_INP).unwrap());
+        assert_eq!(
+            report.to_string(),
+            concat!(
+                r#"{"summary":{"stacks":3,"crates":6,"moves":4},"#,
+                r#""parts":[{"part":1,"answer":"CMZ"},{"part":2,"answer":"MCD"}],"#,
+                r#""artefacts":{"stacks_9000":["C","M","PDNZ"],"stacks_9001":["M","C","PZND"]}}"#
+            )
+        )
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_malformed_move() {
+        let input = TEST_INP.replace("move 3 from 1 to 3", "move 3 from one to 3");
+        assert!(matches!(Day5::parse(&input), Err(Error::Parse { line: 7, column: 13, .. })))
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let input = TEST_INP.replace("move 2 from 2 to 1", "move 2 from 4 to 1");
+        assert!(matches!(Day5::parse(&input), Err(Error::Parse { line: 8, .. })));
+        let input = TEST_INP.replace("move 2 from 2 to 1", "move 3 from 2 to 1");
+        let res = Day5::solve_part1(&input);
+        assert!(matches!(res, Err(Error::Parse { line: 8, ref content, .. }) if content == "move 3 from 2 to 1"))
+    }
+
+    #[test]
+    fn test_invalid_move_quoted() {
+        let line = "move  1 from 0 to 1 ";
+        let input = TEST_INP.replace("move 1 from 1 to 2", line);
+        assert!(matches!(Day5::parse(&input), Err(Error::Parse { line: 9, ref content, .. }) if content == line));
+        let mut cargo = Day5::parse(TEST_INP).unwrap().0;
+        let mov = Move { count: 4, from: 1, to: 2, source: Some((3, String::from("take 4 from the first"))) };
+        let res = cargo.apply(mov, Crane::CrateMover9000);
+        assert!(matches!(res, Err(Error::Parse { line: 4, ref content, .. }) if content == "take 4 from the first"));
+        let res = cargo.apply(Move { count: 4, from: 1, to: 2, source: None }, Crane::CrateMover9000);
+        assert!(matches!(res, Err(Error::InvalidState(ref reason)) if reason == "Stack 1 only has 2 crates for move 4 from 1 to 2"))
+    }
+
+    #[test]
+    fn test_limited_crane() {
+        let (cargo, moves) = Day5::parse(TEST_INP).unwrap();
+        assert_eq!(
+            rearrange(&cargo, &moves, Crane::Limited(1)).unwrap().stacks(),
+            rearrange(&cargo, &moves, Crane::CrateMover9000).unwrap().stacks()
+        );
+        assert_eq!(rearrange(&cargo, &moves, Crane::Limited(3)).unwrap().stacks(), ["M", "C", "PZND"]);
+        // Two lifts for the move of three, N and D on top first then Z onto them
+        assert_eq!(rearrange(&cargo, &moves, Crane::Limited(2)).unwrap().stacks(), ["M", "C", "PNDZ"]);
+        assert!(rearrange(&cargo, &moves, Crane::Limited(0)).is_err())
+    }
+
+    #[test]
+    fn test_history() {
+        let (start, moves) = Day5::parse(TEST_INP).unwrap();
+        let mut cargo = start.clone();
+        cargo.execute(&moves, Crane::CrateMover9001).unwrap();
+        assert_eq!(cargo.history().count(), 4);
+        assert_eq!(cargo.undo().as_ref(), Some(&moves[3]));
+        assert_eq!(cargo.undo().as_ref(), Some(&moves[2]));
+        assert_eq!(cargo.stacks(), ["", "MC", "PZND"]);
+        assert_eq!(cargo.redo().unwrap().as_ref(), Some(&moves[2]));
+        assert_eq!(cargo.top_crates(), "CD");
+        while cargo.undo().is_some() {}
+        assert_eq!(cargo.stacks(), start.stacks());
+        cargo.apply(moves[0].clone(), Crane::CrateMover9000).unwrap();
+        assert_eq!(cargo.redo().unwrap(), None)
+    }
+
+    #[test]
+    fn test_drawing_round_trip() {
+        let (cargo, _) = Day5::parse(TEST_INP).unwrap();
+        assert_eq!(cargo.to_string(), TEST_INP.split("\n\n").next().unwrap());
+        let drawing =
+r#"[Zz]
+[Y] [XL]                                 [Q]
+ 1   2   3   4   5   6   7   8   9   10  11"#;
+        let cargo = Cargo::parse(drawing).unwrap();
+        assert_eq!(cargo.stacks(), ["YZz", "XL", "", "", "", "", "", "", "", "", "Q"]);
+        assert_eq!(cargo.top_crates(), "ZzXLQ");
+        assert_eq!(Cargo::parse(&cargo.to_string()).unwrap(), cargo);
+        let mut moved = cargo.clone();
+        moved.apply(Move { count: 2, from: 1, to: 11, source: None }, Crane::CrateMover9001).unwrap();
+        assert_eq!(Cargo::parse(&moved.to_string()).unwrap().stacks(), moved.stacks())
+    }
+
+    #[test]
+    fn test_malformed_drawing() {
+        let positions: Vec<_> = ["[A]\n 1  3", "[A] B\n 1   2", "    [B]\n[A]\n 1   2", "[A][B]\n 1   2"]
+            .iter()
+            .map(|drawing| match Cargo::parse(drawing) {
+                Err(Error::Parse { line, column, .. }) => (line, column),
+                _ => (0, 0)
+            })
+            .collect();
+        assert_eq!(positions, [(2, 5), (1, 5), (1, 5), (1, 1)])
+    }
+
+    fn replay(cargo: &Cargo, plan: &Plan, crane: Crane) -> Cargo {
+        let Plan::Moves(moves) = plan else {
+            panic!("No plan: {:?}", plan)
+        };
+        rearrange(cargo, moves, crane).unwrap()
+    }
+
+    #[test]
+    fn test_plan_shortest() {
+        let (cargo, moves) = Day5::parse(TEST_INP).unwrap();
+        let target = Target::Arrangement(rearrange(&cargo, &moves, Crane::CrateMover9001).unwrap());
+        let planned = plan(&cargo, &target, Crane::CrateMover9001).unwrap();
+        assert_eq!(replay(&cargo, &planned, Crane::CrateMover9001).stacks(), ["M", "C", "PZND"]);
+        assert!(matches!(planned, Plan::Moves(ref moves) if moves.len() <= 4));
+        let planned = plan(&cargo, &Target::Tops(String::from("ZDN")), Crane::CrateMover9000).unwrap();
+        assert_eq!(planned, Plan::Moves(vec![Move { count: 1, from: 1, to: 3, source: None }]));
+        assert_eq!(plan(&cargo, &Target::Tops(String::from("NDP")), Crane::CrateMover9000).unwrap(), Plan::Moves(vec![]))
+    }
+
+    #[test]
+    fn test_plan_unreachable() {
+        let (cargo, _) = Day5::parse(TEST_INP).unwrap();
+        let other = Cargo::parse("[A]\n 1   2   3").unwrap();
+        assert!(matches!(plan(&cargo, &Target::Arrangement(other), Crane::CrateMover9000), Ok(Plan::Unreachable(_))));
+        // Single crates between two stacks keep them in the same order around the stacks
+        let two = Cargo::parse("[B]\n[A] [C]\n 1   2").unwrap();
+        let swapped = Cargo::parse("[A]\n[B] [C]\n 1   2").unwrap();
+        assert!(matches!(plan(&two, &Target::Arrangement(swapped.clone()), Crane::CrateMover9000), Ok(Plan::Unreachable(_))));
+        assert!(matches!(plan(&two, &Target::Tops(String::from("CC")), Crane::CrateMover9001), Ok(Plan::Unreachable(_))));
+        let planned = plan(&two, &Target::Arrangement(swapped), Crane::CrateMover9001).unwrap();
+        assert_eq!(replay(&two, &planned, Crane::CrateMover9001).stacks(), ["BA", "C"])
+    }
+
+    #[test]
+    fn test_plan_built() {
+        let row = |level: usize| (0..4).map(|stack| format!("[{}{}]", stack, level)).collect::<Vec<_>>().join(" ");
+        let drawing = |levels: Vec<usize>| {
+            levels.into_iter()
+                .map(row)
+                .chain([String::from(" 1    2    3    4")])
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let cargo = Cargo::parse(&drawing((0..7).rev().collect())).unwrap();
+        // Every stack upside down, too many crates for a search
+        let goal = Cargo::parse(&drawing((0..7).collect())).unwrap();
+        let planned = plan(&cargo, &Target::Arrangement(goal.clone()), Crane::Limited(2)).unwrap();
+        assert_eq!(replay(&cargo, &planned, Crane::Limited(2)).stacks(), goal.stacks());
+        // Tops can't be built, the bottom crate takes more moves to dig out than there's room to search
+        let res = plan(&cargo, &Target::Tops(String::from("00162636")), Crane::Limited(2));
+        assert!(matches!(res, Err(Error::InvalidState(ref reason)) if reason.starts_with("Search limit")));
+        // Checked against the crates before searching
+        for tops in ["Z9Z9Z9Z9", "0616263606", "0606", "061626360"] {
+            assert!(matches!(plan(&cargo, &Target::Tops(String::from(tops)), Crane::Limited(2)), Ok(Plan::Unreachable(_))))
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::{HashMap, VecDeque};
+
+use common::{Error, Result};
+
+use crate::{move_crates, Cargo, Crane, Crate, Move};
+
+/// Arrangements searched for a shortest plan before falling back to building one
+pub const SEARCH_LIMIT: usize = 20_000;
+
+/// What the stacks should look like once the plan is carried out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// The crates on top of the stacks, as `Cargo::top_crates` gives them
+    Tops(String),
+    /// Every stack as in the given cargo, its history aside
+    Arrangement(Cargo)
+}
+
+impl Target {
+    fn reached(&self, stacks: &[Vec<Crate>]) -> bool {
+        match self {
+            Target::Tops(tops) => {
+                let reached: String = stacks.iter()
+                    .filter_map(|stack| stack.last())
+                    .map(|c| c.0.as_str())
+                    .collect();
+                reached == *tops
+            },
+            Target::Arrangement(cargo) => cargo.stacks == stacks
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plan {
+    /// Moves to make in turn
+    Moves(Vec<Move>),
+    /// Why no moves reach the target
+    Unreachable(String)
+}
+
+fn sorted_crates(stacks: &[Vec<Crate>]) -> Vec<&Crate> {
+    let mut crates: Vec<&Crate> = stacks.iter().flatten().collect();
+    crates.sort_by(|a, b| a.0.cmp(&b.0));
+    crates
+}
+
+/// Whether `tops` reads as the labels of at most `stacks` crates, `left` counting the crates of each label
+/// not used yet
+fn readable_tops<'a>(tops: &str, left: &mut HashMap<&'a str, usize>, stacks: usize) -> bool {
+    if tops.is_empty() {
+        return true;
+    }
+    let labels: Vec<&'a str> = left.iter()
+        .filter(|(label, count)| **count > 0 && !label.is_empty() && tops.starts_with(**label))
+        .map(|(label, _)| *label)
+        .collect();
+    stacks > 0 && labels.into_iter().any(
+        |label| {
+            *left.get_mut(label).unwrap() -= 1;
+            let readable = readable_tops(&tops[label.len()..], left, stacks - 1);
+            *left.get_mut(label).unwrap() += 1;
+            readable
+        }
+    )
+}
+
+/// Moves leading from `cargo` to `target` with `crane`
+///
+/// Plans as short as possible are searched first, every move of any count between any two stacks. A
+/// target out of reach is proven so by searching every arrangement the crates can be put in, by a
+/// count of the crates, or by tops that aren't the labels of crates of the cargo, one per stack at most. If there are too many arrangements to search, an arrangement over three stacks
+/// or more is still built one crate at a time, and any other target fails with a search limit error, as
+/// it may still be reachable.
+pub fn plan(cargo: &Cargo, target: &Target, crane: Crane) -> Result<Plan> {
+    if crane.capacity() == 0 {
+        return Err(Error::invalid_state("A crane has to lift at least one crate"));
+    }
+    match target {
+        Target::Arrangement(goal) => {
+            if goal.stacks.len() != cargo.stacks.len() {
+                return Ok(Plan::Unreachable(format!("{} stacks can't become {}", cargo.stacks.len(), goal.stacks.len())));
+            }
+            if sorted_crates(&goal.stacks) != sorted_crates(&cargo.stacks) {
+                return Ok(Plan::Unreachable(String::from("The target doesn't have the same crates")));
+            }
+        },
+        Target::Tops(tops) => {
+            let mut left = HashMap::new();
+            for c in cargo.stacks.iter().flatten() {
+                *left.entry(c.0.as_str()).or_insert(0) += 1;
+            }
+            if !readable_tops(tops, &mut left, cargo.stacks.len()) {
+                return Ok(
+                    Plan::Unreachable(format!("{} isn't the tops of {} stacks of the cargo's crates", tops, cargo.stacks.len()))
+                );
+            }
+        }
+    }
+    match search(&cargo.stacks, target, crane) {
+        Some(plan) => Ok(plan),
+        None => match target {
+            Target::Arrangement(goal) if cargo.stacks.len() >= 3 => Ok(Plan::Moves(build(&cargo.stacks, &goal.stacks))),
+            _ => Err(Error::invalid_state(format!("Search limit of {} arrangements exceeded before finding a plan", SEARCH_LIMIT)))
+        }
+    }
+}
+
+/// Breadth first search for a shortest plan, `None` when there are too many arrangements
+fn search(start: &[Vec<Crate>], target: &Target, crane: Crane) -> Option<Plan> {
+    // Every arrangement seen, with the one it was reached from and the move made
+    let mut seen: Vec<(Vec<Vec<Crate>>, usize, Move)> = vec![(start.to_vec(), 0, Move { count: 0, from: 1, to: 1, source: None })];
+    let mut index: HashMap<Vec<Vec<Crate>>, usize> = HashMap::from([(start.to_vec(), 0)]);
+    let mut queue = VecDeque::from([0]);
+    while let Some(current) = queue.pop_front() {
+        if target.reached(&seen[current].0) {
+            let mut moves = vec![];
+            let mut at = current;
+            while at != 0 {
+                moves.push(seen[at].2.clone());
+                at = seen[at].1;
+            }
+            moves.reverse();
+            return Some(Plan::Moves(moves));
+        }
+        let stacks = &seen[current].0;
+        let mut next = vec![];
+        for from in 0..stacks.len() {
+            for to in (0..stacks.len()).filter(|to| *to != from) {
+                for count in 1..=stacks[from].len() {
+                    let mov = Move { count, from: from + 1, to: to + 1, source: None };
+                    let mut stacks = stacks.clone();
+                    move_crates(&mut stacks, &mov, crane.capacity());
+                    next.push((stacks, mov));
+                }
+            }
+        }
+        for (stacks, mov) in next {
+            if !index.contains_key(&stacks) {
+                if seen.len() == SEARCH_LIMIT {
+                    return None;
+                }
+                index.insert(stacks.clone(), seen.len());
+                queue.push_back(seen.len());
+                seen.push((stacks, current, mov));
+            }
+        }
+    }
+    Some(Plan::Unreachable(format!("None of the {} arrangements the crates can be put in is the target", seen.len())))
+}
+
+/// Builds the target stack after stack with single crate moves, which every crane makes the same way
+///
+/// The crates of a stack being built are dug out of the others, the crates above them going on
+/// yet another stack. Stacks already built only ever get crates on top of them, so three stacks
+/// are needed. Expects the target to have the same crates.
+fn build(start: &[Vec<Crate>], goal: &[Vec<Crate>]) -> Vec<Move> {
+    let mut stacks = start.to_vec();
+    let n = stacks.len();
+    let mut floors = vec![0; n];
+    let mut moves = vec![];
+    let mut make = |stacks: &mut Vec<Vec<Crate>>, from: usize, to: usize| {
+        let mov = Move { count: 1, from: from + 1, to: to + 1, source: None };
+        move_crates(stacks, &mov, 1);
+        moves.push(mov);
+    };
+    for x in 0..n {
+        // What's already in place at the bottom of the stack stays
+        let kept = stacks[x].iter()
+            .zip(&goal[x])
+            .take_while(|(a, b)| a == b)
+            .count();
+        while stacks[x].len() > kept {
+            make(&mut stacks, x, (x + 1) % n);
+        }
+        for c in &goal[x][kept..] {
+            // The crate with the fewest crates above it, below the floors are only crates in place
+            let (source, depth) = (0..n)
+                .filter(|s| *s != x)
+                .filter_map(
+                    |s| {
+                        let loose = &stacks[s][floors[s]..];
+                        loose.iter().rposition(|d| d == c).map(|p| (s, loose.len() - p - 1))
+                    }
+                )
+                .min_by_key(|(_, depth)| *depth)
+                .unwrap();
+            let spare = (0..n).find(|s| *s != x && *s != source).unwrap();
+            for _ in 0..depth {
+                make(&mut stacks, source, spare);
+            }
+            make(&mut stacks, source, x);
+        }
+        floors[x] = goal[x].len();
+    }
+    moves
+}
@@ -0,0 +1,40 @@
+use common::{Generated, Rng};
+
+const PACKET_MARKER: usize = 4;
+const MESSAGE_MARKER: usize = 14;
+
+/// Transmission of `size` characters from a narrow alphabet, ending with the whole alphabet so both
+/// markers exist, the answers are found by checking every window
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let alphabet = rng.between(2, 16) as u8;
+    let mut transmission: Vec<u8> = (0..size)
+        .map(|_| b'a' + rng.below(alphabet as usize) as u8)
+        .collect();
+    let mut tail: Vec<u8> = (b'a'..=b'z').collect();
+    rng.shuffle(&mut tail);
+    transmission.extend(tail);
+
+    let marker = |length: usize| {
+        transmission.windows(length)
+            .position(
+                |window| window.iter().enumerate().all(|(i, c)| !window[..i].contains(c))
+            )
+            .map(|start| start + length)
+            .unwrap()
+    };
+    let (packet, message) = (marker(PACKET_MARKER), marker(MESSAGE_MARKER));
+    Generated::solved(String::from_utf8(transmission).unwrap(), packet, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day6;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..50 {
+            generate(&mut Rng::new(seed), seed as usize * 10).check::<Day6>().unwrap();
+        }
+    }
+}
@@ -1,38 +1,71 @@
-fn find_marker(transmission: &str, window_size: usize) -> usize {
-    let mut rolling_window = vec!['\0'; window_size];
-    for (i, c) in transmission.chars().enumerate() {
-        rolling_window.pop();
-        rolling_window.insert(0, c);
-        let set = std::collections::BTreeSet::from_iter(rolling_window.iter());
-        if set.len() == rolling_window.len() && i >= window_size{
-            return i + 1;
-        }
-    }
-    0
-}
+pub mod generator;
+
+mod scanner;
+
+use common::{Error, Json, Puzzle, Result};
+
+pub use scanner::{first_markers, scan, Marker, MarkerScanner, MarkerSpec, MarkerWindow};
+
+const PACKET_MARKER: usize = 4;
+const MESSAGE_MARKER: usize = 14;
+
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-pub fn decode_packet(input_path: &str) -> usize {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => find_marker(&content, 4),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.trim_end().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        decode(input, PACKET_MARKER)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        decode(input, MESSAGE_MARKER)
+    }
+
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("length", input.chars().count())
     }
-}
 
-pub fn decode_message(input_path: &str) -> usize {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => find_marker(&content, 14),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
+    /// The window of distinct characters found for each marker
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        let specs = [MarkerSpec::distinct(PACKET_MARKER), MarkerSpec::distinct(MESSAGE_MARKER)];
+        let windows: Vec<Option<String>> = first_markers(input.as_bytes(), &specs)?
+            .into_iter()
+            .map(|found| found.map(|(_, contents)| String::from_utf8_lossy(&contents).into_owned()))
+            .collect();
+        Ok(
+            Json::object()
+                .with("packet_marker", windows[0].clone())
+                .with("message_marker", windows[1].clone())
+        )
     }
 }
 
+/// End of the first window of `window_size` distinct bytes, `None` without one or for an empty window
+pub fn find_marker(transmission: &str, window_size: usize) -> Option<usize> {
+    let [marker] = first_markers(transmission.as_bytes(), &[MarkerSpec::distinct(window_size)]).ok()?.try_into().ok()?;
+    marker.map(|(marker, _)| marker.end)
+}
+
+fn decode(transmission: &str, window_size: usize) -> Result<usize> {
+    find_marker(transmission, window_size)
+        .ok_or_else(|| Error::invalid_state(format!("No marker of {} distinct characters", window_size)))
+}
+
+pub fn decode_packet(input_path: &str) -> Result<usize> {
+    Day6::part1(&Day6::parse_file(input_path)?)
+}
+
+pub fn decode_message(input_path: &str) -> Result<usize> {
+    Day6::part2(&Day6::parse_file(input_path)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,51 +78,62 @@ mod tests {
 
     #[test]
     fn test_input1() {
-        assert_eq!(find_marker(TEST_INP1, 4), 7)
+        assert_eq!(find_marker(TEST_INP1, 4), Some(7))
     }
 
     #[test]
     fn test_input2() {
-        assert_eq!(find_marker(TEST_INP2, 4), 5)
+        assert_eq!(find_marker(TEST_INP2, 4), Some(5))
     }
 
     #[test]
     fn test_input3() {
-        assert_eq!(find_marker(TEST_INP3, 4), 6)
+        assert_eq!(find_marker(TEST_INP3, 4), Some(6))
     }
 
     #[test]
     fn test_input4() {
-        assert_eq!(find_marker(TEST_INP4, 4), 10)
+        assert_eq!(find_marker(TEST_INP4, 4), Some(10))
     }
 
     #[test]
     fn test_input5() {
-        assert_eq!(find_marker(TEST_INP5, 4), 11)
+        assert_eq!(find_marker(TEST_INP5, 4), Some(11))
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(find_marker(TEST_INP1, 14), 19)
+        assert_eq!(find_marker(TEST_INP1, 14), Some(19))
     }
 
     #[test]
     fn test_input2_part2() {
-        assert_eq!(find_marker(TEST_INP2, 14), 23)
+        assert_eq!(find_marker(TEST_INP2, 14), Some(23))
     }
 
     #[test]
     fn test_input3_part2() {
-        assert_eq!(find_marker(TEST_INP3, 14), 23)
+        assert_eq!(find_marker(TEST_INP3, 14), Some(23))
     }
 
     #[test]
     fn test_input4_part2() {
-        assert_eq!(find_marker(TEST_INP4, 14), 29)
+        assert_eq!(find_marker(TEST_INP4, 14), Some(29))
     }
 
     #[test]
     fn test_input5_part2() {
-        assert_eq!(find_marker(TEST_INP5, 14), 26)
+        assert_eq!(find_marker(TEST_INP5, 14), Some(26))
+    }
+
+    #[test]
+    fn test_no_marker() {
+        assert_eq!(find_marker("abcabcabcabc", 4), None)
+    }
+
+    #[test]
+    fn test_marker_in_first_window() {
+        assert_eq!(find_marker("abcdabcd", 4), Some(4));
+        assert_eq!(find_marker("abcd", 4), Some(4))
     }
 }
\ No newline at end of file
@@ -0,0 +1,329 @@
+use std::io::{ErrorKind, Read};
+
+use common::{Error, Result};
+
+/// What a window of the stream has to hold to be a marker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkerSpec {
+    kind: Kind,
+    /// Bytes a marker can be made of, any byte when `None`
+    alphabet: Option<Vec<u8>>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    /// At least `distinct` different bytes among the last `window`
+    Distinct { window: usize, distinct: usize },
+    /// Exactly these bytes, in this order
+    Preamble(Vec<u8>)
+}
+
+impl MarkerSpec {
+    /// A window of `window` bytes all different, the puzzle's markers
+    pub fn distinct(window: usize) -> Self {
+        MarkerSpec::at_least(window, window)
+    }
+
+    /// A window of `window` bytes with at least `distinct` different ones
+    pub fn at_least(window: usize, distinct: usize) -> Self {
+        MarkerSpec { kind: Kind::Distinct { window, distinct }, alphabet: None }
+    }
+
+    pub fn preamble(pattern: &[u8]) -> Self {
+        MarkerSpec { kind: Kind::Preamble(pattern.to_vec()), alphabet: None }
+    }
+
+    /// Only windows made of bytes of `alphabet` are markers
+    pub fn within(self, alphabet: &[u8]) -> Self {
+        MarkerSpec { alphabet: Some(alphabet.to_vec()), ..self }
+    }
+
+    pub fn window(&self) -> usize {
+        match &self.kind {
+            Kind::Distinct { window, .. } => *window,
+            Kind::Preamble(pattern) => pattern.len()
+        }
+    }
+
+    fn check(&self) -> Result<()> {
+        match &self.kind {
+            _ if self.window() == 0 => Err(Error::invalid_state("Markers need a window of at least one byte")),
+            Kind::Distinct { window, distinct } if distinct > window => {
+                Err(Error::invalid_state(format!("A window of {} bytes can't have {} different ones", window, distinct)))
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+/// Bytes `start..end` of the stream, counted from 0, making a marker of the spec at index `spec`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marker {
+    pub spec: usize,
+    pub start: usize,
+    pub end: usize
+}
+
+/// A marker with the bytes of its window
+pub type MarkerWindow = (Marker, Vec<u8>);
+
+/// Where a spec stands after the bytes scanned so far
+#[derive(Debug, Clone)]
+struct Tracker {
+    spec: MarkerSpec,
+    counts: [u32; 256],
+    distinct: usize,
+    /// Bytes in the window that aren't in the alphabet
+    foreign: usize,
+    allowed: [bool; 256],
+    /// Length of the longest prefix of the preamble the stream ends with
+    matched: usize,
+    /// For each prefix of the preamble, the length of its longest proper prefix that's also its suffix
+    fallback: Vec<usize>
+}
+
+impl Tracker {
+    fn new(spec: &MarkerSpec) -> Self {
+        let mut allowed = [spec.alphabet.is_none(); 256];
+        for byte in spec.alphabet.iter().flatten() {
+            allowed[*byte as usize] = true;
+        }
+        let mut fallback = vec![0];
+        if let Kind::Preamble(pattern) = &spec.kind {
+            let mut matched = 0;
+            for byte in &pattern[1..] {
+                while matched > 0 && pattern[matched] != *byte {
+                    matched = fallback[matched - 1];
+                }
+                if pattern[matched] == *byte {
+                    matched += 1;
+                }
+                fallback.push(matched);
+            }
+        }
+        Tracker { spec: spec.clone(), counts: [0; 256], distinct: 0, foreign: 0, allowed, matched: 0, fallback }
+    }
+
+    fn leave(&mut self, byte: u8) {
+        self.counts[byte as usize] -= 1;
+        if self.counts[byte as usize] == 0 {
+            self.distinct -= 1;
+        }
+        if !self.allowed[byte as usize] {
+            self.foreign -= 1;
+        }
+    }
+
+    /// Takes the byte into the window, true if the window is now a marker
+    fn enter(&mut self, byte: u8, scanned: usize) -> bool {
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        if !self.allowed[byte as usize] {
+            self.foreign += 1;
+        }
+        let complete = scanned >= self.spec.window() && self.foreign == 0;
+        match &self.spec.kind {
+            Kind::Distinct { distinct, .. } => complete && self.distinct >= *distinct,
+            Kind::Preamble(pattern) => {
+                if self.matched == pattern.len() {
+                    self.matched = self.fallback[self.matched - 1];
+                }
+                while self.matched > 0 && pattern[self.matched] != byte {
+                    self.matched = self.fallback[self.matched - 1];
+                }
+                if pattern[self.matched] == byte {
+                    self.matched += 1;
+                }
+                complete && self.matched == pattern.len()
+            }
+        }
+    }
+}
+
+/// Finds markers of several specs in a single pass, a constant amount of work per byte and spec
+///
+/// Only as many bytes as the largest window are kept, so streams of any length can be scanned.
+#[derive(Debug, Clone)]
+pub struct MarkerScanner {
+    trackers: Vec<Tracker>,
+    /// Last bytes scanned, the one at `position` modulo the length being the oldest
+    recent: Vec<u8>,
+    position: usize
+}
+
+impl MarkerScanner {
+    pub fn new(specs: &[MarkerSpec]) -> Result<Self> {
+        if specs.is_empty() {
+            return Err(Error::invalid_state("No marker to scan for"));
+        }
+        for spec in specs {
+            spec.check()?;
+        }
+        let longest = specs.iter().map(MarkerSpec::window).max().unwrap();
+        Ok(MarkerScanner { trackers: specs.iter().map(Tracker::new).collect(), recent: vec![0; longest], position: 0 })
+    }
+
+    /// Bytes scanned so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Scans the next byte, reporting each marker it completes in the order of the specs
+    ///
+    /// The bytes of a marker's window come along in two slices, the second following the first, as
+    /// the window can wrap around the ones kept.
+    pub fn push(&mut self, byte: u8, report: &mut impl FnMut(Marker, &[u8], &[u8])) {
+        let length = self.recent.len();
+        for tracker in &mut self.trackers {
+            let window = tracker.spec.window();
+            if self.position >= window {
+                tracker.leave(self.recent[(self.position - window) % length]);
+            }
+        }
+        self.recent[self.position % length] = byte;
+        self.position += 1;
+        for (spec, tracker) in self.trackers.iter_mut().enumerate() {
+            if tracker.enter(byte, self.position) {
+                let start = self.position - tracker.spec.window();
+                let (older, newer) = held(&self.recent, start, self.position);
+                report(Marker { spec, start, end: self.position }, older, newer);
+            }
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8], mut report: impl FnMut(Marker, &[u8], &[u8])) {
+        for byte in bytes {
+            self.push(*byte, &mut report);
+        }
+    }
+}
+
+/// Bytes `start..end` of the stream out of the ring of the last ones scanned, `end` being the latest
+fn held(recent: &[u8], start: usize, end: usize) -> (&[u8], &[u8]) {
+    let first = start % recent.len();
+    if first + end - start <= recent.len() {
+        (&recent[first..first + end - start], &[])
+    } else {
+        (&recent[first..], &recent[..end % recent.len()])
+    }
+}
+
+/// Scans the whole stream, reporting every marker as it's found with the bytes of its window, and
+/// returns the bytes scanned
+pub fn scan(
+    mut reader: impl Read,
+    specs: &[MarkerSpec],
+    mut report: impl FnMut(Marker, &[u8], &[u8])
+) -> Result<usize> {
+    let mut scanner = MarkerScanner::new(specs)?;
+    let mut buffer = [0; 1 << 16];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(scanner.position()),
+            Ok(read) => scanner.feed(&buffer[..read], &mut report),
+            Err(er) if er.kind() == ErrorKind::Interrupted => (),
+            Err(er) => return Err(Error::Io(er))
+        }
+    }
+}
+
+/// First marker of each spec with the bytes of its window
+///
+/// Scanning stops at the byte ending the last of them, but the stream is read 64 KiB at a time so
+/// up to that many bytes past it can be taken from the reader.
+pub fn first_markers(mut reader: impl Read, specs: &[MarkerSpec]) -> Result<Vec<Option<MarkerWindow>>> {
+    let mut scanner = MarkerScanner::new(specs)?;
+    let mut found = vec![None; specs.len()];
+    let mut buffer = [0; 1 << 16];
+    while found.contains(&None) {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(er) if er.kind() == ErrorKind::Interrupted => continue,
+            Err(er) => return Err(Error::Io(er))
+        };
+        for byte in &buffer[..read] {
+            if !found.contains(&None) {
+                break;
+            }
+            scanner.push(
+                *byte,
+                &mut |marker: Marker, older: &[u8], newer: &[u8]| {
+                    if found[marker.spec].is_none() {
+                        found[marker.spec] = Some((marker, [older, newer].concat()));
+                    }
+                }
+            );
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ends(stream: &str, spec: MarkerSpec) -> Vec<usize> {
+        let mut ends = vec![];
+        scan(stream.as_bytes(), &[spec], |marker, _, _| ends.push(marker.end)).unwrap();
+        ends
+    }
+
+    #[test]
+    fn every_marker() {
+        let mut markers = vec![];
+        let specs = [MarkerSpec::distinct(3), MarkerSpec::distinct(4)];
+        let read = scan("abcabd".as_bytes(), &specs, |marker, _, _| markers.push(marker)).unwrap();
+        assert_eq!(read, 6);
+        let ends: Vec<_> = markers.iter().map(|marker| (marker.spec, marker.end)).collect();
+        assert_eq!(ends, [(0, 3), (0, 4), (0, 5), (0, 6), (1, 6)]);
+        assert_eq!(markers[4], Marker { spec: 1, start: 2, end: 6 });
+        assert!(MarkerScanner::new(&[MarkerSpec::distinct(4), MarkerSpec::distinct(0)]).is_err());
+        assert!(MarkerScanner::new(&[MarkerSpec::at_least(2, 3)]).is_err())
+    }
+
+    #[test]
+    fn windows_across_the_kept_bytes() {
+        let mut windows = vec![];
+        let specs = [MarkerSpec::distinct(2), MarkerSpec::distinct(4)];
+        scan(
+            "abcabd".as_bytes(),
+            &specs,
+            |marker, older, newer| windows.push((marker.spec, String::from_utf8([older, newer].concat()).unwrap()))
+        ).unwrap();
+        let expected = [(0, "ab"), (0, "bc"), (0, "ca"), (0, "ab"), (0, "bd"), (1, "cabd")];
+        assert_eq!(windows, expected.map(|(spec, window)| (spec, String::from(window))))
+    }
+
+    #[test]
+    fn other_specs() {
+        assert_eq!(ends("aabbab", MarkerSpec::at_least(3, 2)), [3, 4, 5, 6]);
+        assert_eq!(ends("ab1cd2ef", MarkerSpec::distinct(2).within(b"abcdef")), [2, 5, 8]);
+        assert_eq!(ends("xxaabaabaab", MarkerSpec::preamble(b"aabaab")), [8, 11]);
+        assert_eq!(ends("ababa", MarkerSpec::preamble(b"ab").within(b"a")), [] as [usize; 0])
+    }
+
+    #[test]
+    fn first_markers_stop_early() {
+        let stream = "mjqjpqmgbljsphdztnvjfqwrcgsmlb".repeat(1000);
+        let specs = [MarkerSpec::distinct(4), MarkerSpec::distinct(14), MarkerSpec::distinct(27)];
+        let ends: Vec<_> = first_markers(stream.as_bytes(), &specs).unwrap()
+            .into_iter()
+            .map(|found| found.map(|(marker, contents)| (marker.end, String::from_utf8(contents).unwrap())))
+            .collect();
+        assert_eq!(ends, [Some((7, String::from("jpqm"))), Some((19, String::from("qmgbljsphdztnv"))), None])
+    }
+
+    #[test]
+    fn first_markers_read_a_buffer_at_a_time() {
+        let specs = [MarkerSpec::distinct(2), MarkerSpec::preamble(b"ab")];
+        let bytes = "ab".repeat(50_000);
+        let mut stream = bytes.as_bytes();
+        let found = first_markers(&mut stream, &specs).unwrap();
+        let ends: Vec<_> = found.iter().map(|found| found.as_ref().map(|(marker, _)| marker.end)).collect();
+        assert_eq!(ends, [Some(2), Some(2)]);
+        assert_eq!(stream.len(), 100_000 - (1 << 16))
+    }
+}
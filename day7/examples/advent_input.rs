@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day7::process_console("./day7/resources/input.txt", 100000);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
 fn main() {
     let res = advent_of_code_2022_day7::select_directory_for_deletion("./day7/resources/input.txt", 70000000, 30000000);
-    println!("{:?}", res)
+    match res {
+        Ok(res) => println!("{:?}", res),
+        Err(er) => println!("{}", er)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,436 @@
+use std::fmt::Display;
+
+use common::{Error, Result};
+
+/// Index of the root directory, the only node without a parent of its own
+pub const ROOT: usize = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Directory { name: String, parent: usize, children: Vec<usize> },
+    File { name: String, parent: usize, size: usize }
+}
+
+impl Node {
+    pub fn name(&self) -> &str {
+        match self {
+            Node::Directory { name, .. } | Node::File { name, .. } => name
+        }
+    }
+
+    /// The directory holding the node, the root being its own parent
+    pub fn parent(&self) -> usize {
+        match self {
+            Node::Directory { parent, .. } | Node::File { parent, .. } => *parent
+        }
+    }
+
+    pub fn is_directory(&self) -> bool {
+        matches!(self, Node::Directory { .. })
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Directory { name, .. } => write!(f, "{}", name),
+            Node::File { name, size, .. } => write!(f, "{} {}", size, name)
+        }
+    }
+}
+
+/// A node met walking a directory, with its recursive size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Walked {
+    pub id: usize,
+    /// Steps down from the node walked, 0 for that node itself
+    pub depth: usize,
+    pub size: usize
+}
+
+/// Directories and files indexed by the order they were created in
+///
+/// Removed nodes leave their index empty, so the indices of the others never change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Option<Node>>
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
+
+/// Path of the parent directory and name of the last component
+fn split_last(path: &str) -> Result<(&str, &str)> {
+    let (parent, name) = match path.trim_end_matches('/').rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => ("", path.trim_end_matches('/'))
+    };
+    match name {
+        "" | "." | ".." => Err(Error::invalid_state(format!("'{}' doesn't name a node", path))),
+        _ => Ok((parent, name))
+    }
+}
+
+impl FileSystem {
+    /// Just the root directory
+    pub fn new() -> Self {
+        FileSystem { nodes: vec![Some(Node::Directory { name: String::from("/"), parent: ROOT, children: vec![] })] }
+    }
+
+    pub fn node(&self, id: usize) -> Option<&Node> {
+        self.nodes.get(id).and_then(Option::as_ref)
+    }
+
+    /// Indices of the nodes still there
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodes.iter()
+            .enumerate()
+            .filter(|(_, node)| node.is_some())
+            .map(|(id, _)| id)
+    }
+
+    /// Contents of a directory, in the order they were added
+    pub fn children(&self, id: usize) -> Result<&[usize]> {
+        match self.node(id) {
+            Some(Node::Directory { children, .. }) => Ok(children),
+            Some(node) => Err(Error::invalid_state(format!("'{}' isn't a directory", node.name()))),
+            None => Err(Error::invalid_state(format!("No node {}", id)))
+        }
+    }
+
+    /// Node named `name` in the directory `id`
+    pub fn child(&self, id: usize, name: &str) -> Option<usize> {
+        self.children(id).ok()?
+            .iter()
+            .find(|child| self.nodes[**child].as_ref().is_some_and(|node| node.name() == name))
+            .copied()
+    }
+
+    /// Node at `path`, absolute when it starts with `/` and otherwise relative to the directory `from`
+    ///
+    /// `.` stays in the directory and `..` goes up to its parent, the root's parent being itself. Only
+    /// directories can be stepped through, so a file must be the last component.
+    pub fn resolve(&self, from: usize, path: &str) -> Result<usize> {
+        let mut current = if path.starts_with('/') { ROOT } else { from };
+        for component in path.split('/') {
+            self.children(current)?;
+            current = match component {
+                "" | "." => current,
+                ".." => self.nodes[current].as_ref().unwrap().parent(),
+                name => {
+                    self.child(current, name)
+                        .ok_or_else(|| Error::invalid_state(format!("No '{}' in '{}'", name, self.path(current))))?
+                }
+            };
+        }
+        Ok(current)
+    }
+
+    /// Node at the absolute `path`
+    pub fn lookup(&self, path: &str) -> Option<usize> {
+        self.resolve(ROOT, path).ok()
+    }
+
+    /// Absolute path of a node
+    pub fn path(&self, id: usize) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while current != ROOT {
+            let node = self.nodes[current].as_ref().unwrap();
+            names.push(node.name());
+            current = node.parent();
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Size of a file, or of every file under a directory
+    pub fn size(&self, id: usize) -> usize {
+        match self.node(id) {
+            Some(Node::Directory { children, .. }) => children.iter().map(|child| self.size(*child)).sum(),
+            Some(Node::File { size, .. }) => *size,
+            None => 0
+        }
+    }
+
+    /// The node `id` and everything under it, parents before their contents in the order they were added
+    pub fn walk(&self, id: usize) -> Result<Vec<Walked>> {
+        if self.node(id).is_none() {
+            return Err(Error::invalid_state(format!("No node {}", id)));
+        }
+        let mut walked = vec![];
+        self.walk_into(id, 0, &mut walked);
+        Ok(walked)
+    }
+
+    /// Walks `id` into `walked`, returning its size
+    fn walk_into(&self, id: usize, depth: usize, walked: &mut Vec<Walked>) -> usize {
+        let at = walked.len();
+        walked.push(Walked { id, depth, size: 0 });
+        let size = match self.nodes[id].as_ref().unwrap() {
+            Node::Directory { children, .. } => children.iter().map(|child| self.walk_into(*child, depth + 1, walked)).sum(),
+            Node::File { size, .. } => *size
+        };
+        walked[at].size = size;
+        size
+    }
+
+    fn add(&mut self, parent: usize, node: Node) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Some(node));
+        if let Some(Node::Directory { children, .. }) = &mut self.nodes[parent] {
+            children.push(id);
+        }
+        id
+    }
+
+    /// Directory `name` in the directory `parent`, made unless it's already there
+    pub fn mkdir(&mut self, parent: usize, name: &str) -> Result<usize> {
+        self.children(parent)?;
+        match self.child(parent, name) {
+            Some(id) if self.nodes[id].as_ref().unwrap().is_directory() => Ok(id),
+            Some(_) => Err(Error::invalid_state(format!("'{}' is a file", name))),
+            None => Ok(self.add(parent, Node::Directory { name: name.to_string(), parent, children: vec![] }))
+        }
+    }
+
+    /// Every directory along `path` from the directory `from`, made when missing, returning the last one
+    pub fn mkdir_all(&mut self, from: usize, path: &str) -> Result<usize> {
+        let mut current = if path.starts_with('/') { ROOT } else { from };
+        self.children(current)?;
+        for component in path.split('/') {
+            current = match component {
+                "" | "." => current,
+                ".." => self.nodes[current].as_ref().unwrap().parent(),
+                name => self.mkdir(current, name)?
+            };
+        }
+        Ok(current)
+    }
+
+    /// File `name` in the directory `parent`, made empty when missing, its size set when given
+    pub fn touch(&mut self, parent: usize, name: &str, size: Option<usize>) -> Result<usize> {
+        self.children(parent)?;
+        match self.child(parent, name) {
+            Some(id) => match self.nodes[id].as_mut().unwrap() {
+                Node::File { size: current, .. } => {
+                    *current = size.unwrap_or(*current);
+                    Ok(id)
+                },
+                Node::Directory { .. } => Err(Error::invalid_state(format!("'{}' is a directory", name)))
+            },
+            None => Ok(self.add(parent, Node::File { name: name.to_string(), parent, size: size.unwrap_or(0) }))
+        }
+    }
+
+    fn detach(&mut self, id: usize) {
+        let parent = self.nodes[id].as_ref().unwrap().parent();
+        if let Some(Node::Directory { children, .. }) = &mut self.nodes[parent] {
+            children.retain(|child| *child != id);
+        }
+    }
+
+    /// Removes a node and everything under it
+    pub fn remove(&mut self, id: usize) -> Result<()> {
+        if id == ROOT {
+            return Err(Error::invalid_state("The root can't be removed"));
+        }
+        let walked = self.walk(id)?;
+        self.detach(id);
+        for node in walked {
+            self.nodes[node.id] = None;
+        }
+        Ok(())
+    }
+
+    /// Moves a node into the directory `parent` under the name `name`
+    pub fn rename(&mut self, id: usize, parent: usize, name: &str) -> Result<()> {
+        if id == ROOT {
+            return Err(Error::invalid_state("The root can't be moved"));
+        }
+        if self.node(id).is_none() {
+            return Err(Error::invalid_state(format!("No node {}", id)));
+        }
+        self.children(parent)?;
+        let mut ancestor = parent;
+        while ancestor != ROOT {
+            if ancestor == id {
+                return Err(Error::invalid_state(format!("'{}' can't be moved into itself", self.path(id))));
+            }
+            ancestor = self.nodes[ancestor].as_ref().unwrap().parent();
+        }
+        match self.child(parent, name) {
+            Some(existing) if existing == id => return Ok(()),
+            Some(_) => return Err(Error::invalid_state(format!("'{}' already exists in '{}'", name, self.path(parent)))),
+            None => ()
+        }
+        self.detach(id);
+        match self.nodes[id].as_mut().unwrap() {
+            Node::Directory { name: current, parent: above, .. } | Node::File { name: current, parent: above, .. } => {
+                *current = name.to_string();
+                *above = parent;
+            }
+        }
+        if let Some(Node::Directory { children, .. }) = &mut self.nodes[parent] {
+            children.push(id);
+        }
+        Ok(())
+    }
+
+    /// Runs one shell command from the directory `current`, returning the directory it leaves the shell in
+    ///
+    /// Understands `cd` into an existing directory, `mkdir` of nested paths, `rm` of files and whole directories, `mv` into an
+    /// existing directory or to a new name, and `touch` with an optional size. `ls` is left to the caller
+    /// as its output follows on the next lines.
+    pub fn run(&mut self, current: usize, command: &[&str]) -> Result<usize> {
+        match command {
+            ["cd", path] => {
+                let id = self.resolve(current, path)?;
+                self.children(id)?;
+                Ok(id)
+            },
+            ["mkdir", paths @ ..] if !paths.is_empty() => {
+                for path in paths {
+                    self.mkdir_all(current, path)?;
+                }
+                Ok(current)
+            },
+            ["rm", paths @ ..] if !paths.is_empty() => {
+                for path in paths {
+                    let id = self.resolve(current, path)?;
+                    if id == current || self.path(current).starts_with(&format!("{}/", self.path(id))) {
+                        return Err(Error::invalid_state(format!("'{}' holds the current directory", path)));
+                    }
+                    self.remove(id)?;
+                }
+                Ok(current)
+            },
+            ["mv", from, to] => {
+                let id = self.resolve(current, from)?;
+                match self.resolve(current, to) {
+                    Ok(target) if self.nodes[target].as_ref().unwrap().is_directory() => {
+                        let name = self.nodes[id].as_ref().unwrap().name().to_string();
+                        self.rename(id, target, &name)?;
+                    },
+                    _ => {
+                        let (parent, name) = split_last(to)?;
+                        let parent = self.resolve(current, parent)?;
+                        self.rename(id, parent, name)?;
+                    }
+                }
+                Ok(current)
+            },
+            ["touch", path, size @ ..] if size.len() <= 1 => {
+                let size = match size {
+                    [size] => Some(size.parse().map_err(|_| Error::invalid_state(format!("Invalid size '{}'", size)))?),
+                    _ => None
+                };
+                let (parent, name) = split_last(path)?;
+                let parent = self.resolve(current, parent)?;
+                self.touch(parent, name, size)?;
+                Ok(current)
+            },
+            _ => Err(Error::invalid_state("Unresolved command"))
+        }
+    }
+}
+
+/// The tree under the root, one node per line, each indented one `| ` deeper than its directory
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let walked = self.walk(ROOT).unwrap();
+        for (i, node) in walked.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}|-{}", "| ".repeat(node.depth), self.nodes[node.id].as_ref().unwrap())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(commands: &[&str]) -> FileSystem {
+        let mut file_system = FileSystem::new();
+        let mut current = ROOT;
+        for command in commands {
+            current = file_system.run(current, &Vec::from_iter(command.split(' '))).unwrap();
+        }
+        file_system
+    }
+
+    #[test]
+    fn paths() {
+        let file_system = tree(&["mkdir a/b c", "cd /a/b", "touch ../f 10", "touch /c/g 5", "touch h"]);
+        let b = file_system.lookup("/a/b").unwrap();
+        assert_eq!(file_system.resolve(b, "../../c/./g").ok(), file_system.lookup("/c/g"));
+        assert_eq!(file_system.resolve(b, "/..").unwrap(), ROOT);
+        assert_eq!(file_system.path(file_system.lookup("a/f").unwrap()), "/a/f");
+        assert!(file_system.lookup("/a/f/x").is_none());
+        assert!(file_system.lookup("/a/f/..").is_none());
+        assert!(file_system.lookup("/a/f/.").is_none());
+        assert!(file_system.lookup("/x").is_none());
+        assert_eq!(file_system.size(ROOT), 15);
+        let names: Vec<_> = file_system.children(file_system.lookup("/a").unwrap()).unwrap()
+            .iter()
+            .map(|child| file_system.node(*child).unwrap().name())
+            .collect();
+        assert_eq!(names, ["b", "f"]);
+        assert_eq!(file_system.to_string(), "|-/\n| |-a\n| | |-b\n| | | |-0 h\n| | |-10 f\n| |-c\n| | |-5 g")
+    }
+
+    #[test]
+    fn walk_sizes() {
+        let file_system = tree(&["mkdir a/b", "touch a/b/x 3", "touch a/y 4", "touch z 1"]);
+        let walked: Vec<_> = file_system.walk(ROOT).unwrap()
+            .into_iter()
+            .map(|node| (file_system.path(node.id), node.depth, node.size))
+            .collect();
+        assert_eq!(
+            walked,
+            [
+                (String::from("/"), 0, 8),
+                (String::from("/a"), 1, 7),
+                (String::from("/a/b"), 2, 3),
+                (String::from("/a/b/x"), 3, 3),
+                (String::from("/a/y"), 2, 4),
+                (String::from("/z"), 1, 1)
+            ]
+        )
+    }
+
+    #[test]
+    fn changes() {
+        let mut file_system = tree(&["mkdir a/b d", "touch a/b/x 3", "touch a/y 4", "mv a/b d", "mv a/y d/b/w", "touch d/b/w"]);
+        assert_eq!(file_system.lookup("/d/b/w").map(|id| file_system.size(id)), Some(4));
+        assert!(file_system.lookup("/a/b").is_none());
+        assert!(file_system.run(ROOT, &["mv", "d", "d/b"]).is_err());
+        assert!(file_system.run(ROOT, &["mv", "d/b/x", "d/b/w"]).is_err());
+        assert!(file_system.run(ROOT, &["touch", "d"]).is_err());
+        assert!(file_system.run(ROOT, &["mkdir", "d/b/x"]).is_err());
+        let d = file_system.lookup("/d").unwrap();
+        assert!(file_system.run(d, &["rm", "/d"]).is_err());
+        file_system.run(ROOT, &["rm", "d/b"]).unwrap();
+        assert_eq!(file_system.ids().count(), 3);
+        assert_eq!(file_system.size(ROOT), 0);
+        assert!(file_system.run(ROOT, &["rm", "/"]).is_err())
+    }
+
+    #[test]
+    fn cd_needs_an_existing_directory() {
+        let mut file_system = tree(&["mkdir a", "touch a/f 1"]);
+        let a = file_system.lookup("/a").unwrap();
+        assert_eq!(file_system.run(ROOT, &["cd", "a"]).unwrap(), a);
+        assert_eq!(file_system.run(a, &["cd", ".."]).unwrap(), ROOT);
+        assert!(file_system.run(ROOT, &["cd", "b"]).is_err());
+        assert!(file_system.run(ROOT, &["cd", "a/f"]).is_err());
+        assert!(file_system.lookup("/b").is_none())
+    }
+}
@@ -0,0 +1,99 @@
+use common::{Generated, Rng};
+
+use crate::{AT_MOST, TOTAL_SIZE, UNTIL_AVAILABLE};
+
+struct Directory {
+    name: String,
+    parent: usize,
+    children: Vec<usize>,
+    files: Vec<(String, usize)>
+}
+
+fn name(rng: &mut Rng, taken: &[&str]) -> String {
+    loop {
+        let mut name: String = (0..rng.between(1, 8)).map(|_| (b'a' + rng.below(26) as u8) as char).collect();
+        if rng.chance(40) {
+            name.push('.');
+            name.extend((0..3).map(|_| (b'a' + rng.below(26) as u8) as char));
+        }
+        if !taken.contains(&name.as_str()) {
+            return name;
+        }
+    }
+}
+
+fn taken(directories: &[Directory], directory: usize) -> Vec<&str> {
+    directories[directory].children.iter()
+        .map(|child| directories[*child].name.as_str())
+        .chain(directories[directory].files.iter().map(|(name, _)| name.as_str()))
+        .collect()
+}
+
+fn list(directories: &[Directory], directory: usize, console: &mut Vec<String>) {
+    console.push(String::from("$ ls"));
+    for child in directories[directory].children.iter() {
+        console.push(format!("dir {}", directories[*child].name));
+    }
+    for (name, size) in directories[directory].files.iter() {
+        console.push(format!("{} {}", size, name));
+    }
+    for child in directories[directory].children.iter() {
+        console.push(format!("$ cd {}", directories[*child].name));
+        list(directories, *child, console);
+        console.push(String::from("$ cd .."));
+    }
+}
+
+/// Console session exploring a tree of `size` directories, each size summed up from the tree itself
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.max(1);
+    let largest_file = (TOTAL_SIZE / 2 / (count * 4)).clamp(1, 150_000) as i64;
+    let mut directories = vec![Directory { name: String::from("/"), parent: 0, children: vec![], files: vec![] }];
+    for index in 0..count {
+        if index > 0 {
+            let parent = rng.below(index);
+            let name = name(rng, &taken(&directories, parent));
+            directories.push(Directory { name, parent, children: vec![], files: vec![] });
+            directories[parent].children.push(index);
+        }
+        for _ in 0..rng.between(0, 6) {
+            let name = name(rng, &taken(&directories, index));
+            directories[index].files.push((name, rng.between(1, largest_file) as usize));
+        }
+    }
+
+    // A large file fills the disk enough for the update to need space
+    let used: usize = directories.iter().flat_map(|d| d.files.iter().map(|(_, size)| size)).sum();
+    let target_used = rng.between((TOTAL_SIZE - UNTIL_AVAILABLE + 1) as i64, TOTAL_SIZE as i64 - 1) as usize;
+    if used < target_used {
+        let holder = rng.below(count);
+        let name = name(rng, &taken(&directories, holder));
+        directories[holder].files.push((name, target_used - used));
+    }
+
+    // Children are always created after their parent
+    let mut sizes: Vec<usize> = directories.iter().map(|d| d.files.iter().map(|(_, size)| size).sum()).collect();
+    for index in (1..count).rev() {
+        sizes[directories[index].parent] += sizes[index];
+    }
+    let small: usize = sizes.iter().filter(|size| **size <= AT_MOST).sum();
+    let needed = UNTIL_AVAILABLE - (TOTAL_SIZE - sizes[0]);
+    let deleted = sizes.iter().filter(|size| **size >= needed).min().unwrap();
+
+    let mut console = vec![String::from("$ cd /")];
+    list(&directories, 0, &mut console);
+    Generated::solved(console.join("\n"), small, deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day7;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 40).check::<Day7>().unwrap();
+        }
+    }
+}
@@ -1,223 +1,133 @@
-use std::fmt::Display;
+pub mod generator;
 
-enum FSNode {
-    Directory(String, usize, Vec<usize>),
-    File(String, usize)
-}
-
-impl Display for FSNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            FSNode::Directory(name, _, _) => write!(f, "{}", name),
-            FSNode::File(name, size) => write!(f, "{} {}", size, name)
-        }
-    }
-}
+mod filesystem;
 
-impl FSNode {
-    fn name_of_index(storage: &Vec<Self>, index: usize) -> &str {
-        match storage.get(index).unwrap() {
-            FSNode::Directory(name, _, _) => name,
-            FSNode::File(name, _) => name
-        }
-    }
+use common::{Error, Json, Puzzle, Result};
 
-    fn parent_of_index(storage: &Vec<Self>, index: usize) -> usize {
-        match storage.get(index).unwrap() {
-            FSNode::Directory(_, par, _) => *par,
-            _ => panic!("Current dir was file")
-        }
-    }
+pub use filesystem::{FileSystem, Node, Walked, ROOT};
 
-    fn index_has_child(storage: &Vec<Self>, current: usize, file: &str) -> Option<usize> {
-        match storage.get(current).unwrap() {
-            FSNode::Directory(_, _, children) => {
-                for i in children {
-                    if Self::name_of_index(storage, *i) == file.to_string() {
-                        return Some(*i)
-                    }
-                };
-                None
-            }
-            _ => None
-        }
-    }
+const AT_MOST: usize = 100_000;
+const TOTAL_SIZE: usize = 70_000_000;
+const UNTIL_AVAILABLE: usize = 30_000_000;
 
-    fn cd_from_index(storage: &mut Vec<Self>, current: usize, cd: &str) -> usize {
-        let index = Self::index_has_child(storage, current, cd);
-        let new_dir = FSNode::Directory(cd.to_string(), current, vec![]);
-        index
-            .unwrap_or(
-                {
-                    let l = storage.len();
-                    storage.push(new_dir);
-                    storage.get_mut(current).unwrap().add_node(l);
-                    l
-                }
-            )
-    }
+pub struct Day7;
 
-    fn add_dir_to_index(storage: &mut Vec<Self>, current: usize, dir: &str) {
-        let index = Self::index_has_child(storage, current, dir);
-        let new_dir = FSNode::Directory(dir.to_string(), current, vec![]);
-        match index {
-            None => {
-                let l = storage.len();
-                storage.push(new_dir);
-                storage.get_mut(current).unwrap().add_node(l);
-            },
-            _ => panic!("Directory already exists")
-        }
-    }
+impl Puzzle for Day7 {
+    type Input = FileSystem;
+    type Answer1 = usize;
+    type Answer2 = usize;
 
-    fn add_file_to_index(storage: &mut Vec<Self>, current: usize, file: &str, size: usize) {
-        let index = Self::index_has_child(storage, current, file);
-        let new_file = FSNode::File(file.to_string(), size);
-        match index {
-            None => {
-                let l = storage.len();
-                storage.push(new_file);
-                storage.get_mut(current).unwrap().add_node(l);
-            },
-            _ => panic!("Directory already exists")
-        }
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_file_system_from_console(input)
     }
 
-    fn get_size_if_at_most(storage: &Vec<Self>, at_most: usize) -> usize {
-        storage.iter().enumerate()
-            .filter(|(_, fs)| match fs { FSNode::Directory(_, _, _) => true, _ => false})
-            .map(|(i, _)| Self::get_size_of_index(storage, i))
-            .filter(|s| *s <= at_most)
-            .sum()
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        Ok(directories_with_at_most_recursive(input, AT_MOST))
     }
 
-    fn get_size_if_not_less(storage: &Vec<Self>, not_less: usize) -> usize {
-        storage.iter().enumerate()
-            .filter(|(_, fs)| match fs { FSNode::Directory(_, _, _) => true, _ => false})
-            .map(|(i, _)| Self::get_size_of_index(storage, i))
-            .filter(|s| *s >= not_less)
-            .min().unwrap()
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        select_directory_until_available(input, TOTAL_SIZE, UNTIL_AVAILABLE)
     }
 
-    fn get_size_of_index(storage: &Vec<Self>, index: usize) -> usize {
-        let cur = storage.get(index).unwrap();
-        match cur {
-            FSNode::Directory(_, _, children) => {
-                children.iter()
-                    .map(|fs| Self::get_size_of_index(storage, *fs))
-                    .sum()
-            }
-            FSNode::File(_, size) => *size,
-        }
+    fn summary(input: &Self::Input) -> Json {
+        let directories = input.ids().filter(|id| input.node(*id).unwrap().is_directory()).count();
+        Json::object()
+            .with("directories", directories)
+            .with("files", input.ids().count() - directories)
+            .with("used", input.size(ROOT))
     }
 
-    #[allow(dead_code)]
-    fn display_index(storage: &Vec<Self>, index: usize, indent: usize) {
-        for _ in 0..indent {
-            print!("| ");
-        }
-        print!("|-");
-        let fs = storage.get(index).unwrap();
-        println!("{}", fs);
-        match fs {
-            FSNode::Directory(_, _, children) => {
-                for c in children {
-                    Self::display_index(storage, *c, indent + 1)
-                }
-            },
-            _ => ()
-        }
+    fn artefacts(input: &Self::Input) -> Result<Json> {
+        Ok(Json::object().with("tree", json_of(input, ROOT)))
     }
+}
 
-    fn add_node(&mut self, node: usize) {
-        match self {
-            FSNode::Directory(_, _, children) => children.push(node),
-            _ => panic!("Current dir was file")
-        }
+/// Tree under `id` with each node's recursive size
+fn json_of(file_system: &FileSystem, id: usize) -> Json {
+    let node = file_system.node(id).unwrap();
+    let json = Json::object()
+        .with("name", node.name())
+        .with("size", file_system.size(id));
+    match file_system.children(id) {
+        Ok(children) => json.with("children", children.iter().map(|child| json_of(file_system, *child)).collect::<Json>()),
+        Err(_) => json
     }
 }
 
-const ROOT_PATH: &str = "/";
-
-fn build_dic_tree_from_console(console: &str) -> Vec<FSNode> {
+/// Replays the console, commands changing the tree as they go and listings adding what they show
+///
+/// Listing a directory again only updates it, and a listed file's size replaces the one known.
+fn build_file_system_from_console(console: &str) -> Result<FileSystem> {
     let lines = console.split_terminator("\n");
 
-    let mut dic_tree = vec![FSNode::Directory(ROOT_PATH.to_string(), 0, vec![])];
-    let mut current_dir = 0;
+    let mut file_system = FileSystem::new();
+    let mut current_dir = ROOT;
 
     let mut reading_ls = false;
-    for l in lines {
+    for (i, l) in lines.enumerate() {
         let ln = Vec::from_iter(l.trim().split_terminator(" "));
-        match (ln.as_slice(), reading_ls) {
-            (["$", "cd", "/"], false) => current_dir = 0,
-            (["$", "cd", "/"], true) => {
-                current_dir = 0;
-                reading_ls = false;
-            },
-            (["$", "cd", ".."], false) => {
-                current_dir = FSNode::parent_of_index(&dic_tree, current_dir);
+        let res = match (ln.as_slice(), reading_ls) {
+            (["$", "ls"], _) => {
+                reading_ls = true;
+                Ok(())
             },
-            (["$", "cd", ".."], true) => {
-                current_dir = FSNode::parent_of_index(&dic_tree, current_dir);
+            // The transcript may enter directories before any listing shows them
+            (["$", "cd", path], _) => {
                 reading_ls = false;
+                file_system.mkdir_all(current_dir, path)
+                    .map(|dir| current_dir = dir)
             },
-            (["$", "cd", dir], false) => {
-                current_dir = FSNode::cd_from_index(&mut dic_tree, current_dir, dir);
-            },
-            (["$", "cd", dir], true) => {
-                current_dir = FSNode::cd_from_index(&mut dic_tree, current_dir, dir);
+            (["$", command @ ..], _) => {
                 reading_ls = false;
+                file_system.run(current_dir, command)
+                    .map(|dir| current_dir = dir)
             },
-            (["$", "ls"], false) => reading_ls = true,
-            (["$", "ls"], true) => reading_ls = true,
-            (["dir", dir], true) => {
-                FSNode::add_dir_to_index(&mut dic_tree, current_dir, dir);
-            },
+            (["dir", dir], true) => file_system.mkdir(current_dir, dir).map(|_| ()),
             ([size, file], true) => {
-                FSNode::add_file_to_index(&mut dic_tree, current_dir, file, size.parse().unwrap());
+                let size = common::parse_field(size, i, l)?;
+                file_system.touch(current_dir, file, Some(size)).map(|_| ())
             },
-            ln_err => panic!("Unresolved line '{:?}' ({})", ln_err, reading_ls)
-        }
+            (_, true) => Err(Error::invalid_state("Unresolved listing line")),
+            (_, false) => Err(Error::invalid_state("Unresolved command"))
+        };
+        res.map_err(|er| Error::parse(i, l, er.to_string()))?;
     }
 
-    dic_tree
+    Ok(file_system)
 }
 
-fn directories_with_at_most_recursive(console: &str, at_most: usize) -> usize {
-    let dic_storage = build_dic_tree_from_console(console);
-    FSNode::get_size_if_at_most(&dic_storage, at_most)
+/// Directory sizes, each directory counted once
+fn directory_sizes(file_system: &FileSystem) -> impl Iterator<Item = usize> + '_ {
+    file_system.walk(ROOT).unwrap()
+        .into_iter()
+        .filter(|walked| file_system.node(walked.id).unwrap().is_directory())
+        .map(|walked| walked.size)
 }
 
-pub fn process_console(input_path: &str, at_most: usize) -> usize {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => directories_with_at_most_recursive(&content, at_most),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn directories_with_at_most_recursive(file_system: &FileSystem, at_most: usize) -> usize {
+    directory_sizes(file_system).filter(|s| *s <= at_most).sum()
+}
+
+pub fn process_console(input_path: &str, at_most: usize) -> Result<usize> {
+    Ok(directories_with_at_most_recursive(&Day7::parse_file(input_path)?, at_most))
 }
 
-fn select_directory_until_available(console: &str, total_size: usize, until_available: usize) -> usize {
-    let dic_storage = build_dic_tree_from_console(console);
-    let current_available = total_size - FSNode::get_size_of_index(&dic_storage, 0);
+pub fn select_directory_until_available(file_system: &FileSystem, total_size: usize, until_available: usize) -> Result<usize> {
+    let used = file_system.size(ROOT);
+    let current_available = total_size.checked_sub(used)
+        .ok_or_else(|| Error::invalid_state(format!("Used space {} exceeds the disk size {}", used, total_size)))?;
     if until_available < current_available {
-        0
+        Ok(0)
     } else {
-        FSNode::get_size_if_not_less(&dic_storage, until_available - current_available)
+        directory_sizes(file_system)
+            .filter(|s| *s >= until_available - current_available)
+            .min()
+            .ok_or_else(|| Error::invalid_state(format!("No directory frees {} of space", until_available)))
     }
 }
 
-pub fn select_directory_for_deletion(input_path: &str, total_size: usize, until_available: usize) -> usize {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => select_directory_until_available(&content, total_size, until_available),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn select_directory_for_deletion(input_path: &str, total_size: usize, until_available: usize) -> Result<usize> {
+    select_directory_until_available(&Day7::parse_file(input_path)?, total_size, until_available)
 }
 
 #[cfg(test)]
@@ -251,17 +161,44 @@ $ ls
 
     #[test]
     fn test_input1() {
-        assert_eq!(directories_with_at_most_recursive(TEST_INP1, 100000), 95437)
+        assert_eq!(Day7::solve_part1(TEST_INP1).unwrap(), 95437)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(select_directory_until_available(TEST_INP1, 70_000_000, 30_000_000), 24933642)
+        assert_eq!(Day7::solve_part2(TEST_INP1).unwrap(), 24933642)
+    }
+
+    #[test]
+    fn display_tree() {
+        let file_system = Day7::parse(TEST_INP1).unwrap();
+        let tree = file_system.to_string();
+        assert_eq!(tree.lines().count(), 14);
+        assert!(tree.starts_with("|-/\n| |-a\n| | |-e\n| | | |-584 i"))
+    }
+
+    #[test]
+    fn test_relisting_and_commands() {
+        let console = format!("{}\n$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd /a/e\n$ rm /d\n$ mv /b.txt ..\n$ touch /a/new 5", TEST_INP1);
+        let file_system = Day7::parse(&console).unwrap();
+        assert_eq!(file_system.lookup("/a/b.txt").map(|id| file_system.size(id)), Some(14848514));
+        assert!(file_system.lookup("/d").is_none());
+        assert_eq!(directories_with_at_most_recursive(&file_system, AT_MOST), 584)
+    }
+
+    #[test]
+    fn test_tree_artefact() {
+        let tree = Day7::artefacts(&Day7::parse(TEST_INP1).unwrap()).unwrap();
+        let root = tree.get("tree").unwrap();
+        assert_eq!(root.get("size"), Some(&Json::from(48381165)));
+        let Some(Json::Array(children)) = root.get("children") else { panic!("Root without children") };
+        assert_eq!(children[0].get("name"), Some(&Json::from("a")));
+        assert_eq!(children[0].get("size"), Some(&Json::from(94853)))
     }
 
     #[test]
-    fn display_index() {
-        let dic_storage = build_dic_tree_from_console(TEST_INP1);
-        FSNode::display_index(&dic_storage, 0, 0)
+    fn test_unresolved_line() {
+        let console = "$ cd /\n$ ls\ndir a\n$ cp a b";
+        assert!(matches!(Day7::parse(console), Err(Error::Parse { line: 4, .. })))
     }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+use common::{Generated, Rng};
+
+/// Square forest of side `size`, visibility worked out from the tallest tree on each side
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let side = size.max(1);
+    let trees: Vec<Vec<u8>> = (0..side)
+        .map(|_| (0..side).map(|_| rng.below(10) as u8).collect())
+        .collect();
+
+    let sight_lines = |x: usize, y: usize| -> [Vec<u8>; 4] {
+        [
+            (0..y).rev().map(|i| trees[i][x]).collect(),
+            (x + 1..side).map(|j| trees[y][j]).collect(),
+            (y + 1..side).map(|i| trees[i][x]).collect(),
+            (0..x).rev().map(|j| trees[y][j]).collect()
+        ]
+    };
+    let mut visible = 0;
+    let mut best = 0;
+    for (y, row) in trees.iter().enumerate() {
+        for (x, height) in row.iter().copied().enumerate() {
+            let lines = sight_lines(x, y);
+            if lines.iter().any(|line| line.iter().all(|tree| *tree < height)) {
+                visible += 1;
+            }
+            let score: usize = lines.iter()
+                .map(|line| line.iter().position(|tree| *tree >= height).map_or(line.len(), |blocked| blocked + 1))
+                .product();
+            best = best.max(score);
+        }
+    }
+
+    let input = trees.iter()
+        .map(|row| row.iter().map(|h| (b'0' + h) as char).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Generated::solved(input, visible, best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day8;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), seed as usize + 1).check::<Day8>().unwrap();
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+
+use common::{Generated, Rng};
+
+/// Tail positions of a rope with `knots` knots, each knot stepping towards the previous one once it's too far
+fn tail_positions(moves: &[((i32, i32), usize)], knots: usize) -> usize {
+    let mut rope = vec![(0, 0); knots];
+    let mut visited = HashSet::from([(0, 0)]);
+    for ((dx, dy), count) in moves {
+        for _ in 0..*count {
+            rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+            for k in 1..knots {
+                let (x, y) = (rope[k - 1].0 - rope[k].0, rope[k - 1].1 - rope[k].1);
+                if x.abs() > 1 || y.abs() > 1 {
+                    rope[k] = (rope[k].0 + x.signum(), rope[k].1 + y.signum());
+                }
+            }
+            visited.insert(rope[knots - 1]);
+        }
+    }
+    visited.len()
+}
+
+/// `size` head motions, the rope replayed knot by knot
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let directions = [("U", (0, 1)), ("D", (0, -1)), ("L", (-1, 0)), ("R", (1, 0))];
+    let moves: Vec<_> = (0..size.max(1))
+        .map(|_| (*rng.pick(&directions), rng.between(1, 20) as usize))
+        .collect();
+
+    let input = moves.iter()
+        .map(|((name, _), count)| format!("{} {}", name, count))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let steps: Vec<_> = moves.iter().map(|((_, step), count)| (*step, *count)).collect();
+    Generated::solved(input, tail_positions(&steps, 2), tail_positions(&steps, 10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day9;
+
+    #[test]
+    fn generated_answers() {
+        for seed in 0..20 {
+            generate(&mut Rng::new(seed), 100).check::<Day9>().unwrap();
+        }
+    }
+}
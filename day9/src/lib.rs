@@ -1,28 +1,62 @@
+pub mod generator;
+
+use common::{Error, Json, Puzzle, Result};
+
+const SHORT_ROPE: usize = 2;
+const LONG_ROPE: usize = 10;
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    type Input = Vec<Move>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_move_list(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Self::Answer1> {
+        run_knot_motion_simulation(input, SHORT_ROPE)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Self::Answer2> {
+        run_knot_motion_simulation(input, LONG_ROPE)
+    }
+
+    /// Moves are counted one square at a time
+    fn summary(input: &Self::Input) -> Json {
+        Json::object().with("steps", input.len())
+    }
+}
+
 #[derive(Clone)]
-enum Move {
+pub enum Move {
     Up,
     Down,
     Left,
     Right
 }
 
-fn build_move_list(knot_moves: &str) -> Vec<Move> {
-    knot_moves.lines()
+fn build_move_list(knot_moves: &str) -> Result<Vec<Move>> {
+    let moves = knot_moves.lines()
+        .enumerate()
         .map(
-            |l| {
-                let (dir, count) = l.split_once(" ").unwrap();
+            |(i, l)| {
+                let (dir, count) = l.split_once(" ")
+                    .ok_or_else(|| Error::parse(i, l, "Expected a direction and a count"))?;
                 let m = match dir {
                     "U" => Move::Up,
                     "D" => Move::Down,
                     "L" => Move::Left,
                     "R" => Move::Right,
-                    _ => panic!("Unknown move,")
+                    _ => return Err(Error::parse(i, l, "Unknown move"))
                 };
-                vec![m; count.parse().unwrap()]
+                Ok(vec![m; common::parse_field(count, i, l)?])
             }
         )
-        .flatten()
-        .collect()
+        .collect::<Result<Vec<_>>>()?;
+    Ok(moves.concat())
 }
 
 fn move_head(head: &(i32, i32), m: &Move) -> (i32, i32) {
@@ -49,13 +83,16 @@ fn move_knot(parent: &(i32, i32), current: &(i32, i32)) -> (i32, i32) {
         ( 0,      -2) => (current.0    , current.1 - 1),
         ( 1..= 2, -2) => (current.0 + 1, current.1 - 1),
         (-2..=-1, -2) => (current.0 - 1, current.1 - 1),
-        _ => panic!("Distance too far {:?} {:?}", parent, current)
+        // Knots follow one step at a time, so they're never more than two steps apart either way
+        _ => unreachable!("Distance too far {:?} {:?}", parent, current)
     }
 }
 
-fn run_knot_motion_simulation(knot_moves: &str, rope_length: usize) -> u32 {
-    let moves = build_move_list(knot_moves);
-    moves.iter()
+fn run_knot_motion_simulation(moves: &[Move], rope_length: usize) -> Result<u32> {
+    if rope_length == 0 {
+        return Err(Error::invalid_state("A rope needs at least one knot"));
+    }
+    let visited = moves.iter()
         .fold(
             (&mut std::collections::BTreeSet::new(), &mut vec![(0, 0); rope_length]),
             |(set, knots), m| {
@@ -63,7 +100,7 @@ fn run_knot_motion_simulation(knot_moves: &str, rope_length: usize) -> u32 {
                 let mut previous = knots_iter.next().unwrap();
                 *previous = move_head(previous, m);
                 for knot in knots_iter {
-                    *knot = move_knot(previous, &knot);
+                    *knot = move_knot(previous, knot);
                     previous = knot;
                 }
                 set.insert(*previous);
@@ -71,29 +108,16 @@ fn run_knot_motion_simulation(knot_moves: &str, rope_length: usize) -> u32 {
             }
         )
         .0
-        .len() as u32
+        .len() as u32;
+    Ok(visited)
 }
 
-pub fn simulate_knot_motion(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => run_knot_motion_simulation(&content, 2),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn simulate_knot_motion(input_path: &str) -> Result<u32> {
+    Day9::part1(&Day9::parse_file(input_path)?)
 }
 
-pub fn simulate_long_knot_motion(input_path: &str) -> u32 {
-    let content = std::fs::read_to_string(input_path);
-    match content {
-        Ok(content) => run_knot_motion_simulation(&content, 10),
-        Err(er) => {
-            println!("{}", er);
-            0
-        }
-    }
+pub fn simulate_long_knot_motion(input_path: &str) -> Result<u32> {
+    Day9::part2(&Day9::parse_file(input_path)?)
 }
 
 #[cfg(test)]
@@ -122,11 +146,16 @@ U 20"#;
 
     #[test]
     fn test_input1() {
-        assert_eq!(run_knot_motion_simulation(TEST_INP1, 2), 13)
+        assert_eq!(Day9::solve_part1(TEST_INP1).unwrap(), 13)
     }
 
     #[test]
     fn test_input1_part2() {
-        assert_eq!(run_knot_motion_simulation(TEST_INP2, 10), 36)
+        assert_eq!(Day9::solve_part2(TEST_INP2).unwrap(), 36)
+    }
+
+    #[test]
+    fn test_unknown_move() {
+        assert!(matches!(Day9::parse("R 4\nX 4"), Err(Error::Parse { line: 2, .. })))
     }
 }
\ No newline at end of file
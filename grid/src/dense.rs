@@ -0,0 +1,186 @@
+use std::ops::{Index, IndexMut};
+
+use common::{Error, Result};
+
+use crate::Point;
+
+/// Rectangular grid storing every cell, addressed from (0, 0) at the top left
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    /// Builds a grid out of rows, `None` if they don't all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = rows.len();
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /// Parses a character map line by line, every line must have the same length
+    pub fn parse(input: &str, mut tile: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut width = None;
+        let mut cells = vec![];
+        let mut height = 0;
+        for (i, l) in input.lines().enumerate() {
+            let mut row_width = 0;
+            for (j, c) in l.chars().enumerate() {
+                cells.push(tile(c).ok_or_else(|| Error::parse_at(i, j, l, format!("Unknown tile '{}'", c)))?);
+                row_width += 1;
+            }
+            match width {
+                Some(width) if width != row_width => {
+                    return Err(Error::parse(i, l, format!("Expected {} tiles in the row", width)));
+                },
+                _ => width = Some(row_width)
+            }
+            height += 1;
+        }
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    /// Position of `p` in the row major cell storage
+    pub fn index_of(&self, p: Point) -> Option<usize> {
+        self.contains(p).then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new((index % self.width) as isize, (index / self.width) as isize)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index_of(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Keeps the coordinates inside the grid by wrapping around its edges
+    pub fn wrap(&self, p: Point) -> Point {
+        Point::new(p.x.rem_euclid(self.width as isize), p.y.rem_euclid(self.height as isize))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|i| self.point_of(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter()
+            .enumerate()
+            .map(|(i, cell)| (self.point_of(i), cell))
+    }
+
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours4().filter(|n| self.contains(*n))
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|n| self.contains(*n))
+    }
+
+    /// Walks from `from` (excluded) by `step` until leaving the grid
+    pub fn ray(&self, from: Point, step: Point) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(from + step), move |p| Some(*p + step))
+            .take_while(|p| self.contains(*p))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    pub fn render(&self, mut tile: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut tile).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("{:?} outside a {}x{} grid", p, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p).unwrap_or_else(|| panic!("{:?} outside a {}x{} grid", p, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = "#..\n.#.\n..#\n##.";
+
+    fn wall(c: char) -> Option<bool> {
+        match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid = Grid::parse(MAP, wall).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert!(grid[Point::new(1, 1)]);
+        assert!(!grid[Point::new(2, 3)]);
+        assert_eq!(grid.render(|w| if *w { '#' } else { '.' }), MAP)
+    }
+
+    #[test]
+    fn parse_reports_position() {
+        assert!(matches!(Grid::parse("#..\n.x.", wall), Err(Error::Parse { line: 2, column: 2, .. })));
+        assert!(matches!(Grid::parse("#..\n.#", wall), Err(Error::Parse { line: 2, .. })))
+    }
+
+    #[test]
+    fn bounded_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours4(Point::ORIGIN).count(), 2);
+        assert_eq!(grid.neighbours8(Point::ORIGIN).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.ray(Point::new(1, 0), Point::SOUTH).count(), 2);
+        assert_eq!(grid.wrap(Point::new(-1, 3)), Point::new(2, 0))
+    }
+}
@@ -0,0 +1,7 @@
+mod dense;
+mod point;
+mod sparse;
+
+pub use dense::Grid;
+pub use point::Point;
+pub use sparse::SparseGrid;
@@ -0,0 +1,133 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+use common::Json;
+
+/// Signed grid coordinates, `x` grows to the right and `y` grows downwards as text does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+    pub const NORTH: Point = Point::new(0, -1);
+    pub const SOUTH: Point = Point::new(0, 1);
+    pub const EAST: Point = Point::new(1, 0);
+    pub const WEST: Point = Point::new(-1, 0);
+    pub const NORTH_EAST: Point = Point::new(1, -1);
+    pub const NORTH_WEST: Point = Point::new(-1, -1);
+    pub const SOUTH_EAST: Point = Point::new(1, 1);
+    pub const SOUTH_WEST: Point = Point::new(-1, 1);
+
+    /// Steps to the 4 orthogonal neighbours, clockwise from north
+    pub const ORTHOGONAL: [Point; 4] = [Point::NORTH, Point::EAST, Point::SOUTH, Point::WEST];
+    /// Steps to all 8 neighbours, clockwise from north
+    pub const ADJACENT: [Point; 8] = [
+        Point::NORTH,
+        Point::NORTH_EAST,
+        Point::EAST,
+        Point::SOUTH_EAST,
+        Point::SOUTH,
+        Point::SOUTH_WEST,
+        Point::WEST,
+        Point::NORTH_WEST
+    ];
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Point::ORTHOGONAL.into_iter().map(move |step| self + step)
+    }
+
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        Point::ADJACENT.into_iter().map(move |step| self + step)
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Quarter turn clockwise of a step, as seen on screen
+    pub fn rotate_clockwise(self) -> Self {
+        Point::new(-self.y, self.x)
+    }
+
+    pub fn rotate_counterclockwise(self) -> Self {
+        Point::new(self.y, -self.x)
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+/// Written as an `[x, y]` pair in the reports
+impl From<Point> for Json {
+    fn from(p: Point) -> Self {
+        Json::from(vec![p.x, p.y])
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: isize) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbours() {
+        let p = Point::new(2, -3);
+        assert_eq!(p.neighbours4().count(), 4);
+        assert!(p.neighbours8().all(|n| n.x.abs_diff(p.x) <= 1 && n.y.abs_diff(p.y) <= 1 && n != p));
+        assert_eq!(p.neighbours4().filter(|n| n.manhattan(p) == 1).count(), 4)
+    }
+
+    #[test]
+    fn rotations() {
+        assert_eq!(Point::NORTH.rotate_clockwise(), Point::EAST);
+        assert_eq!(Point::EAST.rotate_clockwise(), Point::SOUTH);
+        assert_eq!(Point::NORTH.rotate_counterclockwise(), Point::WEST);
+        assert_eq!(Point::WEST.rotate_clockwise().rotate_counterclockwise(), Point::WEST)
+    }
+}
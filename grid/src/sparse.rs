@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use common::{Error, Result};
+
+use crate::Point;
+
+/// Unbounded grid only storing the occupied cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid { cells: HashMap::new() }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a character map, skipping the `empty` tiles, the first line is at `y` 0
+    pub fn parse(input: &str, empty: char, mut tile: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut grid = SparseGrid::new();
+        for (i, l) in input.lines().enumerate() {
+            for (j, c) in l.chars().enumerate().filter(|(_, c)| *c != empty) {
+                let cell = tile(c).ok_or_else(|| Error::parse_at(i, j, l, format!("Unknown tile '{}'", c)))?;
+                grid.insert(Point::new(j as isize, i as isize), cell);
+            }
+        }
+        Ok(grid)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn insert(&mut self, p: Point, cell: T) -> Option<T> {
+        self.cells.insert(p, cell)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    /// Occupied points in no particular order
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(p, cell)| (*p, cell))
+    }
+
+    /// Occupied neighbours of `p`
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours4().filter(|n| self.contains(*n))
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|n| self.contains(*n))
+    }
+
+    /// Top left and bottom right corners enclosing every occupied cell
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.cells.keys()
+            .fold(
+                None,
+                |bounds, p| match bounds {
+                    None => Some((*p, *p)),
+                    Some((min, max)) => Some((
+                        Point::new(min.x.min(p.x), min.y.min(p.y)),
+                        Point::new(max.x.max(p.x), max.y.max(p.y))
+                    ))
+                }
+            )
+    }
+
+    /// Draws the area within `bounds`, `empty` for the cells without a value
+    pub fn render(&self, empty: char, mut tile: impl FnMut(&T) -> char) -> String {
+        match self.bounds() {
+            None => String::new(),
+            Some((min, max)) => (min.y..=max.y)
+                .map(
+                    |y| {
+                        (min.x..=max.x)
+                            .map(|x| self.get(Point::new(x, y)).map_or(empty, &mut tile))
+                            .collect::<String>()
+                    }
+                )
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        SparseGrid { cells: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_render() {
+        let grid = SparseGrid::parse("..#\n#..\n.#.", '.', |c| (c == '#').then_some(())).unwrap();
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.bounds(), Some((Point::new(0, 0), Point::new(2, 2))));
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 3);
+        assert_eq!(grid.render('.', |_| '#'), "..#\n#..\n.#.");
+        assert!(matches!(SparseGrid::parse("..#\n#?.", '.', |c| (c == '#').then_some(())), Err(Error::Parse { line: 2, column: 2, .. })))
+    }
+
+    #[test]
+    fn signed_coordinates() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Point::new(-2, -1), 'a');
+        grid.insert(Point::new(1, 0), 'b');
+        assert_eq!(grid.bounds(), Some((Point::new(-2, -1), Point::new(1, 0))));
+        assert_eq!(grid.render(' ', |c| *c), "a   \n   b")
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, fmt::Write, hint::black_box, time::{Duration, Instant}};
+
+use common::Puzzle;
+
+use crate::{generators, solvers};
+
+/// Median time of one stage of a day, for a generated input of `size`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub day: u8,
+    pub size: usize,
+    pub stage: String,
+    pub median: Duration
+}
+
+/// Input sizes benchmarked when none are given, from a quarter of a real input's size up to a whole one
+pub fn default_sizes(day: u8) -> Vec<usize> {
+    let size = generators::default_size(day);
+    let mut sizes = vec![(size / 4).max(1), (size / 2).max(1), size];
+    sizes.dedup();
+    sizes
+}
+
+fn median<T>(samples: usize, mut stage: impl FnMut() -> common::Result<T>) -> common::Result<Duration> {
+    let mut times = Vec::with_capacity(samples);
+    for _ in 0..samples.max(1) {
+        let start = Instant::now();
+        black_box(stage()?);
+        times.push(start.elapsed());
+    }
+    times.sort();
+    Ok(times[times.len() / 2])
+}
+
+/// Parse and each part timed on their own, parts always solving an input parsed beforehand
+fn bench_puzzle<P: Puzzle>(day: u8, content: &str, samples: usize) -> Vec<(&'static str, common::Result<Duration>)> {
+    let parse = median(samples, || P::parse(content));
+    let input = match P::parse(content) {
+        Ok(input) => input,
+        Err(_) => return vec![("parse", parse)]
+    };
+    let mut stages = vec![("parse", parse), ("part1", median(samples, || P::part1(&input)))];
+    if solvers::parts(day).contains(&2) {
+        stages.push(("part2", median(samples, || P::part2(&input))));
+    }
+    stages
+}
+
+pub fn bench_day(day: u8, content: &str, samples: usize) -> Vec<(&'static str, common::Result<Duration>)> {
+    match day {
+        1 => bench_puzzle::<day1::Day1>(day, content, samples),
+        2 => bench_puzzle::<day2::Day2>(day, content, samples),
+        3 => bench_puzzle::<day3::Day3>(day, content, samples),
+        4 => bench_puzzle::<day4::Day4>(day, content, samples),
+        5 => bench_puzzle::<day5::Day5>(day, content, samples),
+        6 => bench_puzzle::<day6::Day6>(day, content, samples),
+        7 => bench_puzzle::<day7::Day7>(day, content, samples),
+        8 => bench_puzzle::<day8::Day8>(day, content, samples),
+        9 => bench_puzzle::<day9::Day9>(day, content, samples),
+        10 => bench_puzzle::<day10::Day10>(day, content, samples),
+        11 => bench_puzzle::<day11::Day11>(day, content, samples),
+        12 => bench_puzzle::<day12::Day12>(day, content, samples),
+        13 => bench_puzzle::<day13::Day13>(day, content, samples),
+        14 => bench_puzzle::<day14::Day14>(day, content, samples),
+        15 => bench_puzzle::<day15::Day15>(day, content, samples),
+        16 => bench_puzzle::<day16::Day16>(day, content, samples),
+        17 => bench_puzzle::<day17::Day17>(day, content, samples),
+        18 => bench_puzzle::<day18::Day18>(day, content, samples),
+        19 => bench_puzzle::<day19::Day19>(day, content, samples),
+        20 => bench_puzzle::<day20::Day20>(day, content, samples),
+        21 => bench_puzzle::<day21::Day21>(day, content, samples),
+        22 => bench_puzzle::<day22::Day22>(day, content, samples),
+        23 => bench_puzzle::<day23::Day23>(day, content, samples),
+        24 => bench_puzzle::<day24::Day24>(day, content, samples),
+        _ => bench_puzzle::<day25::Day25>(day, content, samples)
+    }
+}
+
+const HEADER: &str = "day\tsize\tstage\tmedian_ns";
+
+/// Tab separated report, one line per stage, compared against `baseline` when there's one
+pub fn render_report(timings: &[Timing], baseline: Option<&[Timing]>) -> String {
+    let previous: HashMap<_, _> = baseline.unwrap_or_default()
+        .iter()
+        .map(|t| ((t.day, t.size, t.stage.as_str()), t.median))
+        .collect();
+    let mut report = String::from(HEADER);
+    if baseline.is_some() {
+        report += "\tbaseline_ns\tchange";
+    }
+    for t in timings {
+        write!(report, "\n{}\t{}\t{}\t{}", t.day, t.size, t.stage, t.median.as_nanos()).unwrap();
+        match previous.get(&(t.day, t.size, t.stage.as_str())) {
+            Some(before) if !before.is_zero() => {
+                let change = (t.median.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+                write!(report, "\t{}\t{:+.1}%", before.as_nanos(), change).unwrap();
+            },
+            Some(before) => write!(report, "\t{}\t", before.as_nanos()).unwrap(),
+            None if baseline.is_some() => report += "\t\t",
+            None => ()
+        }
+    }
+    report
+}
+
+/// Reads back a report written by `render_report`, ignoring any comparison columns
+pub fn parse_report(report: &str) -> Result<Vec<Timing>, String> {
+    report.lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(
+            |(i, line)| match line.split('\t').collect::<Vec<_>>().as_slice() {
+                [day, size, stage, median, ..] => {
+                    let field = |value: &str| value.parse::<u64>()
+                        .map_err(|_| format!("Line {}: invalid number '{}'", i + 1, value));
+                    Ok(
+                        Timing {
+                            day: field(day)? as u8,
+                            size: field(size)? as usize,
+                            stage: stage.to_string(),
+                            median: Duration::from_nanos(field(median)?)
+                        }
+                    )
+                },
+                _ => Err(format!("Line {}: expected day, size, stage and median", i + 1))
+            }
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(day: u8, size: usize, stage: &str, nanos: u64) -> Timing {
+        Timing { day, size, stage: stage.to_string(), median: Duration::from_nanos(nanos) }
+    }
+
+    #[test]
+    fn report_round_trip() {
+        let before = vec![timing(15, 30, "part2", 2000), timing(20, 5000, "part1", 400)];
+        let after = vec![timing(15, 30, "part2", 1000), timing(23, 70, "parse", 50)];
+        let report = render_report(&after, Some(&before));
+        assert_eq!(
+            report,
+            "day\tsize\tstage\tmedian_ns\tbaseline_ns\tchange\n15\t30\tpart2\t1000\t2000\t-50.0%\n23\t70\tparse\t50\t\t"
+        );
+        assert_eq!(parse_report(&report), Ok(after));
+        assert!(parse_report("day\tsize\tstage\tmedian_ns\n15\t30").is_err())
+    }
+
+    #[test]
+    fn bench_every_stage() {
+        let generated = generators::generate(1, 2022, 10);
+        let stages = bench_day(1, &generated.input, 3);
+        assert_eq!(stages.iter().map(|(stage, _)| *stage).collect::<Vec<_>>(), ["parse", "part1", "part2"]);
+        assert!(stages.iter().all(|(_, time)| time.is_ok()));
+        assert_eq!(bench_day(25, &generators::generate(25, 2022, 10).input, 1).len(), 2)
+    }
+}
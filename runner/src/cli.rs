@@ -0,0 +1,363 @@
+use std::str::FromStr;
+
+use common::trace::Level;
+
+pub const USAGE: &str =
+r#"Usage: aoc run [--day <1-25>] [--part <1|2>] [--input <path|->] [--json] [--trace <debug|trace>]
+               [day options]
+       aoc generate --day <1-25> [--seed <seed>] [--size <size>]
+       aoc verify [--day <1-25>] [--part <1|2>] [--record]
+       aoc bench [--day <1-25>] [--sizes <size,...>] [--seed <seed>] [--samples <count>]
+                 [--output <path>] [--baseline <path>]
+
+Runs the solvers and prints each answer with the time it took.
+Without --day every day is run against ./dayN/resources/input.txt.
+Passing '-' as input reads the puzzle input from stdin.
+With --json each day is printed as a single line JSON report instead, holding the answers,
+a summary of the parsed input and the day's intermediate results.
+With --trace the events the solvers go through are logged to stderr, 'debug' for the steps of a
+solver and 'trace' for every state of its inner loops as well.
+
+Generate prints a synthetic puzzle input for the day, and the answers known for it on stderr.
+The same seed (2022) and size always give the same input, the size defaults to about a real input's.
+
+Verify solves each day's input.txt with the default day options and checks every answer against
+the day's resources/answers.txt. Parts without a recorded answer are skipped, unless --record is
+given, which solves them and writes their answers down.
+
+Bench times parsing and each part separately on generated inputs of every size, keeping the median
+of the samples (5). Sizes default to a quarter, half and all of a real input's size, and need --day.
+The tab separated report goes to stdout or --output, and is compared against an earlier one
+given as --baseline.
+
+Day options:
+    --group-size <elves>    day 3 part 2, elves sharing a badge (3)
+    --at-most <size>        day 7 part 1, directory size limit (100000)
+    --total-size <size>     day 7 part 2, disk size (70000000)
+    --needed <size>         day 7 part 2, space required for the update (30000000)
+    --round-limit <rounds>  day 11, rounds to simulate (20 / 10000)
+    --relief <true|false>   day 11, divide worry by 3 after inspection (true / false)
+    --depth <row>           day 15 part 1, row to probe (2000000)
+    --bound <coord>         day 15 part 2, search area upper bound (4000000)
+    --stop-at <rocks>       day 17, rocks to drop (2022 / 1000000000000)
+    --rounds <rounds>       day 20, mixing rounds (1 / 10)
+    --key <key>             day 20, decryption key (1 / 811589153)"#;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Input {
+    Path(String),
+    Stdin
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Parameters {
+    pub group_size: Option<usize>,
+    pub at_most: Option<usize>,
+    pub total_size: Option<usize>,
+    pub needed: Option<usize>,
+    pub round_limit: Option<usize>,
+    pub relief: Option<bool>,
+    pub depth: Option<isize>,
+    pub bound: Option<isize>,
+    pub stop_at: Option<usize>,
+    pub rounds: Option<usize>,
+    pub key: Option<i64>
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    pub input: Option<Input>,
+    pub json: bool,
+    pub trace: Option<Level>,
+    pub parameters: Parameters
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct GenerateOptions {
+    pub day: u8,
+    pub seed: u64,
+    pub size: Option<usize>
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct VerifyOptions {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    pub record: bool
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+    pub day: Option<u8>,
+    pub sizes: Option<Vec<usize>>,
+    pub seed: u64,
+    pub samples: usize,
+    pub output: Option<String>,
+    pub baseline: Option<String>
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunOptions),
+    Generate(GenerateOptions),
+    Verify(VerifyOptions),
+    Bench(BenchOptions),
+    Help
+}
+
+// Which day each day specific flag belongs to
+const DAY_FLAGS: [(&str, u8); 11] = [
+    ("--group-size", 3),
+    ("--at-most", 7),
+    ("--total-size", 7),
+    ("--needed", 7),
+    ("--round-limit", 11),
+    ("--relief", 11),
+    ("--depth", 15),
+    ("--bound", 15),
+    ("--stop-at", 17),
+    ("--rounds", 20),
+    ("--key", 20)
+];
+
+fn flag_value<T: FromStr>(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<T, String> {
+    let value = args.next()
+        .ok_or_else(|| format!("Missing value for '{}'", flag))?;
+    value.parse()
+        .map_err(|_| format!("Invalid value '{}' for '{}'", value, flag))
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunOptions, String> {
+    let mut options = RunOptions::default();
+    let mut day_flags = vec![];
+    while let Some(flag) = args.next() {
+        let params = &mut options.parameters;
+        match flag.as_str() {
+            "--day" => options.day = Some(flag_value(&flag, &mut args)?),
+            "--part" => options.part = Some(flag_value(&flag, &mut args)?),
+            "--input" => {
+                let input: String = flag_value(&flag, &mut args)?;
+                options.input = Some(if input == "-" { Input::Stdin } else { Input::Path(input) });
+            },
+            "--json" => options.json = true,
+            "--trace" => options.trace = Some(flag_value(&flag, &mut args)?),
+            "--group-size" => params.group_size = Some(flag_value(&flag, &mut args)?),
+            "--at-most" => params.at_most = Some(flag_value(&flag, &mut args)?),
+            "--total-size" => params.total_size = Some(flag_value(&flag, &mut args)?),
+            "--needed" => params.needed = Some(flag_value(&flag, &mut args)?),
+            "--round-limit" => params.round_limit = Some(flag_value(&flag, &mut args)?),
+            "--relief" => params.relief = Some(flag_value(&flag, &mut args)?),
+            "--depth" => params.depth = Some(flag_value(&flag, &mut args)?),
+            "--bound" => params.bound = Some(flag_value(&flag, &mut args)?),
+            "--stop-at" => params.stop_at = Some(flag_value(&flag, &mut args)?),
+            "--rounds" => params.rounds = Some(flag_value(&flag, &mut args)?),
+            "--key" => params.key = Some(flag_value(&flag, &mut args)?),
+            _ => return Err(format!("Unknown option '{}'", flag))
+        }
+        if let Some((_, day)) = DAY_FLAGS.iter().find(|(f, _)| *f == flag) {
+            day_flags.push((flag, *day));
+        }
+    }
+
+    match options.day {
+        Some(1..=25) | None => (),
+        Some(day) => return Err(format!("There is no day {}", day))
+    }
+    match options.part {
+        Some(1 | 2) | None => (),
+        Some(part) => return Err(format!("There is no part {}", part))
+    }
+    if options.day.is_none() && options.input.is_some() {
+        return Err(String::from("'--input' requires '--day'"));
+    }
+    for (flag, flag_day) in day_flags {
+        match options.day {
+            Some(day) if day == flag_day => (),
+            Some(day) => return Err(format!("'{}' does not apply to day {}", flag, day)),
+            None => return Err(format!("'{}' requires '--day {}'", flag, flag_day))
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_generate(mut args: impl Iterator<Item = String>) -> Result<GenerateOptions, String> {
+    let (mut day, mut seed, mut size) = (None, 2022, None);
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--day" => day = Some(flag_value(&flag, &mut args)?),
+            "--seed" => seed = flag_value(&flag, &mut args)?,
+            "--size" => size = Some(flag_value(&flag, &mut args)?),
+            _ => return Err(format!("Unknown option '{}'", flag))
+        }
+    }
+
+    match day {
+        Some(day @ 1..=25) => Ok(GenerateOptions { day, seed, size }),
+        Some(day) => Err(format!("There is no day {}", day)),
+        None => Err(String::from("'generate' requires '--day'"))
+    }
+}
+
+fn parse_verify(mut args: impl Iterator<Item = String>) -> Result<VerifyOptions, String> {
+    let mut options = VerifyOptions::default();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--day" => options.day = Some(flag_value(&flag, &mut args)?),
+            "--part" => options.part = Some(flag_value(&flag, &mut args)?),
+            "--record" => options.record = true,
+            _ => return Err(format!("Unknown option '{}'", flag))
+        }
+    }
+
+    match options.day {
+        Some(1..=25) | None => (),
+        Some(day) => return Err(format!("There is no day {}", day))
+    }
+    match options.part {
+        Some(1 | 2) | None => Ok(options),
+        Some(part) => Err(format!("There is no part {}", part))
+    }
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, String> {
+    let mut options = BenchOptions { day: None, sizes: None, seed: 2022, samples: 5, output: None, baseline: None };
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--day" => options.day = Some(flag_value(&flag, &mut args)?),
+            "--sizes" => {
+                let sizes: String = flag_value(&flag, &mut args)?;
+                options.sizes = Some(
+                    sizes.split(',')
+                        .map(|size| size.parse().map_err(|_| format!("Invalid size '{}' for '{}'", size, flag)))
+                        .collect::<Result<_, _>>()?
+                );
+            },
+            "--seed" => options.seed = flag_value(&flag, &mut args)?,
+            "--samples" => options.samples = flag_value(&flag, &mut args)?,
+            "--output" => options.output = Some(flag_value(&flag, &mut args)?),
+            "--baseline" => options.baseline = Some(flag_value(&flag, &mut args)?),
+            _ => return Err(format!("Unknown option '{}'", flag))
+        }
+    }
+
+    match options.day {
+        Some(1..=25) | None => (),
+        Some(day) => return Err(format!("There is no day {}", day))
+    }
+    if options.day.is_none() && options.sizes.is_some() {
+        return Err(String::from("'--sizes' requires '--day'"));
+    }
+    if options.samples == 0 {
+        return Err(String::from("'--samples' must be at least 1"));
+    }
+    Ok(options)
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    match args.next().as_deref() {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("generate") => parse_generate(args).map(Command::Generate),
+        Some("verify") => parse_verify(args).map(Command::Verify),
+        Some("bench") => parse_bench(args).map(Command::Bench),
+        Some("help" | "--help" | "-h") | None => Ok(Command::Help),
+        Some(command) => Err(format!("Unknown command '{}'", command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parse_run_with_day_options() {
+        let expected = RunOptions {
+            day: Some(17),
+            part: Some(2),
+            input: Some(Input::Stdin),
+            json: false,
+            trace: None,
+            parameters: Parameters {
+                stop_at: Some(5000),
+                ..Default::default()
+            }
+        };
+        assert_eq!(parse("run --day 17 --part 2 --input - --stop-at 5000"), Ok(Command::Run(expected)))
+    }
+
+    #[test]
+    fn parse_run_json() {
+        let expected = RunOptions {
+            day: Some(5),
+            json: true,
+            ..Default::default()
+        };
+        assert_eq!(parse("run --json --day 5"), Ok(Command::Run(expected)));
+        assert!(matches!(parse("run --trace debug"), Ok(Command::Run(RunOptions { trace: Some(Level::Debug), .. }))));
+        assert!(parse("run --trace verbose").is_err())
+    }
+
+    #[test]
+    fn reject_misplaced_day_options() {
+        assert!(parse("run --day 7 --stop-at 5000").is_err());
+        assert!(parse("run --day 2 --group-size 4").is_err());
+        assert!(parse("run --stop-at 5000").is_err());
+        assert!(parse("run --input input.txt").is_err());
+        assert!(parse("run --day 26").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+    }
+
+    #[test]
+    fn parse_generate_options() {
+        assert_eq!(
+            parse("generate --day 16 --size 20"),
+            Ok(Command::Generate(GenerateOptions { day: 16, seed: 2022, size: Some(20) }))
+        );
+        assert_eq!(
+            parse("generate --seed 7 --day 3"),
+            Ok(Command::Generate(GenerateOptions { day: 3, seed: 7, size: None }))
+        );
+        assert!(parse("generate --size 20").is_err());
+        assert!(parse("generate --day 0").is_err());
+        assert!(parse("generate --day 1 --part 1").is_err());
+    }
+
+    #[test]
+    fn parse_verify_options() {
+        assert_eq!(
+            parse("verify --day 10 --record"),
+            Ok(Command::Verify(VerifyOptions { day: Some(10), part: None, record: true }))
+        );
+        assert_eq!(parse("verify"), Ok(Command::Verify(VerifyOptions::default())));
+        assert!(parse("verify --part 3").is_err());
+        assert!(parse("verify --input input.txt").is_err());
+    }
+
+    #[test]
+    fn parse_bench_options() {
+        assert_eq!(
+            parse("bench --day 20 --sizes 100,1000,5000 --samples 3 --baseline before.tsv"),
+            Ok(
+                Command::Bench(
+                    BenchOptions {
+                        day: Some(20),
+                        sizes: Some(vec![100, 1000, 5000]),
+                        seed: 2022,
+                        samples: 3,
+                        output: None,
+                        baseline: Some(String::from("before.tsv"))
+                    }
+                )
+            )
+        );
+        assert!(parse("bench --sizes 100").is_err());
+        assert!(parse("bench --day 20 --sizes 100,x").is_err());
+        assert!(parse("bench --samples 0").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use common::{Generated, Rng};
+
+/// Input size used when none is given, about the size of the real puzzle inputs
+pub fn default_size(day: u8) -> usize {
+    match day {
+        1 => 250,
+        2 => 2500,
+        3 => 100,
+        4 => 1000,
+        5 => 500,
+        6 => 4096,
+        7 => 200,
+        8 => 99,
+        9 => 2000,
+        10 => 140,
+        11 => 8,
+        12 => 80,
+        13 => 150,
+        14 => 150,
+        15 => 30,
+        16 => 40,
+        17 => 10_000,
+        18 => 20,
+        19 => 30,
+        20 => 5000,
+        21 => 60,
+        22 => 50,
+        23 => 70,
+        24 => 120,
+        _ => 100
+    }
+}
+
+pub fn generate(day: u8, seed: u64, size: usize) -> Generated {
+    let rng = &mut Rng::new(seed);
+    match day {
+        1 => day1::generator::generate(rng, size),
+        2 => day2::generator::generate(rng, size),
+        3 => day3::generator::generate(rng, size),
+        4 => day4::generator::generate(rng, size),
+        5 => day5::generator::generate(rng, size),
+        6 => day6::generator::generate(rng, size),
+        7 => day7::generator::generate(rng, size),
+        8 => day8::generator::generate(rng, size),
+        9 => day9::generator::generate(rng, size),
+        10 => day10::generator::generate(rng, size),
+        11 => day11::generator::generate(rng, size),
+        12 => day12::generator::generate(rng, size),
+        13 => day13::generator::generate(rng, size),
+        14 => day14::generator::generate(rng, size),
+        15 => day15::generator::generate(rng, size),
+        16 => day16::generator::generate(rng, size),
+        17 => day17::generator::generate(rng, size),
+        18 => day18::generator::generate(rng, size),
+        19 => day19::generator::generate(rng, size),
+        20 => day20::generator::generate(rng, size),
+        21 => day21::generator::generate(rng, size),
+        22 => day22::generator::generate(rng, size),
+        23 => day23::generator::generate(rng, size),
+        24 => day24::generator::generate(rng, size),
+        _ => day25::generator::generate(rng, size)
+    }
+}
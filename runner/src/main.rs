@@ -0,0 +1,241 @@
+mod bench;
+mod cli;
+mod generators;
+mod solvers;
+
+use std::time::Instant;
+
+use cli::{BenchOptions, Command, GenerateOptions, Input, RunOptions, VerifyOptions};
+use common::{trace, Answers, Json};
+
+fn run(options: RunOptions) -> Result<(), String> {
+    let days: Vec<u8> = match options.day {
+        Some(day) => vec![day],
+        None => (1..=25).collect()
+    };
+
+    // Stdin can only be consumed once, so it's kept around for every day
+    let stdin = match options.input {
+        Some(Input::Stdin) => {
+            Some(
+                common::read_from(std::io::stdin().lock())
+                    .map_err(|er| format!("Failed to read stdin: {}", er))?
+            )
+        },
+        _ => None
+    };
+
+    for day in days {
+        let input_name = match &options.input {
+            Some(Input::Path(path)) => path.clone(),
+            Some(Input::Stdin) => String::from("stdin"),
+            None => solvers::default_input(day)
+        };
+        let file_content;
+        let content = match &stdin {
+            Some(content) => content,
+            None => {
+                file_content = common::read_input(&input_name)
+                    .map_err(|er| format!("Day {} ({}): {}", day, input_name, er))?;
+                &file_content
+            }
+        };
+
+        let parts = match options.part {
+            Some(part) => vec![part],
+            None => solvers::parts(day).to_vec()
+        };
+        let mut reports = vec![];
+        for part in parts {
+            let start = Instant::now();
+            let solve = || solvers::solve(day, part, content, &options.parameters);
+            let answer = match options.trace {
+                Some(level) => {
+                    trace::observe(
+                        level,
+                        move |level, event| eprintln!("[{}] day {} part {}: {}", level, day, part, event),
+                        solve
+                    )
+                },
+                None => solve()
+            }
+                .map_err(|er| format!("Day {} part {} ({}): {}", day, part, input_name, er))?;
+            let elapsed = start.elapsed();
+            if options.json {
+                reports.push(
+                    Json::object()
+                        .with("part", part)
+                        .with("answer", answer)
+                        .with("elapsed_ns", elapsed.as_nanos())
+                );
+            } else {
+                let answer = solvers::answer_text(answer);
+                if answer.contains('\n') {
+                    println!("Day {} part {} ({:?}):\n{}", day, part, elapsed, answer);
+                } else {
+                    println!("Day {} part {}: {} ({:?})", day, part, answer, elapsed);
+                }
+            }
+        }
+
+        if options.json {
+            let (summary, artefacts) = solvers::describe(day, content, &options.parameters)
+                .map_err(|er| format!("Day {} ({}): {}", day, input_name, er))?;
+            let report = Json::object()
+                .with("day", day)
+                .with("input", input_name)
+                .with("summary", summary)
+                .with("parts", reports)
+                .with("artefacts", artefacts);
+            println!("{}", report);
+        }
+    }
+    Ok(())
+}
+
+fn generate(GenerateOptions { day, seed, size }: GenerateOptions) -> Result<(), String> {
+    let generated = generators::generate(day, seed, size.unwrap_or_else(|| generators::default_size(day)));
+    println!("{}", generated.input);
+    for (part, answer) in solvers::parts(day).iter().zip([generated.part1, generated.part2]) {
+        // Kept off stdout so the input can be piped straight into `aoc run --input -`
+        eprintln!("Day {} part {}: {}", day, part, answer.as_deref().unwrap_or("unknown"));
+    }
+    Ok(())
+}
+
+fn bench(options: BenchOptions) -> Result<(), String> {
+    let baseline = match &options.baseline {
+        Some(path) => {
+            let report = common::read_input(path)
+                .map_err(|er| format!("Baseline ({}): {}", path, er))?;
+            Some(bench::parse_report(&report).map_err(|er| format!("Baseline ({}): {}", path, er))?)
+        },
+        None => None
+    };
+    let days: Vec<u8> = match options.day {
+        Some(day) => vec![day],
+        None => (1..=25).collect()
+    };
+
+    let mut timings = vec![];
+    for day in days {
+        let sizes = options.sizes.clone().unwrap_or_else(|| bench::default_sizes(day));
+        for size in sizes {
+            let generated = generators::generate(day, options.seed, size);
+            for (stage, median) in bench::bench_day(day, &generated.input, options.samples) {
+                match median {
+                    Ok(median) => {
+                        eprintln!("Day {} size {} {}: {:?}", day, size, stage, median);
+                        timings.push(bench::Timing { day, size, stage: stage.to_string(), median });
+                    },
+                    // A failing stage has no meaningful time, it's left out of the report
+                    Err(er) => eprintln!("Day {} size {} {}: {}", day, size, stage, er)
+                }
+            }
+        }
+    }
+
+    let report = bench::render_report(&timings, baseline.as_deref());
+    match &options.output {
+        Some(path) => std::fs::write(path, report + "\n").map_err(|er| format!("Report ({}): {}", path, er)),
+        None => {
+            println!("{}", report);
+            Ok(())
+        }
+    }
+}
+
+fn verify(options: VerifyOptions) -> Result<(), String> {
+    let days: Vec<u8> = match options.day {
+        Some(day) => vec![day],
+        None => (1..=25).collect()
+    };
+
+    let (mut passed, mut failed, mut unrecorded) = (0, 0, 0);
+    for day in days {
+        let input_name = solvers::default_input(day);
+        let answers_name = solvers::default_answers(day);
+        let content = common::read_input(&input_name)
+            .map_err(|er| format!("Day {} ({}): {}", day, input_name, er))?;
+        // A day without an answers file yet has nothing recorded
+        let mut answers = if std::path::Path::new(&answers_name).exists() {
+            common::read_input(&answers_name)
+                .and_then(|answers| Answers::parse(&answers))
+                .map_err(|er| format!("Day {} ({}): {}", day, answers_name, er))?
+        } else {
+            Answers::default()
+        };
+
+        let parts = match options.part {
+            Some(part) => vec![part],
+            None => solvers::parts(day).to_vec()
+        };
+        let mut recorded = false;
+        for part in parts {
+            let expected = answers.get(part).map(String::from);
+            if expected.is_none() && !options.record {
+                unrecorded += 1;
+                println!("Day {} part {}: no recorded answer", day, part);
+                continue;
+            }
+
+            let start = Instant::now();
+            let answer = solvers::solve(day, part, &content, &cli::Parameters::default());
+            let elapsed = start.elapsed();
+            match (answer.map(solvers::answer_text), expected) {
+                (Err(er), _) => {
+                    failed += 1;
+                    println!("Day {} part {}: failed, {}", day, part, er);
+                },
+                (Ok(answer), Some(expected)) if answer == expected => {
+                    passed += 1;
+                    println!("Day {} part {}: ok ({:?})", day, part, elapsed);
+                },
+                (Ok(answer), Some(expected)) => {
+                    failed += 1;
+                    if answer.contains('\n') || expected.contains('\n') {
+                        println!("Day {} part {}: mismatch, expected\n{}\ngot\n{}", day, part, expected, answer);
+                    } else {
+                        println!("Day {} part {}: mismatch, expected {} got {}", day, part, expected, answer);
+                    }
+                },
+                (Ok(answer), None) => {
+                    passed += 1;
+                    recorded = true;
+                    println!("Day {} part {}: recorded ({:?})", day, part, elapsed);
+                    answers.set(part, answer);
+                }
+            }
+        }
+        if recorded {
+            std::fs::write(&answers_name, answers.to_string())
+                .map_err(|er| format!("Day {} ({}): {}", day, answers_name, er))?;
+        }
+    }
+
+    println!("{} passed, {} failed, {} without a recorded answer", passed, failed, unrecorded);
+    match failed {
+        0 => Ok(()),
+        _ => Err(String::from("Verification failed"))
+    }
+}
+
+fn main() {
+    let result = cli::parse_args(std::env::args().skip(1))
+        .and_then(
+            |command| match command {
+                Command::Run(options) => run(options),
+                Command::Generate(options) => generate(options),
+                Command::Bench(options) => bench(options),
+                Command::Verify(options) => verify(options),
+                Command::Help => {
+                    println!("{}", cli::USAGE);
+                    Ok(())
+                }
+            }
+        );
+    if let Err(er) = result {
+        eprintln!("{}", er);
+        std::process::exit(1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,149 @@
+use common::{Json, Puzzle};
+
+use crate::cli::Parameters;
+
+pub fn parts(day: u8) -> &'static [u8] {
+    match day {
+        25 => &[1],
+        _ => &[1, 2]
+    }
+}
+
+pub fn default_input(day: u8) -> String {
+    format!("./day{}/resources/input.txt", day)
+}
+
+/// Expected answers for the default input
+pub fn default_answers(day: u8) -> String {
+    format!("./day{}/resources/answers.txt", day)
+}
+
+/// Answer as printed and recorded, text answers as they are rather than quoted
+pub fn answer_text(answer: Json) -> String {
+    match answer {
+        Json::String(answer) => answer,
+        answer => answer.to_string()
+    }
+}
+
+fn solve_puzzle<P: Puzzle>(part: u8, content: &str) -> common::Result<Json> {
+    let input = P::parse(content)?;
+    match part {
+        1 => P::part1(&input).map(Into::into),
+        _ => P::part2(&input).map(Into::into)
+    }
+}
+
+fn describe_puzzle<P: Puzzle>(content: &str) -> common::Result<(Json, Json)> {
+    let input = P::parse(content)?;
+    Ok((P::summary(&input), P::artefacts(&input)?))
+}
+
+/// Summary of the day's input and its artefacts, the artefacts use the default day options except for
+/// day 3's groups, which are as large as `solve` makes them
+pub fn describe(day: u8, content: &str, params: &Parameters) -> common::Result<(Json, Json)> {
+    match day {
+        1 => describe_puzzle::<day1::Day1>(content),
+        2 => describe_puzzle::<day2::Day2>(content),
+        3 => {
+            let rucksacks = day3::Day3::parse(content)?;
+            let size = params.group_size.unwrap_or(3);
+            Ok((day3::summary(&rucksacks, size), day3::artefacts(&rucksacks, size)?))
+        },
+        4 => describe_puzzle::<day4::Day4>(content),
+        5 => describe_puzzle::<day5::Day5>(content),
+        6 => describe_puzzle::<day6::Day6>(content),
+        7 => describe_puzzle::<day7::Day7>(content),
+        8 => describe_puzzle::<day8::Day8>(content),
+        9 => describe_puzzle::<day9::Day9>(content),
+        10 => describe_puzzle::<day10::Day10>(content),
+        11 => describe_puzzle::<day11::Day11>(content),
+        12 => describe_puzzle::<day12::Day12>(content),
+        13 => describe_puzzle::<day13::Day13>(content),
+        14 => describe_puzzle::<day14::Day14>(content),
+        15 => describe_puzzle::<day15::Day15>(content),
+        16 => describe_puzzle::<day16::Day16>(content),
+        17 => describe_puzzle::<day17::Day17>(content),
+        18 => describe_puzzle::<day18::Day18>(content),
+        19 => describe_puzzle::<day19::Day19>(content),
+        20 => describe_puzzle::<day20::Day20>(content),
+        21 => describe_puzzle::<day21::Day21>(content),
+        22 => describe_puzzle::<day22::Day22>(content),
+        23 => describe_puzzle::<day23::Day23>(content),
+        24 => describe_puzzle::<day24::Day24>(content),
+        _ => describe_puzzle::<day25::Day25>(content)
+    }
+}
+
+pub fn solve(day: u8, part: u8, content: &str, params: &Parameters) -> common::Result<Json> {
+    let answer = match (day, part) {
+        (1, _) => solve_puzzle::<day1::Day1>(part, content)?,
+        (2, _) => solve_puzzle::<day2::Day2>(part, content)?,
+        (3, 1) => solve_puzzle::<day3::Day3>(part, content)?,
+        (3, 2) => day3::badge_priorities(&day3::Day3::parse(content)?, params.group_size.unwrap_or(3))?.into(),
+        (4, _) => solve_puzzle::<day4::Day4>(part, content)?,
+        (5, _) => solve_puzzle::<day5::Day5>(part, content)?,
+        (6, _) => solve_puzzle::<day6::Day6>(part, content)?,
+        (7, 1) => {
+            day7::directories_with_at_most_recursive(
+                &day7::Day7::parse(content)?,
+                params.at_most.unwrap_or(100_000)
+            ).into()
+        },
+        (7, 2) => {
+            day7::select_directory_until_available(
+                &day7::Day7::parse(content)?,
+                params.total_size.unwrap_or(70_000_000),
+                params.needed.unwrap_or(30_000_000)
+            )?.into()
+        },
+        (8, _) => solve_puzzle::<day8::Day8>(part, content)?,
+        (9, _) => solve_puzzle::<day9::Day9>(part, content)?,
+        (10, _) => solve_puzzle::<day10::Day10>(part, content)?,
+        (11, 1) => {
+            day11::monkey_bussiness(
+                &day11::Day11::parse(content)?,
+                params.round_limit.unwrap_or(20),
+                params.relief.unwrap_or(true)
+            ).into()
+        },
+        (11, 2) => {
+            day11::monkey_bussiness(
+                &day11::Day11::parse(content)?,
+                params.round_limit.unwrap_or(10_000),
+                params.relief.unwrap_or(false)
+            ).into()
+        },
+        (12, _) => solve_puzzle::<day12::Day12>(part, content)?,
+        (13, _) => solve_puzzle::<day13::Day13>(part, content)?,
+        (14, _) => solve_puzzle::<day14::Day14>(part, content)?,
+        (15, 1) => day15::probe(&day15::Day15::parse(content)?, params.depth.unwrap_or(2_000_000))?.into(),
+        (15, 2) => day15::tune_frequency(&day15::Day15::parse(content)?, params.bound.unwrap_or(4_000_000))?.into(),
+        (16, _) => solve_puzzle::<day16::Day16>(part, content)?,
+        (17, 1) => day17::falling_rocks(&day17::Day17::parse(content)?, params.stop_at.unwrap_or(2022))?.into(),
+        (17, 2) => day17::falling_rocks(&day17::Day17::parse(content)?, params.stop_at.unwrap_or(1_000_000_000_000))?.into(),
+        (18, _) => solve_puzzle::<day18::Day18>(part, content)?,
+        (19, _) => solve_puzzle::<day19::Day19>(part, content)?,
+        (20, 1) => {
+            day20::decrypt(
+                &day20::Day20::parse(content)?,
+                params.rounds.unwrap_or(1),
+                params.key.unwrap_or(1)
+            )?.into()
+        },
+        (20, 2) => {
+            day20::decrypt(
+                &day20::Day20::parse(content)?,
+                params.rounds.unwrap_or(10),
+                params.key.unwrap_or(811_589_153)
+            )?.into()
+        },
+        (21, _) => solve_puzzle::<day21::Day21>(part, content)?,
+        (22, _) => solve_puzzle::<day22::Day22>(part, content)?,
+        (23, _) => solve_puzzle::<day23::Day23>(part, content)?,
+        (24, _) => solve_puzzle::<day24::Day24>(part, content)?,
+        (25, 1) => solve_puzzle::<day25::Day25>(part, content)?,
+        _ => return Err(common::Error::invalid_state(format!("Day {} has no part {}", day, part)))
+    };
+    Ok(answer)
+}
\ No newline at end of file